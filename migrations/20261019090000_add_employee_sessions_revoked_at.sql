@@ -0,0 +1,2 @@
+-- Migration: Add sessions revocation timestamp to employees
+ALTER TABLE public."employees" ADD COLUMN IF NOT EXISTS sessions_revoked_at TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,27 @@
+-- Migration: Create employee suspensions table
+CREATE TABLE IF NOT EXISTS public."employee_suspensions" (
+    pk_employee_suspension_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_employee_id UUID NOT NULL,
+    fk_suspending_employee_id UUID NOT NULL,
+    reason VARCHAR(1000) NOT NULL,
+    start_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    end_at TIMESTAMP WITH TIME ZONE,
+    lifted_at TIMESTAMP WITH TIME ZONE,
+    fk_lifting_employee_id UUID,
+    lift_reason VARCHAR(1000),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT employee_suspension_period_check CHECK (end_at IS NULL OR end_at > start_at),
+
+    CONSTRAINT fk_employee_id
+    FOREIGN KEY (fk_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_suspending_employee_id
+    FOREIGN KEY (fk_suspending_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_lifting_employee_id
+    FOREIGN KEY (fk_lifting_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+CREATE INDEX IF NOT EXISTS employee_suspensions_fk_employee_id_idx ON public."employee_suspensions" (fk_employee_id);
@@ -0,0 +1,10 @@
+-- Migration: Add revocation informations to employee accreditations and permissions invalidation to employees
+ALTER TABLE public."employee_accreditation_authorizations"
+    ADD COLUMN IF NOT EXISTS revoked_at TIMESTAMP WITH TIME ZONE,
+    ADD COLUMN IF NOT EXISTS fk_revoking_employee_id UUID,
+    ADD COLUMN IF NOT EXISTS revocation_reason VARCHAR(1000),
+    ADD CONSTRAINT fk_revoking_employee_id
+    FOREIGN KEY (fk_revoking_employee_id)
+    REFERENCES employees(pk_employee_id);
+
+ALTER TABLE public."employees" ADD COLUMN IF NOT EXISTS permissions_updated_at TIMESTAMP WITH TIME ZONE;
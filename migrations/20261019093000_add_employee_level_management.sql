@@ -0,0 +1,7 @@
+-- Migration: Allow employee levels to be soft deleted and audited
+ALTER TABLE public."employee_levels" ADD COLUMN IF NOT EXISTS deleted_at TIMESTAMP WITH TIME ZONE;
+
+ALTER TYPE "EntityType" ADD VALUE IF NOT EXISTS 'EMPLOYEE_LEVEL';
+
+-- entities such as levels are not identified by a UUID, their identifier is kept in the description
+ALTER TABLE public."employee_action_histories" ALTER COLUMN fk_entity_id DROP NOT NULL;
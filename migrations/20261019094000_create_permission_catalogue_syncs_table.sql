@@ -0,0 +1,8 @@
+-- Migration: Create permission catalogue syncs table
+CREATE TABLE IF NOT EXISTS public."permission_catalogue_syncs" (
+    pk_permission_catalogue_sync_id SERIAL PRIMARY KEY,
+    catalogue_version INTEGER NOT NULL,
+    applied_changes JSONB NOT NULL,
+    reported_differences JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
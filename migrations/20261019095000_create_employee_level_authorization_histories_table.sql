@@ -0,0 +1,24 @@
+-- Migration: Create employee level authorization histories table
+-- Keeps the periods during which an authorization type was granted to a level,
+-- used to compute the effective permissions of an employee at a past date
+CREATE TABLE IF NOT EXISTS public."employee_level_authorization_histories" (
+    fk_employee_level_id INTEGER NOT NULL,
+    fk_employee_authorization_type_id INTEGER NOT NULL,
+    granted_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+
+    CONSTRAINT employee_level_authorization_history_pkey PRIMARY KEY (fk_employee_level_id, fk_employee_authorization_type_id, granted_at),
+
+    CONSTRAINT fk_employee_level_id
+    FOREIGN KEY (fk_employee_level_id)
+    REFERENCES employee_levels(pk_employee_level_id),
+    CONSTRAINT fk_employee_authorization_type_id
+    FOREIGN KEY (fk_employee_authorization_type_id)
+    REFERENCES employee_authorization_types(pk_employee_authorization_type_id)
+);
+
+-- The existing grants have no known start, they are considered granted since ever
+INSERT INTO employee_level_authorization_histories (fk_employee_level_id, fk_employee_authorization_type_id, granted_at)
+SELECT fk_employee_level_id, fk_employee_authorization_type_id, '-infinity'
+FROM link_employee_authorization
+ON CONFLICT DO NOTHING;
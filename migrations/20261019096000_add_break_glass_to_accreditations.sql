@@ -0,0 +1,4 @@
+-- Migration: Add break-glass flag and justification to employee accreditations
+ALTER TABLE public."employee_accreditation_authorizations"
+    ADD COLUMN IF NOT EXISTS is_break_glass BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS justification VARCHAR(1000);
@@ -0,0 +1,31 @@
+-- Migration: Create employee accreditation requests table
+CREATE TABLE IF NOT EXISTS public."employee_accreditation_requests" (
+    pk_employee_accreditation_request_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_requester_employee_id UUID NOT NULL,
+    fk_employee_level_id INTEGER NOT NULL,
+    start_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    end_at TIMESTAMP WITH TIME ZONE,
+    justification VARCHAR(1000) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    fk_reviewing_employee_id UUID,
+    reviewed_at TIMESTAMP WITH TIME ZONE,
+    review_comment VARCHAR(1000),
+    accreditation_created_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT employee_accreditation_request_status_check CHECK (status IN ('PENDING', 'APPROVED', 'REJECTED', 'CANCELLED')),
+    CONSTRAINT employee_accreditation_request_period_check CHECK (end_at IS NULL OR end_at > start_at),
+
+    CONSTRAINT fk_requester_employee_id
+    FOREIGN KEY (fk_requester_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_employee_level_id
+    FOREIGN KEY (fk_employee_level_id)
+    REFERENCES employee_levels(pk_employee_level_id),
+    CONSTRAINT fk_reviewing_employee_id
+    FOREIGN KEY (fk_reviewing_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+CREATE INDEX IF NOT EXISTS employee_accreditation_requests_fk_requester_employee_id_idx ON public."employee_accreditation_requests" (fk_requester_employee_id);
+CREATE INDEX IF NOT EXISTS employee_accreditation_requests_status_idx ON public."employee_accreditation_requests" (status);
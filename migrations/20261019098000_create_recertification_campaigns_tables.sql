@@ -0,0 +1,52 @@
+-- Migration: Create recertification campaigns and campaign items tables
+CREATE TABLE IF NOT EXISTS public."recertification_campaigns" (
+    pk_recertification_campaign_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL,
+    fk_creating_employee_id UUID NOT NULL,
+    due_at TIMESTAMP WITH TIME ZONE,
+    end_unreviewed_on_close BOOLEAN NOT NULL DEFAULT TRUE,
+    closed_at TIMESTAMP WITH TIME ZONE,
+    fk_closing_employee_id UUID,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT fk_creating_employee_id
+    FOREIGN KEY (fk_creating_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_closing_employee_id
+    FOREIGN KEY (fk_closing_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+-- One row per accreditation active when the campaign was opened
+CREATE TABLE IF NOT EXISTS public."recertification_campaign_items" (
+    pk_recertification_campaign_item_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_recertification_campaign_id UUID NOT NULL,
+    fk_recipient_employee_id UUID NOT NULL,
+    accreditation_created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    fk_employee_level_id INTEGER NOT NULL,
+    accreditation_start_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    accreditation_end_at TIMESTAMP WITH TIME ZONE,
+    fk_reviewer_employee_id UUID,
+    decision VARCHAR(20),
+    decided_at TIMESTAMP WITH TIME ZONE,
+    review_comment VARCHAR(1000),
+
+    CONSTRAINT recertification_campaign_item_decision_check CHECK (decision IN ('KEEP', 'REVOKE', 'EXPIRED', 'ENDED')),
+    CONSTRAINT recertification_campaign_item_accreditation_unique UNIQUE (fk_recertification_campaign_id, fk_recipient_employee_id, accreditation_created_at),
+
+    CONSTRAINT fk_recertification_campaign_id
+    FOREIGN KEY (fk_recertification_campaign_id)
+    REFERENCES recertification_campaigns(pk_recertification_campaign_id),
+    CONSTRAINT fk_accreditation
+    FOREIGN KEY (fk_recipient_employee_id, accreditation_created_at)
+    REFERENCES employee_accreditation_authorizations(fk_recipient_employee_id, created_at),
+    CONSTRAINT fk_employee_level_id
+    FOREIGN KEY (fk_employee_level_id)
+    REFERENCES employee_levels(pk_employee_level_id),
+    CONSTRAINT fk_reviewer_employee_id
+    FOREIGN KEY (fk_reviewer_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+CREATE INDEX IF NOT EXISTS recertification_campaign_items_fk_campaign_id_idx ON public."recertification_campaign_items" (fk_recertification_campaign_id);
+CREATE INDEX IF NOT EXISTS recertification_campaign_items_fk_reviewer_employee_id_idx ON public."recertification_campaign_items" (fk_reviewer_employee_id);
@@ -0,0 +1,6 @@
+-- Migration: Create the system employee
+-- The background tasks record their actions under this employee. It is deactivated and
+-- its password hash matches no password, so nobody can log in with it.
+INSERT INTO public."employees" (pk_employee_id, firstname, lastname, personal_email, login_password_hash, professional_email, professional_email_password, deactivated_at)
+VALUES ('00000000-0000-0000-0000-000000000001', 'System', 'Plannify', 'system@plannify.invalid', '!', 'system@plannify.invalid', '!', NOW())
+ON CONFLICT (pk_employee_id) DO NOTHING;
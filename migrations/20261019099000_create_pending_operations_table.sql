@@ -0,0 +1,34 @@
+-- Migration: Create pending operations table (four-eyes approval)
+CREATE TABLE IF NOT EXISTS public."pending_operations" (
+    pk_pending_operation_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    operation_type VARCHAR(50) NOT NULL,
+    payload JSONB NOT NULL,
+    fk_employee_authorization_type_id INTEGER NOT NULL,
+    fk_requesting_employee_id UUID NOT NULL,
+    justification VARCHAR(1000),
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    fk_reviewing_employee_id UUID,
+    reviewed_at TIMESTAMP WITH TIME ZONE,
+    review_comment VARCHAR(1000),
+    executed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT pending_operation_type_check CHECK (operation_type IN ('DEACTIVATE_DRIVER', 'GRANT_ADMIN_ACCREDITATION', 'UPDATE_ADMIN_ACCREDITATION', 'REVEAL_PROFESSIONAL_EMAIL_PASSWORD')),
+    CONSTRAINT pending_operation_status_check CHECK (status IN ('PENDING', 'APPROVED', 'EXECUTED', 'REJECTED', 'CANCELLED')),
+
+    CONSTRAINT fk_employee_authorization_type_id
+    FOREIGN KEY (fk_employee_authorization_type_id)
+    REFERENCES employee_authorization_types(pk_employee_authorization_type_id),
+    CONSTRAINT fk_requesting_employee_id
+    FOREIGN KEY (fk_requesting_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_reviewing_employee_id
+    FOREIGN KEY (fk_reviewing_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+CREATE INDEX IF NOT EXISTS pending_operations_fk_requesting_employee_id_idx ON public."pending_operations" (fk_requesting_employee_id);
+CREATE INDEX IF NOT EXISTS pending_operations_status_idx ON public."pending_operations" (status);
+
+-- the same operation cannot wait twice for an approval or for its execution
+CREATE UNIQUE INDEX IF NOT EXISTS pending_operations_waiting_idx ON public."pending_operations" (operation_type, payload) WHERE status IN ('PENDING', 'APPROVED');
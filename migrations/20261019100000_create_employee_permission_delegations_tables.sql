@@ -0,0 +1,43 @@
+-- Migration: Create employee permission delegations tables
+CREATE TABLE IF NOT EXISTS public."employee_permission_delegations" (
+    pk_employee_permission_delegation_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_delegator_employee_id UUID NOT NULL,
+    fk_delegate_employee_id UUID NOT NULL,
+    start_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    end_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    reason VARCHAR(1000),
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    fk_revoking_employee_id UUID,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT employee_permission_delegation_period_check CHECK (end_at > start_at),
+    CONSTRAINT employee_permission_delegation_self_check CHECK (fk_delegator_employee_id != fk_delegate_employee_id),
+
+    CONSTRAINT fk_delegator_employee_id
+    FOREIGN KEY (fk_delegator_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_delegate_employee_id
+    FOREIGN KEY (fk_delegate_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_revoking_employee_id
+    FOREIGN KEY (fk_revoking_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+CREATE TABLE IF NOT EXISTS public."employee_permission_delegation_types" (
+    fk_employee_permission_delegation_id UUID NOT NULL,
+    fk_employee_authorization_type_id INTEGER NOT NULL,
+
+    CONSTRAINT employee_permission_delegation_type_pkey PRIMARY KEY (fk_employee_permission_delegation_id, fk_employee_authorization_type_id),
+
+    CONSTRAINT fk_employee_permission_delegation_id
+    FOREIGN KEY (fk_employee_permission_delegation_id)
+    REFERENCES employee_permission_delegations(pk_employee_permission_delegation_id)
+    ON DELETE CASCADE,
+    CONSTRAINT fk_employee_authorization_type_id
+    FOREIGN KEY (fk_employee_authorization_type_id)
+    REFERENCES employee_authorization_types(pk_employee_authorization_type_id)
+);
+
+CREATE INDEX IF NOT EXISTS employee_permission_delegations_fk_delegator_employee_id_idx ON public."employee_permission_delegations" (fk_delegator_employee_id);
+CREATE INDEX IF NOT EXISTS employee_permission_delegations_fk_delegate_employee_id_idx ON public."employee_permission_delegations" (fk_delegate_employee_id);
@@ -0,0 +1,76 @@
+-- Migration: Create employee teams tables, teams hold accreditations inherited by their members
+CREATE TABLE IF NOT EXISTS public."employee_teams" (
+    pk_employee_team_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name VARCHAR(255) NOT NULL,
+    description VARCHAR(1000),
+    fk_creating_employee_id UUID NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    deleted_at TIMESTAMP WITH TIME ZONE,
+
+    CONSTRAINT fk_creating_employee_id
+    FOREIGN KEY (fk_creating_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+-- the name of a deleted team can be reused
+CREATE UNIQUE INDEX IF NOT EXISTS employee_teams_name_idx ON public."employee_teams" (LOWER(name)) WHERE deleted_at IS NULL;
+
+CREATE TABLE IF NOT EXISTS public."employee_team_memberships" (
+    pk_employee_team_membership_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_employee_team_id UUID NOT NULL,
+    fk_employee_id UUID NOT NULL,
+    joined_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    left_at TIMESTAMP WITH TIME ZONE,
+    fk_adding_employee_id UUID NOT NULL,
+    fk_removing_employee_id UUID,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT employee_team_membership_period_check CHECK (left_at IS NULL OR left_at >= joined_at),
+
+    CONSTRAINT fk_employee_team_id
+    FOREIGN KEY (fk_employee_team_id)
+    REFERENCES employee_teams(pk_employee_team_id),
+    CONSTRAINT fk_employee_id
+    FOREIGN KEY (fk_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_adding_employee_id
+    FOREIGN KEY (fk_adding_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_removing_employee_id
+    FOREIGN KEY (fk_removing_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+CREATE TABLE IF NOT EXISTS public."employee_team_accreditations" (
+    pk_employee_team_accreditation_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_employee_team_id UUID NOT NULL,
+    fk_employee_level_id INTEGER NOT NULL,
+    fk_authorizing_employee_id UUID NOT NULL,
+    start_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    end_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    fk_revoking_employee_id UUID,
+    revocation_reason VARCHAR(1000),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT employee_team_accreditation_period_check CHECK (end_at IS NULL OR end_at >= start_at),
+
+    CONSTRAINT fk_employee_team_id
+    FOREIGN KEY (fk_employee_team_id)
+    REFERENCES employee_teams(pk_employee_team_id),
+    CONSTRAINT fk_employee_level_id
+    FOREIGN KEY (fk_employee_level_id)
+    REFERENCES employee_levels(pk_employee_level_id),
+    CONSTRAINT fk_authorizing_employee_id
+    FOREIGN KEY (fk_authorizing_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_revoking_employee_id
+    FOREIGN KEY (fk_revoking_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+CREATE INDEX IF NOT EXISTS employee_team_memberships_fk_employee_team_id_idx ON public."employee_team_memberships" (fk_employee_team_id);
+CREATE INDEX IF NOT EXISTS employee_team_memberships_fk_employee_id_idx ON public."employee_team_memberships" (fk_employee_id);
+CREATE INDEX IF NOT EXISTS employee_team_accreditations_fk_employee_team_id_idx ON public."employee_team_accreditations" (fk_employee_team_id);
+
+ALTER TYPE "EntityType" ADD VALUE IF NOT EXISTS 'EMPLOYEE_TEAM';
@@ -0,0 +1,24 @@
+-- Migration: Create driver workdays table
+CREATE TABLE IF NOT EXISTS public."driver_workdays" (
+    pk_driver_workday_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_driver_id UUID NOT NULL,
+    workday_date DATE NOT NULL,
+    start_time TIME NOT NULL,
+    end_time TIME NOT NULL,
+    -- the workday ends on the day after `workday_date`
+    is_overnight BOOLEAN NOT NULL DEFAULT false,
+    break_minutes INTEGER NOT NULL DEFAULT 0,
+    rest_minutes INTEGER NOT NULL DEFAULT 0,
+    notes VARCHAR(1000),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+
+    CONSTRAINT driver_workday_time_check CHECK (is_overnight OR end_time > start_time),
+    CONSTRAINT driver_workday_break_minutes_check CHECK (break_minutes >= 0),
+    CONSTRAINT driver_workday_rest_minutes_check CHECK (rest_minutes >= 0),
+    CONSTRAINT driver_workday_date_unique UNIQUE (fk_driver_id, workday_date),
+
+    CONSTRAINT fk_driver_id
+    FOREIGN KEY (fk_driver_id)
+    REFERENCES drivers(pk_driver_id)
+);
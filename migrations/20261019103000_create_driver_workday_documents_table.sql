@@ -0,0 +1,22 @@
+-- Migration: Create driver workday documents table, the files themselves live in the document storage
+CREATE TABLE IF NOT EXISTS public."driver_workday_documents" (
+    pk_driver_workday_document_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_driver_workday_id UUID NOT NULL,
+    file_name VARCHAR(255) NOT NULL,
+    content_type VARCHAR(255) NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    storage_key VARCHAR(1024) NOT NULL UNIQUE,
+    fk_uploading_employee_id UUID NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT driver_workday_document_size_check CHECK (size_bytes > 0),
+
+    CONSTRAINT fk_driver_workday_id
+    FOREIGN KEY (fk_driver_workday_id)
+    REFERENCES driver_workdays(pk_driver_workday_id),
+    CONSTRAINT fk_uploading_employee_id
+    FOREIGN KEY (fk_uploading_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+CREATE INDEX IF NOT EXISTS driver_workday_documents_fk_driver_workday_id_idx ON public."driver_workday_documents" (fk_driver_workday_id);
@@ -0,0 +1,29 @@
+-- Migration: Create driver mails table, the outbox of the mails sent to the drivers
+CREATE TABLE IF NOT EXISTS public."driver_mails" (
+    pk_driver_mail_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_driver_id UUID NOT NULL,
+    template VARCHAR(50) NOT NULL,
+    subject VARCHAR(255) NOT NULL,
+    body TEXT NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    -- address the mail was delivered to, the driver email can change afterwards
+    recipient_email VARCHAR(255),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error VARCHAR(1000),
+    fk_sending_employee_id UUID NOT NULL,
+    sent_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+
+    CONSTRAINT driver_mail_status_check CHECK (status IN ('PENDING', 'SENDING', 'SENT', 'FAILED', 'SKIPPED')),
+
+    CONSTRAINT fk_driver_id
+    FOREIGN KEY (fk_driver_id)
+    REFERENCES drivers(pk_driver_id),
+    CONSTRAINT fk_sending_employee_id
+    FOREIGN KEY (fk_sending_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+CREATE INDEX IF NOT EXISTS driver_mails_fk_driver_id_idx ON public."driver_mails" (fk_driver_id);
+CREATE INDEX IF NOT EXISTS driver_mails_pending_idx ON public."driver_mails" (created_at) WHERE status = 'PENDING';
@@ -0,0 +1,29 @@
+-- Migration: Create driver suspensions table
+CREATE TABLE IF NOT EXISTS public."driver_suspensions" (
+    pk_driver_suspension_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_driver_id UUID NOT NULL,
+    fk_suspending_employee_id UUID NOT NULL,
+    reason VARCHAR(1000) NOT NULL,
+    start_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    end_at TIMESTAMP WITH TIME ZONE,
+    lifted_at TIMESTAMP WITH TIME ZONE,
+    -- no lifting employee when the suspension was lifted automatically at its end
+    fk_lifting_employee_id UUID,
+    lift_reason VARCHAR(1000),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT driver_suspension_period_check CHECK (end_at IS NULL OR end_at > start_at),
+
+    CONSTRAINT fk_driver_id
+    FOREIGN KEY (fk_driver_id)
+    REFERENCES drivers(pk_driver_id),
+    CONSTRAINT fk_suspending_employee_id
+    FOREIGN KEY (fk_suspending_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_lifting_employee_id
+    FOREIGN KEY (fk_lifting_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+CREATE INDEX IF NOT EXISTS driver_suspensions_fk_driver_id_idx ON public."driver_suspensions" (fk_driver_id);
+CREATE INDEX IF NOT EXISTS driver_suspensions_due_idx ON public."driver_suspensions" (end_at) WHERE lifted_at IS NULL;
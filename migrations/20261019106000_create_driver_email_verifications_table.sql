@@ -0,0 +1,37 @@
+-- Migration: Create driver email verifications table
+CREATE TABLE IF NOT EXISTS public."driver_email_verifications" (
+    pk_driver_email_verification_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_driver_id UUID NOT NULL,
+    -- address being verified, the verification fails if the driver email changes meanwhile
+    email VARCHAR(255) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    fk_requesting_employee_id UUID NOT NULL,
+    sent_count INTEGER NOT NULL DEFAULT 1,
+    last_sent_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    confirmed_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    fk_revoking_employee_id UUID,
+    -- last mail sent, its link is only rendered when sending
+    fk_driver_mail_id UUID,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT driver_email_verification_status_check CHECK (status IN ('PENDING', 'CONFIRMED', 'REVOKED', 'EXPIRED')),
+
+    CONSTRAINT fk_driver_id
+    FOREIGN KEY (fk_driver_id)
+    REFERENCES drivers(pk_driver_id),
+    CONSTRAINT fk_requesting_employee_id
+    FOREIGN KEY (fk_requesting_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_revoking_employee_id
+    FOREIGN KEY (fk_revoking_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_driver_mail_id
+    FOREIGN KEY (fk_driver_mail_id)
+    REFERENCES driver_mails(pk_driver_mail_id)
+    ON DELETE SET NULL
+);
+
+-- a driver has one pending verification at most
+CREATE UNIQUE INDEX IF NOT EXISTS driver_email_verifications_pending_idx ON public."driver_email_verifications" (fk_driver_id) WHERE status = 'PENDING';
@@ -0,0 +1,57 @@
+-- Migration: Create driver erasures table, the tombstone of the drivers whose personal data was erased
+CREATE TABLE IF NOT EXISTS public."driver_erasures" (
+    pk_driver_erasure_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_driver_id UUID NOT NULL,
+    fk_pending_operation_id UUID NOT NULL,
+    fk_requesting_employee_id UUID NOT NULL,
+    fk_approving_employee_id UUID NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'SCHEDULED',
+    -- end of the grace period, the erasure can be cancelled until then
+    scheduled_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    cancelled_at TIMESTAMP WITH TIME ZONE,
+    fk_cancelling_employee_id UUID,
+    cancel_reason VARCHAR(1000),
+    completed_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT driver_erasure_status_check CHECK (status IN ('SCHEDULED', 'CANCELLED', 'COMPLETED')),
+
+    CONSTRAINT fk_driver_id
+    FOREIGN KEY (fk_driver_id)
+    REFERENCES drivers(pk_driver_id),
+    CONSTRAINT fk_pending_operation_id
+    FOREIGN KEY (fk_pending_operation_id)
+    REFERENCES pending_operations(pk_pending_operation_id),
+    CONSTRAINT fk_requesting_employee_id
+    FOREIGN KEY (fk_requesting_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_approving_employee_id
+    FOREIGN KEY (fk_approving_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_cancelling_employee_id
+    FOREIGN KEY (fk_cancelling_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+-- a driver is erased once at most
+CREATE UNIQUE INDEX IF NOT EXISTS driver_erasures_driver_idx ON public."driver_erasures" (fk_driver_id) WHERE status IN ('SCHEDULED', 'COMPLETED');
+CREATE INDEX IF NOT EXISTS driver_erasures_due_idx ON public."driver_erasures" (scheduled_at) WHERE status = 'SCHEDULED';
+
+-- the stored files of the erased drivers left to delete
+CREATE TABLE IF NOT EXISTS public."driver_erasure_purges" (
+    pk_driver_erasure_purge_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_driver_erasure_id UUID NOT NULL,
+    storage_key VARCHAR(1024) NOT NULL UNIQUE,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+
+    CONSTRAINT fk_driver_erasure_id
+    FOREIGN KEY (fk_driver_erasure_id)
+    REFERENCES driver_erasures(pk_driver_erasure_id)
+);
+
+ALTER TABLE public."pending_operations" DROP CONSTRAINT IF EXISTS pending_operation_type_check;
+ALTER TABLE public."pending_operations" ADD CONSTRAINT pending_operation_type_check
+    CHECK (operation_type IN ('DEACTIVATE_DRIVER', 'GRANT_ADMIN_ACCREDITATION', 'UPDATE_ADMIN_ACCREDITATION', 'REVEAL_PROFESSIONAL_EMAIL_PASSWORD', 'ERASE_DRIVER'));
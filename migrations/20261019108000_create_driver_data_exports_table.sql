@@ -0,0 +1,30 @@
+-- Migration: Create driver data exports table, the archives themselves live in the document storage
+CREATE TABLE IF NOT EXISTS public."driver_data_exports" (
+    pk_driver_data_export_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_driver_id UUID NOT NULL,
+    fk_requesting_employee_id UUID NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    storage_key VARCHAR(1024) UNIQUE,
+    size_bytes BIGINT,
+    last_error TEXT,
+    started_at TIMESTAMP WITH TIME ZONE,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    -- the archive is deleted from the storage once expired
+    expires_at TIMESTAMP WITH TIME ZONE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT driver_data_export_status_check CHECK (status IN ('PENDING', 'RUNNING', 'COMPLETED', 'FAILED', 'EXPIRED')),
+
+    CONSTRAINT fk_driver_id
+    FOREIGN KEY (fk_driver_id)
+    REFERENCES drivers(pk_driver_id),
+    CONSTRAINT fk_requesting_employee_id
+    FOREIGN KEY (fk_requesting_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+CREATE INDEX IF NOT EXISTS driver_data_exports_fk_driver_id_idx ON public."driver_data_exports" (fk_driver_id);
+CREATE INDEX IF NOT EXISTS driver_data_exports_queue_idx ON public."driver_data_exports" (created_at) WHERE status IN ('PENDING', 'RUNNING');
+
+-- a driver has one export in progress at most
+CREATE UNIQUE INDEX IF NOT EXISTS driver_data_exports_in_progress_idx ON public."driver_data_exports" (fk_driver_id) WHERE status IN ('PENDING', 'RUNNING');
@@ -0,0 +1,34 @@
+-- Migration: Upgrade drivers.rest_json to the versioned rest settings (version 2)
+
+-- JSON null means no settings
+UPDATE public."drivers" SET rest_json = NULL WHERE rest_json = 'null'::jsonb;
+
+-- the unversioned shape expressed the durations in hours, the missing fields take their default value
+UPDATE public."drivers" SET rest_json = jsonb_strip_nulls(jsonb_build_object(
+    'version', 2,
+    'daily_rest_minutes', CASE WHEN jsonb_typeof(rest_json->'daily_rest_hours') = 'number' THEN ROUND((rest_json->>'daily_rest_hours')::NUMERIC * 60) END,
+    'weekly_rest_minutes', CASE WHEN jsonb_typeof(rest_json->'weekly_rest_hours') = 'number' THEN ROUND((rest_json->>'weekly_rest_hours')::NUMERIC * 60) END,
+    'break_after_minutes', CASE WHEN jsonb_typeof(rest_json->'break_after_hours') = 'number' THEN ROUND((rest_json->>'break_after_hours')::NUMERIC * 60) END,
+    'break_minutes', rest_json->'break_minutes',
+    'rest_days', rest_json->'rest_days',
+    'break_reminders', rest_json->'break_reminders'
+))
+WHERE jsonb_typeof(rest_json) = 'object' AND NOT rest_json ? 'version';
+
+-- the API refuses to store or patch settings out of the validated ranges, the durations are rounded and brought back
+-- within them and the repeated rest days removed, e.g. a daily rest of 8 hours becomes 540 minutes
+UPDATE public."drivers" SET rest_json = rest_json
+    || CASE WHEN jsonb_typeof(rest_json->'daily_rest_minutes') = 'number'
+        THEN jsonb_build_object('daily_rest_minutes', LEAST(GREATEST(ROUND((rest_json->>'daily_rest_minutes')::NUMERIC), 540), 1440)) ELSE '{}'::jsonb END
+    || CASE WHEN jsonb_typeof(rest_json->'weekly_rest_minutes') = 'number'
+        THEN jsonb_build_object('weekly_rest_minutes', LEAST(GREATEST(ROUND((rest_json->>'weekly_rest_minutes')::NUMERIC), 1440), 10080)) ELSE '{}'::jsonb END
+    || CASE WHEN jsonb_typeof(rest_json->'break_after_minutes') = 'number'
+        THEN jsonb_build_object('break_after_minutes', LEAST(GREATEST(ROUND((rest_json->>'break_after_minutes')::NUMERIC), 60), 600)) ELSE '{}'::jsonb END
+    || CASE WHEN jsonb_typeof(rest_json->'break_minutes') = 'number'
+        THEN jsonb_build_object('break_minutes', LEAST(GREATEST(ROUND((rest_json->>'break_minutes')::NUMERIC), 15), 120)) ELSE '{}'::jsonb END
+    || CASE WHEN jsonb_typeof(rest_json->'rest_days') = 'array'
+        THEN jsonb_build_object('rest_days', (
+            SELECT COALESCE(jsonb_agg(day ORDER BY position), '[]'::jsonb)
+            FROM (SELECT day, MIN(position) AS position FROM jsonb_array_elements(rest_json->'rest_days') WITH ORDINALITY AS days(day, position) GROUP BY day) AS distinct_days
+        )) ELSE '{}'::jsonb END
+WHERE jsonb_typeof(rest_json) = 'object' AND rest_json->'version' = '2'::jsonb;
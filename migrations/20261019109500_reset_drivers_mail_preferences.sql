@@ -0,0 +1,4 @@
+-- Migration: Reset the mail preferences of the drivers
+-- The bits stored until now had no defined meaning (every driver was created with 32),
+-- they cannot be mapped to the NEWSLETTER and PROMOTION categories: the drivers have to opt in again
+UPDATE public."drivers" SET mail_preferences = 0 WHERE mail_preferences <> 0;
@@ -0,0 +1,35 @@
+-- Migration: Create driver password resets table
+CREATE TABLE IF NOT EXISTS public."driver_password_resets" (
+    pk_driver_password_reset_id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fk_driver_id UUID NOT NULL,
+    -- LINK mails a token to choose a new password, TEMPORARY_PASSWORD replaces the password until the driver changes it
+    method VARCHAR(20) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'PENDING',
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    fk_requesting_employee_id UUID NOT NULL,
+    fk_driver_mail_id UUID,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    fk_revoking_employee_id UUID,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT driver_password_reset_method_check CHECK (method IN ('LINK', 'TEMPORARY_PASSWORD')),
+    CONSTRAINT driver_password_reset_status_check CHECK (status IN ('PENDING', 'COMPLETED', 'REVOKED', 'EXPIRED')),
+
+    CONSTRAINT fk_driver_id
+    FOREIGN KEY (fk_driver_id)
+    REFERENCES drivers(pk_driver_id),
+    CONSTRAINT fk_requesting_employee_id
+    FOREIGN KEY (fk_requesting_employee_id)
+    REFERENCES employees(pk_employee_id),
+    CONSTRAINT fk_driver_mail_id
+    FOREIGN KEY (fk_driver_mail_id)
+    REFERENCES driver_mails(pk_driver_mail_id)
+    ON DELETE SET NULL,
+    CONSTRAINT fk_revoking_employee_id
+    FOREIGN KEY (fk_revoking_employee_id)
+    REFERENCES employees(pk_employee_id)
+);
+
+-- a driver has one pending reset at most, the driver application refuses to log in while a temporary password is pending
+CREATE UNIQUE INDEX IF NOT EXISTS driver_password_resets_pending_idx ON public."driver_password_resets" (fk_driver_id) WHERE status = 'PENDING';
@@ -1,118 +1,9 @@
-INSERT INTO employee_authorization_categories (pk_employee_authorization_category_id, name_code, entity_type, category_index) VALUES (1, 'DRIVER_INFORMATIONS', 'DRIVER', 1);
-INSERT INTO employee_authorization_categories (pk_employee_authorization_category_id, name_code, entity_type, category_index) VALUES (2, 'EMPLOYEE_INFORMATIONS', 'EMPLOYEE', 2);
-
-INSERT INTO employee_authorizations (pk_employee_authorization_id, fk_employee_authorization_category_id, feature_code, authorization_index) VALUES (1, 1, 'DRIVER_GLOBAL_INFORMATIONS', 1);
-INSERT INTO employee_authorizations (pk_employee_authorization_id, fk_employee_authorization_category_id, feature_code, authorization_index) VALUES (2, 1, 'DRIVER_WORKDAY_INFORMATIONS', 2);
-INSERT INTO employee_authorizations (pk_employee_authorization_id, fk_employee_authorization_category_id, feature_code, authorization_index) VALUES (3, 1, 'DRIVER_WORKDAY_DOCUMENT_INFORMATIONS', 3);
-INSERT INTO employee_authorizations (pk_employee_authorization_id, fk_employee_authorization_category_id, feature_code, authorization_index) VALUES (4, 1, 'DRIVER_MAIL_INFORMATIONS', 4);
-INSERT INTO employee_authorizations (pk_employee_authorization_id, fk_employee_authorization_category_id, feature_code, authorization_index) VALUES (5, 1, 'DRIVER_SUSPENSION_INFORMATIONS', 5);
-INSERT INTO employee_authorizations (pk_employee_authorization_id, fk_employee_authorization_category_id, feature_code, authorization_index) VALUES (6, 2, 'EMPLOYEE_GLOBAL_FULL_INFORMATIONS', 1);
-INSERT INTO employee_authorizations (pk_employee_authorization_id, fk_employee_authorization_category_id, feature_code, authorization_index) VALUES (7, 2, 'EMPLOYEE_MAIL_INFORMATIONS', 2);
-INSERT INTO employee_authorizations (pk_employee_authorization_id, fk_employee_authorization_category_id, feature_code, authorization_index) VALUES (8, 2, 'EMPLOYEE_SUSPENSION_INFORMATIONS', 3);
-INSERT INTO employee_authorizations (pk_employee_authorization_id, fk_employee_authorization_category_id, feature_code, authorization_index) VALUES (9, 2, 'EMPLOYEE_AUTHORIZATION_INFORMATIONS', 4);
-INSERT INTO employee_authorizations (pk_employee_authorization_id, fk_employee_authorization_category_id, feature_code, authorization_index) VALUES (10, 2, 'EMPLOYEE_LEVEL_INFORMATIONS', 5);
-INSERT INTO employee_authorizations (pk_employee_authorization_id, fk_employee_authorization_category_id, feature_code, authorization_index) VALUES (11, 2, 'EMPLOYEE_ACCREDITATION_INFORMATIONS', 6);
-
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (1, 1, 'R', 'Read all driver informations');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (2, 1, 'C', 'Create a new driver');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (3, 1, 'U', 'Update a driver');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (4, 1, 'D', 'Delete a driver');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (5, 2, 'R', 'Read all driver workdays');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (6, 2, 'C', 'Create a new workday for a driver');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (7, 2, 'U', 'Update a workday for a driver');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (8, 2, 'D', 'Delete a workday for a driver');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (9, 3, 'R', 'Read all driver workday documents');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (10, 3, 'C', 'Create a new workday document for a driver');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (11, 3, 'D', 'Delete a workday document for a driver');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (12, 4, 'R', 'Read all driver mails');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (13, 4, 'C', 'Send a mail to a driver');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (14, 4, 'U', 'Update a sent mail');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (15, 4, 'D', 'Delete a sent mail');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (16, 5, 'R', 'Read all driver suspensions');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (17, 5, 'C', 'Create a new driver suspension');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (18, 5, 'U', 'Update a driver suspension');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (19, 5, 'D', 'Delete a driver suspension');
-
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (20, 6, 'R', 'Read employee global informations');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (21, 6, 'C', 'Create a new employee');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (22, 6, 'U', 'Update an employee');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (23, 6, 'D', 'Delete an employee');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (24, 7, 'R', 'Read employee mail informations');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (25, 7, 'C', 'Send a mail to an employee');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (26, 7, 'U', 'Update a sent mail');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (27, 7, 'D', 'Delete a sent mail');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (28, 8, 'R', 'Read employee suspension informations');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (29, 8, 'C', 'Create a new employee suspension');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (30, 8, 'U', 'Update an employee suspension');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (31, 8, 'D', 'Delete an employee suspension');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (32, 9, 'R', 'Read employee authorizations');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (33, 10, 'R', 'Read employee levels');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (34, 11, 'R', 'Read employee accreditations');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (35, 11, 'C', 'Create a new employee accreditation');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (36, 11, 'U', 'Update an employee accreditation');
-INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description) VALUES (37, 11, 'D', 'Delete an employee accreditation');
-
-INSERT INTO employee_levels (pk_employee_level_id, level_index, level_label) VALUES (1, 1, 'ADMIN');
-INSERT INTO employee_levels (pk_employee_level_id, level_index, level_label) VALUES (2, 2, 'SUPPORT');
-
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 1);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 2);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 3);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 4);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 5);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 6);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 7);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 8);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 9);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 10);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 11);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 12);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 13);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 14);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 15);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 16);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 17);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 18);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 19);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 20);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 21);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 22);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 23);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 24);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 25);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 26);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 27);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 28);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 29);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 30);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 31);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 32);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 33);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 34);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 35);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 36);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (1, 37);
-
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 1);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 2);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 3);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 5);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 6);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 7);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 8);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 9);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 10);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 12);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 13);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 16);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 17);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 18);
-
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 20);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 32);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 33);
-INSERT INTO link_employee_authorization (fk_employee_level_id, fk_employee_authorization_type_id) VALUES (2, 34);
+-- Development data, to run once the API has started: the permission catalogue (permissions.toml)
+-- is synced at startup and creates the levels, categories, features and types with their default grants.
 
 INSERT INTO employees (pk_employee_id, firstname, lastname, gender, personal_email, login_password_hash, phone_number, professional_email, professional_email_password) VALUES ('2e6180da-b376-46df-8043-3b25d7e8be6e', 'Baptiste', 'Bronsin', 'M', 'baptiste.bronsin@outlook.com', '$2b$12$303SJbhjc5y/EouHAgoRkeq70UD3.JqzKp8b5C1ISMvr8ZcJcjPXK', null, 'baptiste.bronsin@plannify.be', 'plannify');
 
-INSERT INTO employee_accreditation_authorizations (fk_recipient_employee_id, fk_employee_level_id, fk_authorizing_employee_id, start_at, end_at) VALUES ('2e6180da-b376-46df-8043-3b25d7e8be6e', 1, null, '2025-01-01', '2026-01-01');
\ No newline at end of file
+INSERT INTO employee_accreditation_authorizations (fk_recipient_employee_id, fk_employee_level_id, fk_authorizing_employee_id, start_at, end_at)
+SELECT '2e6180da-b376-46df-8043-3b25d7e8be6e', pk_employee_level_id, null, '2025-01-01', '2026-01-01'
+FROM employee_levels
+WHERE level_label = 'ADMIN' AND deleted_at IS NULL;
@@ -11,6 +11,10 @@ pub struct Claims {
     pub authorizations: Vec<i32>,
     pub exp: i64, // expiration timestamp
     pub iat: i64, // issued at timestamp
+    /// issued at timestamp in milliseconds, compared with the permissions changes,
+    /// 0 when the token was issued before it was tracked
+    #[serde(default)]
+    pub iat_ms: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -40,6 +44,7 @@ impl Claims {
             authorizations,
             exp: exp.timestamp(),
             iat: now.timestamp(),
+            iat_ms: now.timestamp_millis(),
         }
     }
 
@@ -47,6 +52,15 @@ impl Claims {
         let now = Utc::now().timestamp();
         now > self.exp
     }
+
+    /// Issue time in milliseconds, the tokens issued before it was tracked fall back on `iat`
+    pub fn issued_at_ms(&self) -> i64 {
+        if self.iat_ms == 0 {
+            self.iat * 1000
+        } else {
+            self.iat_ms
+        }
+    }
 }
 
 impl RefreshClaims {
@@ -1,5 +1,5 @@
-use chrono::Utc;
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool};
 use bcrypt::{hash, verify, DEFAULT_COST};
 use uuid::Uuid;
 use jsonwebtoken::{encode, Header, EncodingKey};
@@ -8,6 +8,47 @@ use crate::{
     auth::models::{AuthResponse, Claims, RefreshClaims, RefreshTokenRequest}, employee::models::{Employee, EmployeeCreate, EmployeeLoginRequest}, errors::app_error::AppError
 };
 
+/// Force the employees to refresh their access tokens so that they carry their new permissions.
+///
+/// The employees they currently delegate authorizations to are refreshed as well, since the
+/// delegated authorizations follow the permissions of the delegator.
+pub async fn invalidate_employee_permissions<'e, E: PgExecutor<'e>>(executor: E, employee_ids: &[Uuid]) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+        UPDATE employees SET permissions_updated_at = NOW()
+        WHERE pk_employee_id = ANY($1)
+        OR pk_employee_id IN (
+            SELECT fk_delegate_employee_id FROM employee_permission_delegations
+            WHERE fk_delegator_employee_id = ANY($1) AND revoked_at IS NULL AND end_at > NOW()
+        )
+        "#,
+        employee_ids
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+/// Force the current and future members of a team to refresh their access tokens,
+/// e.g. after a change to the accreditations of the team.
+pub async fn invalidate_team_permissions<'e, E: PgExecutor<'e>>(executor: E, team_id: &Uuid) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+        UPDATE employees SET permissions_updated_at = NOW()
+        WHERE pk_employee_id IN (
+            SELECT fk_employee_id FROM employee_team_memberships
+            WHERE fk_employee_team_id = $1 AND (left_at IS NULL OR left_at > NOW())
+        )
+        "#,
+        team_id
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
 pub struct AuthService {
     pool: PgPool,
     jwt_secret: String,
@@ -59,8 +100,12 @@ impl AuthService {
             return Err(AppError::Validation("Invalid email or password".to_string()));
         }
         
+        // refuse the login while a suspension is in effect
+        self.ensure_not_suspended(employee.pk_employee_id).await?;
+
         // get employee permissions
         let permissions = self.get_employee_permissions(employee.pk_employee_id).await?;
+        let next_permission_change_at = self.get_next_permission_change_at(employee.pk_employee_id).await?;
         
         // update last login
         sqlx::query!(
@@ -72,7 +117,7 @@ impl AuthService {
         .await?;
         
         // generate JWT tokens
-        let access_token = self.generate_access_token(&employee, &permissions)?;
+        let access_token = self.generate_access_token(&employee, &permissions, next_permission_change_at)?;
         let refresh_token = self.generate_refresh_token(&employee)?;
         
         Ok(AuthResponse {
@@ -115,11 +160,26 @@ impl AuthService {
         .await?
         .ok_or(AppError::Validation("Employé non trouvé ou désactivé".to_string()))?;
 
+        // check if the employee sessions have been revoked since the token was issued
+        let sessions_revoked_at = sqlx::query_scalar!(
+            "SELECT sessions_revoked_at FROM employees WHERE pk_employee_id = $1",
+            employee.pk_employee_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        if sessions_revoked_at.is_some_and(|revoked_at| claims.iat <= revoked_at.timestamp()) {
+            return Err(AppError::Validation("Refresh token révoqué".to_string()));
+        }
+
+        self.ensure_not_suspended(employee.pk_employee_id).await?;
+
         // get employee permissions
         let permissions = self.get_employee_permissions(employee.pk_employee_id).await?;
+        let next_permission_change_at = self.get_next_permission_change_at(employee.pk_employee_id).await?;
         
         // generate new access token
-        let access_token = self.generate_access_token(&employee, &permissions)?;
+        let access_token = self.generate_access_token(&employee, &permissions, next_permission_change_at)?;
         let refresh_token = self.generate_refresh_token(&employee)?;
         
         Ok(AuthResponse {
@@ -132,17 +192,59 @@ impl AuthService {
     pub async fn get_employee_permissions(&self, employee_id: Uuid) -> Result<Vec<i32>, AppError> {
         let permissions = sqlx::query!(
             r#"
-            SELECT DISTINCT
-                eat.pk_employee_authorization_type_id as authorization_id
-            FROM employee_accreditation_authorizations eaa
-            JOIN employee_levels el ON eaa.fk_employee_level_id = el.pk_employee_level_id
-            JOIN link_employee_authorization lea ON el.pk_employee_level_id = lea.fk_employee_level_id
-            JOIN employee_authorization_types eat ON lea.fk_employee_authorization_type_id = eat.pk_employee_authorization_type_id
-            JOIN employee_authorizations ea ON eat.fk_employee_authorization_id = ea.pk_employee_authorization_id
-            WHERE eaa.fk_recipient_employee_id = $1
-                AND (eaa.end_at IS NULL OR eaa.end_at > NOW())
-                AND eaa.start_at <= NOW()
-            ORDER BY eat.pk_employee_authorization_type_id
+            SELECT permissions.authorization_id as "authorization_id!"
+            FROM (
+                SELECT lea.fk_employee_authorization_type_id as authorization_id
+                FROM employee_accreditation_authorizations eaa
+                JOIN link_employee_authorization lea ON eaa.fk_employee_level_id = lea.fk_employee_level_id
+                WHERE eaa.fk_recipient_employee_id = $1
+                    AND (eaa.end_at IS NULL OR eaa.end_at > NOW())
+                    AND eaa.start_at <= NOW()
+                UNION
+                SELECT lea.fk_employee_authorization_type_id
+                FROM employee_team_memberships etm
+                JOIN employee_team_accreditations eta ON eta.fk_employee_team_id = etm.fk_employee_team_id
+                JOIN link_employee_authorization lea ON eta.fk_employee_level_id = lea.fk_employee_level_id
+                WHERE etm.fk_employee_id = $1
+                    AND etm.joined_at <= NOW()
+                    AND (etm.left_at IS NULL OR etm.left_at > NOW())
+                    AND eta.start_at <= NOW()
+                    AND (eta.end_at IS NULL OR eta.end_at > NOW())
+                UNION
+                -- delegated authorizations only count while the delegator still holds them through an accreditation
+                SELECT epdt.fk_employee_authorization_type_id
+                FROM employee_permission_delegations epd
+                JOIN employee_permission_delegation_types epdt ON epdt.fk_employee_permission_delegation_id = epd.pk_employee_permission_delegation_id
+                JOIN employees delegator ON delegator.pk_employee_id = epd.fk_delegator_employee_id
+                WHERE epd.fk_delegate_employee_id = $1
+                    AND epd.revoked_at IS NULL
+                    AND epd.start_at <= NOW()
+                    AND epd.end_at > NOW()
+                    AND delegator.deactivated_at IS NULL
+                    AND EXISTS (
+                        SELECT 1 FROM employee_accreditation_authorizations deaa
+                        JOIN link_employee_authorization dlea ON deaa.fk_employee_level_id = dlea.fk_employee_level_id
+                        WHERE deaa.fk_recipient_employee_id = epd.fk_delegator_employee_id
+                            AND dlea.fk_employee_authorization_type_id = epdt.fk_employee_authorization_type_id
+                            AND (deaa.end_at IS NULL OR deaa.end_at > NOW())
+                            AND deaa.start_at <= NOW()
+                    )
+                    AND NOT EXISTS (
+                        SELECT 1 FROM employee_suspensions des
+                        WHERE des.fk_employee_id = epd.fk_delegator_employee_id
+                            AND des.lifted_at IS NULL
+                            AND des.start_at <= NOW()
+                            AND (des.end_at IS NULL OR des.end_at > NOW())
+                    )
+            ) permissions
+            WHERE NOT EXISTS (
+                SELECT 1 FROM employee_suspensions es
+                WHERE es.fk_employee_id = $1
+                    AND es.lifted_at IS NULL
+                    AND es.start_at <= NOW()
+                    AND (es.end_at IS NULL OR es.end_at > NOW())
+            )
+            ORDER BY permissions.authorization_id
             "#,
             employee_id
         )
@@ -154,8 +256,84 @@ impl AuthService {
         Ok(permission_ids)
     }
     
-    fn generate_access_token(&self, employee: &Employee, permissions: &[i32]) -> Result<String, AppError> {
-        let claims = Claims::new(
+    // Next start or end of an accreditation, team membership or delegation of the employee, the permissions
+    // carried by an access token are only valid until then (e.g. the end of a break-glass elevation)
+    async fn get_next_permission_change_at(&self, employee_id: Uuid) -> Result<Option<DateTime<Utc>>, AppError> {
+        let next_change_at = sqlx::query_scalar!(
+            r#"
+            SELECT MIN(boundary) FROM (
+                SELECT start_at AS boundary FROM employee_accreditation_authorizations
+                WHERE fk_recipient_employee_id = $1 AND start_at > NOW()
+                UNION ALL
+                SELECT end_at AS boundary FROM employee_accreditation_authorizations
+                WHERE fk_recipient_employee_id = $1 AND end_at > NOW()
+                UNION ALL
+                SELECT joined_at AS boundary FROM employee_team_memberships
+                WHERE fk_employee_id = $1 AND joined_at > NOW()
+                UNION ALL
+                SELECT left_at AS boundary FROM employee_team_memberships
+                WHERE fk_employee_id = $1 AND left_at > NOW()
+                UNION ALL
+                -- the accreditations of the teams the employee is or will be a member of
+                SELECT eta.start_at AS boundary FROM employee_team_accreditations eta
+                JOIN employee_team_memberships etm ON etm.fk_employee_team_id = eta.fk_employee_team_id
+                WHERE etm.fk_employee_id = $1 AND (etm.left_at IS NULL OR etm.left_at > NOW()) AND eta.start_at > NOW()
+                UNION ALL
+                SELECT eta.end_at AS boundary FROM employee_team_accreditations eta
+                JOIN employee_team_memberships etm ON etm.fk_employee_team_id = eta.fk_employee_team_id
+                WHERE etm.fk_employee_id = $1 AND (etm.left_at IS NULL OR etm.left_at > NOW()) AND eta.end_at > NOW()
+                UNION ALL
+                SELECT start_at AS boundary FROM employee_permission_delegations
+                WHERE fk_delegate_employee_id = $1 AND revoked_at IS NULL AND start_at > NOW()
+                UNION ALL
+                SELECT end_at AS boundary FROM employee_permission_delegations
+                WHERE fk_delegate_employee_id = $1 AND revoked_at IS NULL AND end_at > NOW()
+                UNION ALL
+                -- the delegated authorizations are lost with the accreditations of the delegator
+                SELECT eaa.end_at AS boundary FROM employee_accreditation_authorizations eaa
+                JOIN employee_permission_delegations epd ON epd.fk_delegator_employee_id = eaa.fk_recipient_employee_id
+                WHERE epd.fk_delegate_employee_id = $1 AND epd.revoked_at IS NULL AND epd.end_at > NOW() AND eaa.end_at > NOW()
+            ) boundaries
+            "#,
+            employee_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(next_change_at)
+    }
+
+    // Return an error describing the suspension currently in effect for the employee, if any
+    async fn ensure_not_suspended(&self, employee_id: Uuid) -> Result<(), AppError> {
+        let suspension = sqlx::query!(
+            r#"
+            SELECT end_at FROM employee_suspensions
+            WHERE fk_employee_id = $1
+                AND lifted_at IS NULL
+                AND start_at <= NOW()
+                AND (end_at IS NULL OR end_at > NOW())
+            ORDER BY end_at DESC NULLS FIRST
+            LIMIT 1
+            "#,
+            employee_id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        match suspension {
+            Some(suspension) => {
+                let message = match suspension.end_at {
+                    Some(end_at) => format!("Your account is suspended until {}", end_at.to_rfc3339()),
+                    None => "Your account is suspended until further notice".to_string(),
+                };
+                Err(AppError::Forbidden(message, "EMPLOYEE_SUSPENDED".to_string()))
+            },
+            None => Ok(()),
+        }
+    }
+    
+    fn generate_access_token(&self, employee: &Employee, permissions: &[i32], expires_before: Option<DateTime<Utc>>) -> Result<String, AppError> {
+        let mut claims = Claims::new(
             employee.pk_employee_id,
             employee.professional_email.clone(),
             employee.firstname.clone(),
@@ -163,6 +341,9 @@ impl AuthService {
             permissions.to_vec(),
             self.access_token_duration_minutes, // 24 heures
         );
+        if let Some(expires_before) = expires_before {
+            claims.exp = claims.exp.min(expires_before.timestamp());
+        }
         
         let token = encode(
             &Header::default(),
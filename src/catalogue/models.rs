@@ -0,0 +1,198 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::employee::models::{CrudType, EntityType};
+
+/// Catalogue shipped with the binary, see `permissions.toml` at the root of the repository
+const EMBEDDED_CATALOGUE: &str = include_str!("../../permissions.toml");
+
+#[derive(Debug, Deserialize)]
+pub struct PermissionCatalogue {
+    pub version: i32,
+    #[serde(default)]
+    pub levels: Vec<CatalogueLevel>,
+    #[serde(default)]
+    pub categories: Vec<CatalogueCategory>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CatalogueLevel {
+    pub level_label: String,
+    pub level_index: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CatalogueCategory {
+    pub name_code: String,
+    pub entity_type: EntityType,
+    pub category_index: i32,
+    #[serde(default)]
+    pub features: Vec<CatalogueFeature>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CatalogueFeature {
+    pub feature_code: String,
+    pub authorization_index: i32,
+    #[serde(default)]
+    pub types: Vec<CatalogueAuthorizationType>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CatalogueAuthorizationType {
+    pub id: i32,
+    pub crud_type: CrudType,
+    pub description: String,
+    /// Labels of the levels granted this authorization type when it is created
+    #[serde(default)]
+    pub levels: Vec<String>,
+}
+
+impl PermissionCatalogue {
+    pub fn embedded() -> Result<Self, String> {
+        Self::parse(EMBEDDED_CATALOGUE)
+    }
+
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let catalogue: Self = toml::from_str(content)
+            .map_err(|e| format!("The permission catalogue is not valid: {}", e))?;
+        catalogue.validate()?;
+        Ok(catalogue)
+    }
+
+    /// Check that codes and ids are unique and that grants only reference declared levels
+    fn validate(&self) -> Result<(), String> {
+        let mut level_labels = HashSet::new();
+        for level in &self.levels {
+            if !level_labels.insert(level.level_label.as_str()) {
+                return Err(format!("Level {} is declared twice", level.level_label));
+            }
+        }
+
+        let mut category_codes = HashSet::new();
+        let mut feature_codes = HashSet::new();
+        let mut type_ids = HashSet::new();
+        for category in &self.categories {
+            if !category_codes.insert(category.name_code.as_str()) {
+                return Err(format!("Category {} is declared twice", category.name_code));
+            }
+            for feature in &category.features {
+                if !feature_codes.insert(feature.feature_code.as_str()) {
+                    return Err(format!("Feature {} is declared twice", feature.feature_code));
+                }
+                for authorization_type in &feature.types {
+                    if !type_ids.insert(authorization_type.id) {
+                        return Err(format!("Authorization type {} is declared twice", authorization_type.id));
+                    }
+                    if let Some(level) = authorization_type.levels.iter().find(|level| !level_labels.contains(level.as_str())) {
+                        return Err(format!("Authorization type {} is granted to the undeclared level {}", authorization_type.id, level));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Outcome of a comparison between the catalogue and the database
+#[derive(Debug, Default, Serialize)]
+pub struct CatalogueSyncReport {
+    pub version: i32,
+    pub dry_run: bool,
+    /// Additions and description changes, applied unless running as a dry run
+    pub applied: Vec<String>,
+    /// Destructive differences, never applied
+    pub reported: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PermissionCatalogue;
+
+    #[test]
+    fn test_embedded_catalogue_is_valid() {
+        let catalogue = PermissionCatalogue::embedded().unwrap();
+        assert!(catalogue.version > 0);
+        assert!(catalogue.levels.iter().any(|level| level.level_label == "ADMIN"));
+
+        // the routes rely on the authorization type ids, they must all be declared
+        let type_ids: Vec<i32> = catalogue.categories.iter()
+            .flat_map(|category| &category.features)
+            .flat_map(|feature| &feature.types)
+            .map(|authorization_type| authorization_type.id)
+            .collect();
+        assert!((1..=56).all(|id| type_ids.contains(&id)));
+    }
+
+    #[test]
+    fn test_catalogue_validation() {
+        let duplicated_type = r#"
+            version = 1
+
+            [[levels]]
+            level_label = "ADMIN"
+            level_index = 1
+
+            [[categories]]
+            name_code = "DRIVER_INFORMATIONS"
+            entity_type = "DRIVER"
+            category_index = 1
+
+              [[categories.features]]
+              feature_code = "DRIVER_GLOBAL_INFORMATIONS"
+              authorization_index = 1
+
+                [[categories.features.types]]
+                id = 1
+                crud_type = "R"
+                description = "Read all driver informations"
+
+                [[categories.features.types]]
+                id = 1
+                crud_type = "C"
+                description = "Create a new driver"
+        "#;
+        assert!(PermissionCatalogue::parse(duplicated_type).is_err());
+
+        let undeclared_level = r#"
+            version = 1
+
+            [[categories]]
+            name_code = "DRIVER_INFORMATIONS"
+            entity_type = "DRIVER"
+            category_index = 1
+
+              [[categories.features]]
+              feature_code = "DRIVER_GLOBAL_INFORMATIONS"
+              authorization_index = 1
+
+                [[categories.features.types]]
+                id = 1
+                crud_type = "R"
+                description = "Read all driver informations"
+                levels = ["ADMIN"]
+        "#;
+        assert!(PermissionCatalogue::parse(undeclared_level).is_err());
+
+        let unknown_crud_type = r#"
+            version = 1
+
+            [[categories]]
+            name_code = "DRIVER_INFORMATIONS"
+            entity_type = "DRIVER"
+            category_index = 1
+
+              [[categories.features]]
+              feature_code = "DRIVER_GLOBAL_INFORMATIONS"
+              authorization_index = 1
+
+                [[categories.features.types]]
+                id = 1
+                crud_type = "X"
+                description = "Read all driver informations"
+        "#;
+        assert!(PermissionCatalogue::parse(unknown_crud_type).is_err());
+    }
+}
@@ -0,0 +1,291 @@
+use std::collections::{HashMap, HashSet};
+
+use serde_json::json;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{auth::services::invalidate_employee_permissions, catalogue::models::{CatalogueSyncReport, PermissionCatalogue}, employee::services::record_level_authorization_change, errors::app_error::AppError};
+
+pub struct CatalogueService {
+    pool: PgPool,
+}
+
+impl CatalogueService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // Compare the catalogue with the database, apply the additions and description changes
+    // and report every other difference. Nothing is written when running as a dry run.
+    pub async fn sync(&self, catalogue: &PermissionCatalogue, dry_run: bool) -> Result<CatalogueSyncReport, AppError> {
+        let mut report = CatalogueSyncReport {
+            version: catalogue.version,
+            dry_run,
+            ..Default::default()
+        };
+
+        let mut tx = self.pool.begin().await?;
+
+        // Levels, matched by label
+        let mut level_ids = HashMap::new();
+        let mut created_level_ids = HashSet::new();
+        let existing_levels = sqlx::query_as::<_, (i32, String, i32)>(
+            "SELECT pk_employee_level_id, level_label, level_index FROM employee_levels WHERE deleted_at IS NULL"
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for level in &catalogue.levels {
+            match existing_levels.iter().find(|(_, label, _)| *label == level.level_label) {
+                Some((id, _, index)) => {
+                    if *index != level.level_index {
+                        report.reported.push(format!("Level {} has index {} instead of {}", level.level_label, index, level.level_index));
+                    }
+                    level_ids.insert(level.level_label.as_str(), *id);
+                }
+                None => {
+                    let id = sqlx::query_scalar::<_, i32>(
+                        "INSERT INTO employee_levels (level_index, level_label) VALUES ($1, $2) RETURNING pk_employee_level_id"
+                    )
+                    .bind(level.level_index)
+                    .bind(&level.level_label)
+                    .fetch_one(&mut *tx)
+                    .await?;
+                    report.applied.push(format!("Created level {}", level.level_label));
+                    level_ids.insert(level.level_label.as_str(), id);
+                    created_level_ids.insert(id);
+                }
+            }
+        }
+        for (_, label, _) in &existing_levels {
+            if !catalogue.levels.iter().any(|level| level.level_label == *label) {
+                report.reported.push(format!("Level {} is not declared in the catalogue", label));
+            }
+        }
+
+        // Categories, matched by name code
+        let existing_categories = sqlx::query_as::<_, (i32, String, String, i32)>(
+            "SELECT pk_employee_authorization_category_id, name_code, entity_type::TEXT, category_index FROM employee_authorization_categories"
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let existing_features = sqlx::query_as::<_, (i32, i32, String, i32)>(
+            "SELECT pk_employee_authorization_id, fk_employee_authorization_category_id, feature_code, authorization_index FROM employee_authorizations"
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let existing_types = sqlx::query_as::<_, (i32, i32, Option<String>, String)>(
+            "SELECT pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type::TEXT, description FROM employee_authorization_types"
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+        let existing_grants: HashSet<(i32, i32)> = sqlx::query_as::<_, (i32, i32)>(
+            "SELECT fk_employee_authorization_type_id, fk_employee_level_id FROM link_employee_authorization"
+        )
+        .fetch_all(&mut *tx)
+        .await?
+        .into_iter()
+        .collect();
+
+        let mut granted_level_ids = HashSet::new();
+
+        for category in &catalogue.categories {
+            let category_id = match existing_categories.iter().find(|(_, code, _, _)| *code == category.name_code) {
+                Some((id, _, entity_type, index)) => {
+                    if entity_type != category.entity_type.as_str() {
+                        report.reported.push(format!("Category {} has entity type {} instead of {}", category.name_code, entity_type, category.entity_type.as_str()));
+                    }
+                    if *index != category.category_index {
+                        report.reported.push(format!("Category {} has index {} instead of {}", category.name_code, index, category.category_index));
+                    }
+                    *id
+                }
+                None => {
+                    let id = sqlx::query_scalar::<_, i32>(
+                        r#"
+                        INSERT INTO employee_authorization_categories (name_code, entity_type, category_index)
+                        VALUES ($1, ($2::TEXT)::"EntityType", $3)
+                        RETURNING pk_employee_authorization_category_id
+                        "#
+                    )
+                    .bind(&category.name_code)
+                    .bind(category.entity_type.as_str())
+                    .bind(category.category_index)
+                    .fetch_one(&mut *tx)
+                    .await?;
+                    report.applied.push(format!("Created category {}", category.name_code));
+                    id
+                }
+            };
+
+            // Features, matched by feature code
+            for feature in &category.features {
+                let feature_id = match existing_features.iter().find(|(_, _, code, _)| *code == feature.feature_code) {
+                    Some((id, feature_category_id, _, index)) => {
+                        if *feature_category_id != category_id {
+                            report.reported.push(format!("Feature {} belongs to another category than {}", feature.feature_code, category.name_code));
+                        }
+                        if *index != feature.authorization_index {
+                            report.reported.push(format!("Feature {} has index {} instead of {}", feature.feature_code, index, feature.authorization_index));
+                        }
+                        *id
+                    }
+                    None => {
+                        let id = sqlx::query_scalar::<_, i32>(
+                            r#"
+                            INSERT INTO employee_authorizations (fk_employee_authorization_category_id, feature_code, authorization_index)
+                            VALUES ($1, $2, $3)
+                            RETURNING pk_employee_authorization_id
+                            "#
+                        )
+                        .bind(category_id)
+                        .bind(&feature.feature_code)
+                        .bind(feature.authorization_index)
+                        .fetch_one(&mut *tx)
+                        .await?;
+                        report.applied.push(format!("Created feature {}", feature.feature_code));
+                        id
+                    }
+                };
+
+                // Authorization types, matched by id since the routes reference them
+                for authorization_type in &feature.types {
+                    let is_new_type = match existing_types.iter().find(|(id, _, _, _)| *id == authorization_type.id) {
+                        Some((_, type_feature_id, crud_type, description)) => {
+                            if *type_feature_id != feature_id {
+                                report.reported.push(format!("Authorization type {} belongs to another feature than {}", authorization_type.id, feature.feature_code));
+                            }
+                            if crud_type.as_deref() != Some(authorization_type.crud_type.as_str()) {
+                                report.reported.push(format!("Authorization type {} has CRUD type {} instead of {}", authorization_type.id, crud_type.as_deref().unwrap_or("none"), authorization_type.crud_type.as_str()));
+                            }
+                            if *description != authorization_type.description {
+                                sqlx::query("UPDATE employee_authorization_types SET description = $1 WHERE pk_employee_authorization_type_id = $2")
+                                    .bind(&authorization_type.description)
+                                    .bind(authorization_type.id)
+                                    .execute(&mut *tx)
+                                    .await?;
+                                report.applied.push(format!("Updated the description of authorization type {}", authorization_type.id));
+                            }
+                            false
+                        }
+                        None => {
+                            sqlx::query(
+                                r#"
+                                INSERT INTO employee_authorization_types (pk_employee_authorization_type_id, fk_employee_authorization_id, crud_type, description)
+                                VALUES ($1, $2, ($3::TEXT)::"CrudType", $4)
+                                "#
+                            )
+                            .bind(authorization_type.id)
+                            .bind(feature_id)
+                            .bind(authorization_type.crud_type.as_str())
+                            .bind(&authorization_type.description)
+                            .execute(&mut *tx)
+                            .await?;
+                            report.applied.push(format!("Created authorization type {} ({})", authorization_type.id, authorization_type.description));
+                            true
+                        }
+                    };
+
+                    // Default grants are only given to new types and new levels, the grants
+                    // edited afterwards through the API are left untouched and only reported
+                    for level_label in &authorization_type.levels {
+                        let level_id = level_ids[level_label.as_str()];
+                        if existing_grants.contains(&(authorization_type.id, level_id)) {
+                            continue;
+                        }
+                        if is_new_type || created_level_ids.contains(&level_id) {
+                            Self::grant(&mut tx, authorization_type.id, level_id).await?;
+                            report.applied.push(format!("Granted authorization type {} to level {}", authorization_type.id, level_label));
+                            granted_level_ids.insert(level_id);
+                        } else {
+                            report.reported.push(format!("Authorization type {} is not granted to level {}", authorization_type.id, level_label));
+                        }
+                    }
+                    for (level_label, level_id) in &level_ids {
+                        if existing_grants.contains(&(authorization_type.id, *level_id)) && !authorization_type.levels.iter().any(|label| label == level_label) {
+                            report.reported.push(format!("Authorization type {} is granted to level {} but not by default", authorization_type.id, level_label));
+                        }
+                    }
+                }
+            }
+        }
+
+        let declared_features: Vec<&str> = catalogue.categories.iter()
+            .flat_map(|category| &category.features)
+            .map(|feature| feature.feature_code.as_str())
+            .collect();
+        let declared_types: Vec<i32> = catalogue.categories.iter()
+            .flat_map(|category| &category.features)
+            .flat_map(|feature| &feature.types)
+            .map(|authorization_type| authorization_type.id)
+            .collect();
+        for (_, code, _, _) in &existing_categories {
+            if !catalogue.categories.iter().any(|category| category.name_code == *code) {
+                report.reported.push(format!("Category {} is not declared in the catalogue", code));
+            }
+        }
+        for (_, _, code, _) in &existing_features {
+            if !declared_features.contains(&code.as_str()) {
+                report.reported.push(format!("Feature {} is not declared in the catalogue", code));
+            }
+        }
+        for (id, _, _, description) in &existing_types {
+            if !declared_types.contains(id) {
+                report.reported.push(format!("Authorization type {} ({}) is not declared in the catalogue", id, description));
+            }
+        }
+
+        if dry_run || report.applied.is_empty() {
+            tx.rollback().await?;
+            return Ok(report);
+        }
+
+        // the types are inserted with explicit ids, the sequence must follow
+        sqlx::query(
+            "SELECT setval(pg_get_serial_sequence('employee_authorization_types', 'pk_employee_authorization_type_id'), (SELECT MAX(pk_employee_authorization_type_id) FROM employee_authorization_types))"
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // the holders of the levels that gained permissions must refresh their tokens
+        let holders = sqlx::query_scalar::<_, Uuid>(
+            r#"
+            SELECT DISTINCT fk_recipient_employee_id
+            FROM employee_accreditation_authorizations
+            WHERE fk_employee_level_id = ANY($1)
+            AND start_at <= NOW() AND (end_at IS NULL OR end_at > NOW())
+            "#
+        )
+        .bind(granted_level_ids.into_iter().collect::<Vec<i32>>())
+        .fetch_all(&mut *tx)
+        .await?;
+        invalidate_employee_permissions(&mut *tx, &holders).await?;
+
+        sqlx::query(
+            "INSERT INTO permission_catalogue_syncs (catalogue_version, applied_changes, reported_differences) VALUES ($1, $2, $3)"
+        )
+        .bind(catalogue.version)
+        .bind(json!(report.applied))
+        .bind(json!(report.reported))
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(report)
+    }
+
+    async fn grant(tx: &mut Transaction<'_, Postgres>, authorization_type_id: i32, level_id: i32) -> Result<(), AppError> {
+        sqlx::query(
+            "INSERT INTO link_employee_authorization (fk_employee_authorization_type_id, fk_employee_level_id) VALUES ($1, $2) ON CONFLICT DO NOTHING"
+        )
+        .bind(authorization_type_id)
+        .bind(level_id)
+        .execute(&mut **tx)
+        .await?;
+
+        record_level_authorization_change(&mut **tx, level_id, authorization_type_id, true).await?;
+
+        Ok(())
+    }
+}
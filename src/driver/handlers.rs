@@ -1,12 +1,13 @@
 use axum::{
     extract::{Path, State, Query},
     http::StatusCode,
+    Extension,
     Json,
 };
 use tracing::debug;
 use std::sync::Arc;
 
-use crate::{driver::{models::{CreateDriverRequest, Driver, GetAllDriversQuery, UpdateDriverRequest}, services::DriverService}, models::paginate::{PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}};
+use crate::{driver::{models::{CreateDriverRequest, Driver, GetAllDriversQuery, UpdateDriverRequest}, services::DriverService}, driver_mail::models::MailPreferences, middleware::AuthState, models::paginate::{PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}, pending_operation::models::PendingOperation};
 use crate::errors::app_error::AppError;
 use uuid::Uuid;
 use validator::Validate;
@@ -29,6 +30,11 @@ pub async fn get_all_drivers(
     if filters.limit <= 0 || filters.limit > PAGINATE_MAX_LIMIT {
         return Err(AppError::Validation(format!("Limit must be between 1 and {}.", PAGINATE_MAX_LIMIT)));
     }
+    for categories in [&filters.opted_in, &filters.opted_out].into_iter().flatten() {
+        if categories.parse::<MailPreferences>().is_err() {
+            return Err(AppError::Validation("Mail categories must be a comma separated list of NEWSLETTER and PROMOTION".to_string()));
+        }
+    }
     
     let (drivers, total) = driver_service.get_all_drivers(&filters).await?;
     
@@ -98,18 +104,17 @@ pub async fn update_driver(
     Ok(Json(updated_driver))
 }
 
+// The deactivation is executed once a second employee approves it
 pub async fn deactivate_driver(
     Path(driver_id): Path<String>,
     State(driver_service): State<Arc<DriverService>>,
-) -> Result<StatusCode, AppError> {
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<(StatusCode, Json<PendingOperation>), AppError> {
     let driver_uuid = driver_id.parse::<Uuid>()
         .map_err(|_| AppError::Validation("Driver ID is not valid".to_string()))?;
     
-    // check if the user exists
-    let _existing_driver = driver_service.get_driver_by_id(&driver_uuid).await?;
-    
-    driver_service.deactivate_driver(&driver_uuid).await?;
-    Ok(StatusCode::NO_CONTENT)
+    let operation = driver_service.request_deactivation(&driver_uuid, &auth_state.employee_id).await?;
+    Ok((StatusCode::ACCEPTED, Json(operation)))
 }
 
 #[cfg(test)]
@@ -5,7 +5,7 @@ use uuid::Uuid;
 use serde_json::Value;
 use validator::Validate;
 
-use crate::models::paginate::{default_limit, default_page, default_sort_order};
+use crate::{driver_mail::models::MailPreferences, models::paginate::{default_limit, default_page, default_sort_order}};
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Driver {
@@ -18,8 +18,9 @@ pub struct Driver {
     pub is_searchable: bool,
     pub allow_request_professional_agreement: bool,
     pub language: String,
+    /// Rest settings, see `DriverRestSettings`
     pub rest_json: Option<Value>,
-    pub mail_preferences: i32,
+    pub mail_preferences: MailPreferences,
     pub created_at: DateTime<Utc>,
     pub verified_at: Option<DateTime<Utc>>,
     pub last_login_at: Option<DateTime<Utc>>,
@@ -78,14 +79,14 @@ pub struct UpdateDriverRequest {
     #[validate(length(equal = 2, message = "Language must be a 2 characters code (ex: fr, en)"))]
     pub language: Option<String>,
     
+    /// Replaces the rest settings wholesale, null removes them. Use `/drivers/{id}/rest-settings` to change some fields only
     pub rest_json: Option<Value>,
     
-    #[validate(range(min = 0, message = "Email preferences must be higher than 0"))]
-    pub mail_preferences: Option<i32>,
+    /// Replaces the mail categories the driver opted into, e.g. `["NEWSLETTER"]`
+    pub mail_preferences: Option<MailPreferences>,
     
     pub verified_at: Option<DateTime<Utc>>,
     pub last_login_at: Option<DateTime<Utc>>,
-    pub deactivated_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -118,6 +119,15 @@ pub struct GetAllDriversQuery {
     pub verified: Option<bool>,
     #[serde(default)]
     pub deactivated: Option<bool>,
+    /// Under a suspension currently in effect
+    #[serde(default)]
+    pub suspended: Option<bool>,
+    /// Comma separated mail categories the driver opted into, e.g. `NEWSLETTER,PROMOTION`
+    #[serde(default)]
+    pub opted_in: Option<String>,
+    /// Comma separated mail categories the driver did not opt into
+    #[serde(default)]
+    pub opted_out: Option<String>,
     #[serde(default = "default_sort_order")]
     pub sort_order: String,
 }
@@ -139,6 +149,9 @@ impl Default for GetAllDriversQuery {
             rest_json: None,
             verified: None,
             deactivated: None,
+            suspended: None,
+            opted_in: None,
+            opted_out: None,
             sort_order: default_sort_order(),
         }
     }
@@ -5,11 +5,9 @@ use crate::{driver::{handlers::{create_driver, deactivate_driver, get_all_driver
 use std::sync::Arc;
 
 pub fn protected_driver_routes(
-    jwt_secret: String,
+    middleware_state: MiddlewareState,
     driver_service: Arc<DriverService>,
 ) -> Router {
-    let auth_state = MiddlewareState { jwt_secret };
-
     Router::new()
         .route("/drivers", get(get_all_drivers).route_layer(from_fn(with_required_permissions(vec![1]))))
         .route("/drivers", post(create_driver).route_layer(from_fn(with_required_permissions(vec![2]))))
@@ -17,7 +15,7 @@ pub fn protected_driver_routes(
         .route("/drivers/{id}", put(update_driver).route_layer(from_fn(with_required_permissions(vec![3]))))
         .route("/drivers/{id}", delete(deactivate_driver).route_layer(from_fn(with_required_permissions(vec![4]))))
         .layer(from_fn_with_state(
-            auth_state,
+            middleware_state,
             auth_middleware,
         ))
         .with_state(driver_service.clone())
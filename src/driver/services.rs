@@ -1,8 +1,9 @@
 use sqlx::PgPool;
 use bcrypt::{hash, DEFAULT_COST};
 use uuid::Uuid;
+use serde_json::Value;
 
-use crate::{driver::models::{CreateDriverRequest, Driver, GetAllDriversQuery, UpdateDriverRequest}, errors::app_error::AppError};
+use crate::{driver::models::{CreateDriverRequest, Driver, GetAllDriversQuery, UpdateDriverRequest}, driver_erasure::services::is_driver_erased, driver_mail::models::MailPreferences, driver_rest_settings::models::DriverRestSettings, driver_suspension::services::ACTIVE_DRIVER_SUSPENSION_CONDITION, errors::app_error::AppError, pending_operation::{models::{PendingOperation, PendingOperationAction}, services::create_pending_operation}};
 
 pub struct DriverService {
     pool: PgPool,
@@ -95,6 +96,27 @@ impl DriverService {
             }
         }
         
+        if let Some(is_suspended) = filters.suspended {
+            let suspended_condition = format!(
+                "EXISTS (SELECT 1 FROM driver_suspensions WHERE fk_driver_id = pk_driver_id AND {})",
+                ACTIVE_DRIVER_SUSPENSION_CONDITION
+            );
+            if is_suspended {
+                where_conditions.push(suspended_condition);
+            } else {
+                where_conditions.push(format!("NOT {}", suspended_condition));
+            }
+        }
+        
+        // the categories are checked by the handler, the bits are not user input
+        if let Some(Ok(opted_in)) = filters.opted_in.as_deref().map(str::parse::<MailPreferences>) {
+            where_conditions.push(format!("mail_preferences & {0} = {0}", opted_in.bits()));
+        }
+        
+        if let Some(Ok(opted_out)) = filters.opted_out.as_deref().map(str::parse::<MailPreferences>) {
+            where_conditions.push(format!("mail_preferences & {} = 0", opted_out.bits()));
+        }
+        
         // Build the complete WHERE clause
         let where_clause = if where_conditions.is_empty() {
             "".to_string()
@@ -229,7 +251,7 @@ impl DriverService {
     pub async fn get_driver_by_id(&self, driver_id: &Uuid) -> Result<Driver, AppError> {
         let driver = sqlx::query_as!(
             Driver,
-            "SELECT pk_driver_id, firstname, lastname, gender, email, phone_number, is_searchable, allow_request_professional_agreement, language, rest_json, mail_preferences, created_at, verified_at, last_login_at, deactivated_at FROM \"drivers\" WHERE pk_driver_id = $1",
+            r#"SELECT pk_driver_id, firstname, lastname, gender, email, phone_number, is_searchable, allow_request_professional_agreement, language, rest_json, mail_preferences as "mail_preferences: MailPreferences", created_at, verified_at, last_login_at, deactivated_at FROM "drivers" WHERE pk_driver_id = $1"#,
             driver_id
         )
         .fetch_optional(&self.pool)
@@ -253,7 +275,7 @@ impl DriverService {
             r#"
             INSERT INTO "drivers" (pk_driver_id, firstname, lastname, gender, email, password_hash, phone_number, is_searchable, allow_request_professional_agreement, language, rest_json, mail_preferences, created_at)
             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, NOW())
-            RETURNING pk_driver_id, firstname, lastname, gender, email, phone_number, is_searchable, allow_request_professional_agreement, language, rest_json, mail_preferences, created_at, verified_at, last_login_at, deactivated_at
+            RETURNING pk_driver_id, firstname, lastname, gender, email, phone_number, is_searchable, allow_request_professional_agreement, language, rest_json, mail_preferences as "mail_preferences: MailPreferences", created_at, verified_at, last_login_at, deactivated_at
             "#,
             driver_id,
             create_req.firstname,
@@ -266,7 +288,8 @@ impl DriverService {
             create_req.allow_request_professional_agreement,
             create_req.language,
             serde_json::Value::Null,
-            32
+            // a new driver has not opted into any optional mail
+            MailPreferences::empty().bits()
         )
         .fetch_one(&self.pool)
         .await?;
@@ -276,6 +299,17 @@ impl DriverService {
 
     // Update a user
     pub async fn update_driver(&self, driver_id: &Uuid, update_req: &UpdateDriverRequest) -> Result<Driver, AppError> {
+        if is_driver_erased(&self.pool, driver_id).await? {
+            return Err(AppError::Conflict("The personal data of this driver has been erased".to_string(), "DRIVER_ERASED".to_string()));
+        }
+
+        // null clears the rest settings, the others are validated and stored in the current version
+        let rest_json = match &update_req.rest_json {
+            None => None,
+            Some(Value::Null) => Some(None),
+            Some(value) => Some(Some(DriverRestSettings::parse(value.clone())?.to_value())),
+        };
+
         // Use a simple approach with separate queries for each field
         if let Some(ref firstname) = update_req.firstname {
             sqlx::query!("UPDATE \"drivers\" SET firstname = $1 WHERE pk_driver_id = $2", firstname, driver_id)
@@ -325,14 +359,15 @@ impl DriverService {
                 .await?;
         }
 
-        if let Some(ref rest_json) = update_req.rest_json {
+        if let Some(rest_json) = rest_json {
             sqlx::query!("UPDATE \"drivers\" SET rest_json = $1 WHERE pk_driver_id = $2", rest_json, driver_id)
                 .execute(&self.pool)
                 .await?;
         }
 
+        // replaces the whole set, the bits unknown to the API are dropped as on creation
         if let Some(mail_preferences) = update_req.mail_preferences {
-            sqlx::query!("UPDATE \"drivers\" SET mail_preferences = $1 WHERE pk_driver_id = $2", mail_preferences, driver_id)
+            sqlx::query!("UPDATE \"drivers\" SET mail_preferences = $1 WHERE pk_driver_id = $2", mail_preferences.bits(), driver_id)
                 .execute(&self.pool)
                 .await?;
         }
@@ -349,35 +384,18 @@ impl DriverService {
                 .await?;
         }
 
-        if let Some(deactivated_at) = update_req.deactivated_at {
-            sqlx::query!("UPDATE \"drivers\" SET deactivated_at = $1 WHERE pk_driver_id = $2", deactivated_at, driver_id)
-                .execute(&self.pool)
-                .await?;
-        }
-
         // Get the updated user
         self.get_driver_by_id(driver_id).await
     }
 
-    // Deactivate a user (soft delete)
-    pub async fn deactivate_driver(&self, driver_id: &Uuid) -> Result<(), AppError> {
+    // Store the deactivation as a pending operation, it is executed once another employee approves it
+    pub async fn request_deactivation(&self, driver_id: &Uuid, requester_id: &Uuid) -> Result<PendingOperation, AppError> {
         let driver = self.get_driver_by_id(driver_id).await?;
         if driver.deactivated_at.is_some() {
-            return Err(AppError::NotFound("Driver has already been deactivated".to_string()));
-        }
-
-        let result = sqlx::query!(
-            "UPDATE \"drivers\" SET deactivated_at = NOW() WHERE pk_driver_id = $1",
-            driver_id
-        )
-        .execute(&self.pool)
-        .await?;
-
-        if result.rows_affected() == 0 {
-            return Err(AppError::NotFound("Driver not found".to_string()));
+            return Err(AppError::Conflict("Driver has already been deactivated".to_string(), "DRIVER_DEACTIVATED".to_string()));
         }
 
-        Ok(())
+        create_pending_operation(&self.pool, &PendingOperationAction::DEACTIVATE_DRIVER { fk_driver_id: *driver_id }, requester_id, None).await
     }
 
     // Check if an email exists
@@ -0,0 +1,95 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::{
+    driver_data_export::{models::{DriverDataExport, DriverDataExportStatus, GetAllDriverDataExportsQuery}, services::DriverDataExportService}, errors::app_error::AppError, middleware::AuthState, models::{paginate::{PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}, path::parse_uuid}
+};
+
+async fn paginated_exports(
+    driver_id: Option<&Uuid>,
+    filters: &GetAllDriverDataExportsQuery,
+    export_service: &DriverDataExportService,
+) -> Result<Json<PaginatedResponse<DriverDataExport>>, AppError> {
+    if filters.page == 0 {
+        return Err(AppError::Validation("Page must be greater than 0".to_string()));
+    }
+    if filters.limit == 0 || filters.limit > PAGINATE_MAX_LIMIT {
+        return Err(AppError::Validation(format!("Limit must be between 1 and {}.", PAGINATE_MAX_LIMIT)));
+    }
+    if filters.status.as_deref().is_some_and(|status| status.parse::<DriverDataExportStatus>().is_err()) {
+        return Err(AppError::Validation("Status must be PENDING, RUNNING, COMPLETED, FAILED or EXPIRED".to_string()));
+    }
+
+    let (exports, total) = export_service.get_all_exports(driver_id, filters).await?;
+
+    Ok(Json(PaginatedResponse {
+        data: exports,
+        pagination: PaginationInfo {
+            page: filters.page,
+            limit: filters.limit,
+            total,
+        },
+    }))
+}
+
+pub async fn get_all_exports(
+    Query(filters): Query<GetAllDriverDataExportsQuery>,
+    State(export_service): State<Arc<DriverDataExportService>>,
+) -> Result<Json<PaginatedResponse<DriverDataExport>>, AppError> {
+    paginated_exports(None, &filters, &export_service).await
+}
+
+pub async fn get_driver_exports(
+    Path(driver_id): Path<String>,
+    Query(filters): Query<GetAllDriverDataExportsQuery>,
+    State(export_service): State<Arc<DriverDataExportService>>,
+) -> Result<Json<PaginatedResponse<DriverDataExport>>, AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    paginated_exports(Some(&driver_uuid), &filters, &export_service).await
+}
+
+/// Status of the export, the archive can be downloaded once COMPLETED
+pub async fn get_export_by_id(
+    Path(export_id): Path<String>,
+    State(export_service): State<Arc<DriverDataExportService>>,
+) -> Result<Json<DriverDataExport>, AppError> {
+    let export_uuid = parse_uuid(&export_id, "Driver data export")?;
+    let export = export_service.get_export_by_id(&export_uuid).await?;
+    Ok(Json(export))
+}
+
+// The archive is generated in the background, poll the export until it is COMPLETED
+pub async fn request_export(
+    Path(driver_id): Path<String>,
+    State(export_service): State<Arc<DriverDataExportService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<(StatusCode, Json<DriverDataExport>), AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+
+    let export = export_service.request_export(&driver_uuid, &auth_state.employee_id).await?;
+    Ok((StatusCode::ACCEPTED, Json(export)))
+}
+
+pub async fn download_export(
+    Path(export_id): Path<String>,
+    State(export_service): State<Arc<DriverDataExportService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Response, AppError> {
+    let export_uuid = parse_uuid(&export_id, "Driver data export")?;
+    let (export, content) = export_service.get_export_content(&export_uuid, &auth_state.employee_id).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip".to_string()),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"driver-data-{}.zip\"", export.fk_driver_id)),
+        ],
+        content,
+    ).into_response())
+}
@@ -0,0 +1,165 @@
+use std::{io::{Cursor, Write}, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{driver::models::Driver, driver_mail::models::DriverMail, driver_suspension::models::DriverSuspension, driver_workday::models::DriverWorkday, driver_workday_document::models::DriverWorkdayDocument, models::{csv::csv_line, paginate::{default_limit, default_page, default_sort_order}}};
+
+/// A subject access request: the archive of the personal data held about a driver,
+/// generated in the background
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DriverDataExport {
+    pub pk_driver_data_export_id: Uuid,
+    pub fk_driver_id: Uuid,
+    pub fk_requesting_employee_id: Uuid,
+    /// PENDING, RUNNING, COMPLETED, FAILED or EXPIRED
+    pub status: String,
+    /// Location of the archive in the document storage
+    #[serde(skip_serializing)]
+    pub storage_key: Option<String>,
+    pub size_bytes: Option<i64>,
+    pub last_error: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// The archive can be downloaded until then
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum DriverDataExportStatus {
+    PENDING,
+    /// Being generated
+    RUNNING,
+    /// Ready to be downloaded
+    COMPLETED,
+    FAILED,
+    /// Archive deleted after its retention period
+    EXPIRED,
+}
+
+impl FromStr for DriverDataExportStatus {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PENDING" => Ok(DriverDataExportStatus::PENDING),
+            "RUNNING" => Ok(DriverDataExportStatus::RUNNING),
+            "COMPLETED" => Ok(DriverDataExportStatus::COMPLETED),
+            "FAILED" => Ok(DriverDataExportStatus::FAILED),
+            "EXPIRED" => Ok(DriverDataExportStatus::EXPIRED),
+            _ => Err(()),
+        }
+    }
+}
+
+/// An action of an employee about the driver, from `employee_action_histories`
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DriverActionHistory {
+    pub fk_employee_id: Uuid,
+    pub fk_employee_authorization_type_id: i32,
+    pub description: Option<Value>,
+    pub created_at: DateTime<Utc>,
+}
+
+const DRIVER_COLUMNS: &[&str] = &["pk_driver_id", "firstname", "lastname", "gender", "email", "phone_number", "is_searchable", "allow_request_professional_agreement", "language", "rest_json", "mail_preferences", "created_at", "verified_at", "last_login_at", "deactivated_at"];
+const WORKDAY_COLUMNS: &[&str] = &["pk_driver_workday_id", "fk_driver_id", "workday_date", "start_time", "end_time", "is_overnight", "break_minutes", "rest_minutes", "notes", "created_at", "updated_at"];
+const DOCUMENT_COLUMNS: &[&str] = &["pk_driver_workday_document_id", "fk_driver_workday_id", "file_name", "content_type", "size_bytes", "fk_uploading_employee_id", "created_at"];
+const MAIL_COLUMNS: &[&str] = &["pk_driver_mail_id", "fk_driver_id", "template", "subject", "body", "status", "recipient_email", "attempts", "last_error", "fk_sending_employee_id", "sent_at", "created_at", "updated_at"];
+const SUSPENSION_COLUMNS: &[&str] = &["pk_driver_suspension_id", "fk_driver_id", "fk_suspending_employee_id", "reason", "start_at", "end_at", "lifted_at", "fk_lifting_employee_id", "lift_reason", "created_at"];
+const ACTION_COLUMNS: &[&str] = &["fk_employee_id", "fk_employee_authorization_type_id", "description", "created_at"];
+
+/// Everything written to the archive
+#[derive(Debug, Serialize)]
+pub struct DriverDataExportContent {
+    pub exported_at: DateTime<Utc>,
+    pub driver: Driver,
+    pub workdays: Vec<DriverWorkday>,
+    pub documents: Vec<DriverWorkdayDocument>,
+    pub mails: Vec<DriverMail>,
+    pub suspensions: Vec<DriverSuspension>,
+    pub actions: Vec<DriverActionHistory>,
+}
+
+impl DriverDataExportContent {
+    /// ZIP archive holding `data.json` and one CSV file per collection
+    pub fn to_archive(&self) -> Result<Vec<u8>, String> {
+        let files = [
+            ("data.json", serde_json::to_string_pretty(self).map_err(|e| e.to_string())?),
+            ("driver.csv", to_csv(std::slice::from_ref(&self.driver), DRIVER_COLUMNS)),
+            ("workdays.csv", to_csv(&self.workdays, WORKDAY_COLUMNS)),
+            ("documents.csv", to_csv(&self.documents, DOCUMENT_COLUMNS)),
+            ("mails.csv", to_csv(&self.mails, MAIL_COLUMNS)),
+            ("suspensions.csv", to_csv(&self.suspensions, SUSPENSION_COLUMNS)),
+            ("actions.csv", to_csv(&self.actions, ACTION_COLUMNS)),
+        ];
+
+        let mut archive = ZipWriter::new(Cursor::new(Vec::new()));
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for (name, content) in files {
+            archive.start_file(name, options).map_err(|e| e.to_string())?;
+            archive.write_all(content.as_bytes()).map_err(|e| e.to_string())?;
+        }
+
+        let cursor = archive.finish().map_err(|e| e.to_string())?;
+        Ok(cursor.into_inner())
+    }
+}
+
+/// A header line then one CSV line per row, holding the given serialized fields in their order.
+/// Nested values are written as JSON.
+pub fn to_csv<T: Serialize>(rows: &[T], columns: &[&str]) -> String {
+    let mut csv = csv_line(columns);
+    for row in rows {
+        let fields = match serde_json::to_value(row) {
+            Ok(Value::Object(fields)) => fields,
+            _ => continue,
+        };
+        csv.push_str(&csv_line(columns.iter().map(|column| match fields.get(*column) {
+            None | Some(Value::Null) => String::new(),
+            Some(Value::String(value)) => value.clone(),
+            Some(value) => value.to_string(),
+        })));
+    }
+    csv
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetAllDriverDataExportsQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    /// PENDING, RUNNING, COMPLETED, FAILED or EXPIRED
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Row {
+        name: &'static str,
+        note: Option<&'static str>,
+        tags: Vec<u8>,
+    }
+
+    #[test]
+    fn test_to_csv() {
+        assert_eq!(to_csv::<Row>(&[], &["name", "note"]), "name,note\r\n");
+
+        // the columns keep the given order whatever the order of the fields
+        let csv = to_csv(&[
+            Row { name: "plain", note: None, tags: vec![] },
+            Row { name: "Doe, \"Jo\"", note: Some("two\nlines"), tags: vec![1, 2] },
+        ], &["tags", "name", "note", "missing"]);
+        assert_eq!(csv, "tags,name,note,missing\r\n[],plain,,\r\n\"[1,2]\",\"Doe, \"\"Jo\"\"\",\"two\nlines\",\r\n");
+    }
+}
@@ -0,0 +1,24 @@
+use axum::{
+    middleware::{from_fn, from_fn_with_state}, routing::{get, post}, Router
+};
+use crate::{
+    driver_data_export::{handlers::{download_export, get_all_exports, get_driver_exports, get_export_by_id, request_export}, services::DriverDataExportService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+};
+use std::sync::Arc;
+
+pub fn protected_driver_data_export_routes(
+    middleware_state: MiddlewareState,
+    export_service: Arc<DriverDataExportService>,
+) -> Router {
+    Router::new()
+        .route("/drivers/data-exports", get(get_all_exports).route_layer(from_fn(with_required_permissions(vec![54]))))
+        .route("/drivers/data-exports/{id}", get(get_export_by_id).route_layer(from_fn(with_required_permissions(vec![54]))))
+        .route("/drivers/data-exports/{id}/download", get(download_export).route_layer(from_fn(with_required_permissions(vec![54]))))
+        .route("/drivers/{id}/data-exports", get(get_driver_exports).route_layer(from_fn(with_required_permissions(vec![54]))))
+        .route("/drivers/{id}/data-exports", post(request_export).route_layer(from_fn(with_required_permissions(vec![54]))))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(export_service.clone())
+}
@@ -0,0 +1,317 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    driver::services::DriverService, driver_data_export::models::{DriverActionHistory, DriverDataExport, DriverDataExportContent, DriverDataExportStatus, GetAllDriverDataExportsQuery}, driver_erasure::services::is_driver_erased, driver_mail::{models::DriverMail, services::MAIL_COLUMNS}, driver_suspension::{models::DriverSuspension, services::SUSPENSION_COLUMNS}, driver_workday::{models::DriverWorkday, services::WORKDAY_COLUMNS}, driver_workday_document::{models::DriverWorkdayDocument, services::DOCUMENT_COLUMNS}, employee::models::EntityType, errors::app_error::AppError, history::{models::NewActionHistory, services::record_action}, storage::DocumentStorage
+};
+
+const EXPORT_COLUMNS: &str = "pk_driver_data_export_id, fk_driver_id, fk_requesting_employee_id, status, storage_key, size_bytes, last_error, started_at, completed_at, expires_at, created_at";
+
+const DEFAULT_RETENTION_HOURS: i64 = 7 * 24;
+
+pub struct DriverDataExportService {
+    pool: PgPool,
+    storage: Arc<dyn DocumentStorage>,
+    driver_service: DriverService,
+    retention: Duration,
+}
+
+impl DriverDataExportService {
+    pub fn new(pool: PgPool, storage: Arc<dyn DocumentStorage>) -> Self {
+        let retention_hours = std::env::var("DRIVER_DATA_EXPORT_RETENTION_HOURS")
+            .ok()
+            .and_then(|hours| hours.parse().ok())
+            .unwrap_or(DEFAULT_RETENTION_HOURS);
+
+        Self {
+            driver_service: DriverService::new(pool.clone()),
+            pool,
+            storage,
+            retention: Duration::hours(retention_hours),
+        }
+    }
+
+    // Get all exports, optionally restricted to one driver
+    pub async fn get_all_exports(&self, driver_id: Option<&Uuid>, filters: &GetAllDriverDataExportsQuery) -> Result<(Vec<DriverDataExport>, u64), AppError> {
+        if let Some(driver_id) = driver_id {
+            self.driver_service.get_driver_by_id(driver_id).await?;
+        }
+
+        let offset = (filters.page - 1) * filters.limit;
+
+        let where_clause = "WHERE ($1::UUID IS NULL OR fk_driver_id = $1) AND ($2::VARCHAR IS NULL OR status = $2)";
+
+        let total_count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) as count FROM driver_data_exports {}", where_clause))
+            .bind(driver_id)
+            .bind(&filters.status)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let order_direction = match filters.sort_order.to_lowercase().as_str() {
+            "desc" => "DESC",
+            _ => "ASC",
+        };
+
+        let exports = sqlx::query_as::<_, DriverDataExport>(&format!(
+            "SELECT {} FROM driver_data_exports {} ORDER BY created_at {} LIMIT $3 OFFSET $4",
+            EXPORT_COLUMNS,
+            where_clause,
+            order_direction
+        ))
+        .bind(driver_id)
+        .bind(&filters.status)
+        .bind(filters.limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((exports, total_count as u64))
+    }
+
+    pub async fn get_export_by_id(&self, export_id: &Uuid) -> Result<DriverDataExport, AppError> {
+        sqlx::query_as::<_, DriverDataExport>(&format!("SELECT {} FROM driver_data_exports WHERE pk_driver_data_export_id = $1", EXPORT_COLUMNS))
+            .bind(export_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Driver data export not found".to_string()))
+    }
+
+    // Queue the export, the archive is generated by the background job
+    pub async fn request_export(&self, driver_id: &Uuid, author_id: &Uuid) -> Result<DriverDataExport, AppError> {
+        self.driver_service.get_driver_by_id(driver_id).await?;
+        if is_driver_erased(&self.pool, driver_id).await? {
+            return Err(AppError::Conflict("The personal data of this driver has been erased".to_string(), "DRIVER_ERASED".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // the index on the exports in progress also holds against concurrent requests
+        let export = sqlx::query_as::<_, DriverDataExport>(&format!(
+            r#"
+            INSERT INTO driver_data_exports (fk_driver_id, fk_requesting_employee_id) VALUES ($1, $2)
+            ON CONFLICT (fk_driver_id) WHERE status IN ('PENDING', 'RUNNING') DO NOTHING
+            RETURNING {}
+            "#,
+            EXPORT_COLUMNS
+        ))
+        .bind(driver_id)
+        .bind(author_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("An export of this driver is already in progress".to_string(), "DRIVER_DATA_EXPORT_IN_PROGRESS".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 54,
+            entity_id: Some(*driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "REQUEST_DRIVER_DATA_EXPORT",
+                "export_id": export.pk_driver_data_export_id,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(export)
+    }
+
+    // Every download is recorded, the archive holds all the personal data of the driver
+    pub async fn get_export_content(&self, export_id: &Uuid, author_id: &Uuid) -> Result<(DriverDataExport, Bytes), AppError> {
+        let export = self.get_export_by_id(export_id).await?;
+        let storage_key = match (export.status.parse::<DriverDataExportStatus>(), &export.storage_key) {
+            (Ok(DriverDataExportStatus::COMPLETED), Some(storage_key)) => storage_key,
+            (Ok(DriverDataExportStatus::EXPIRED), _) => return Err(AppError::Conflict("The archive of this export has expired".to_string(), "DRIVER_DATA_EXPORT_EXPIRED".to_string())),
+            _ => return Err(AppError::Conflict("The archive of this export is not ready".to_string(), "DRIVER_DATA_EXPORT_NOT_READY".to_string())),
+        };
+
+        let content = self.storage.get(storage_key).await?;
+
+        record_action(&self.pool, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 54,
+            entity_id: Some(export.fk_driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "DOWNLOAD_DRIVER_DATA_EXPORT",
+                "export_id": export.pk_driver_data_export_id,
+            }),
+        }).await?;
+
+        Ok((export, content))
+    }
+
+    async fn collect_content(&self, driver_id: &Uuid) -> Result<DriverDataExportContent, AppError> {
+        let driver = self.driver_service.get_driver_by_id(driver_id).await?;
+
+        let workdays = sqlx::query_as::<_, DriverWorkday>(&format!(
+            "SELECT {} FROM driver_workdays WHERE fk_driver_id = $1 ORDER BY workday_date",
+            WORKDAY_COLUMNS
+        ))
+        .bind(driver_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let documents = sqlx::query_as::<_, DriverWorkdayDocument>(&format!(
+            "SELECT {} FROM driver_workday_documents WHERE fk_driver_workday_id IN (SELECT pk_driver_workday_id FROM driver_workdays WHERE fk_driver_id = $1) ORDER BY created_at",
+            DOCUMENT_COLUMNS
+        ))
+        .bind(driver_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        // the mails that actually reached the driver
+        let mails = sqlx::query_as::<_, DriverMail>(&format!(
+            "SELECT {} FROM driver_mails WHERE fk_driver_id = $1 AND status = 'SENT' ORDER BY sent_at",
+            MAIL_COLUMNS
+        ))
+        .bind(driver_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let suspensions = sqlx::query_as::<_, DriverSuspension>(&format!(
+            "SELECT {} FROM driver_suspensions WHERE fk_driver_id = $1 ORDER BY start_at",
+            SUSPENSION_COLUMNS
+        ))
+        .bind(driver_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let actions = sqlx::query_as::<_, DriverActionHistory>(
+            r#"
+            SELECT fk_employee_id, fk_employee_authorization_type_id, description, created_at
+            FROM employee_action_histories
+            WHERE fk_entity_type = 'DRIVER' AND fk_entity_id = $1
+            ORDER BY created_at
+            "#
+        )
+        .bind(driver_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(DriverDataExportContent {
+            exported_at: Utc::now(),
+            driver,
+            workdays,
+            documents,
+            mails,
+            suspensions,
+            actions,
+        })
+    }
+
+    async fn generate_archive(&self, export: &DriverDataExport) -> Result<(String, i64), AppError> {
+        let content = self.collect_content(&export.fk_driver_id).await?;
+        let archive = tokio::task::spawn_blocking(move || content.to_archive())
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to generate the archive: {}", e)))?
+            .map_err(|e| AppError::Internal(format!("Failed to generate the archive: {}", e)))?;
+
+        let storage_key = format!("drivers/{}/exports/{}.zip", export.fk_driver_id, export.pk_driver_data_export_id);
+        let size_bytes = archive.len() as i64;
+        self.storage.put(&storage_key, Bytes::from(archive), "application/zip").await?;
+
+        Ok((storage_key, size_bytes))
+    }
+
+    // Generate the queued archives one at a time. An export left running by a stopped
+    // instance is picked up again after a while.
+    pub async fn process_pending_exports(&self) -> Result<(), AppError> {
+        loop {
+            let claimed = sqlx::query_as::<_, DriverDataExport>(&format!(
+                r#"
+                UPDATE driver_data_exports SET
+                    status = 'RUNNING',
+                    started_at = NOW()
+                WHERE pk_driver_data_export_id = (
+                    SELECT pk_driver_data_export_id FROM driver_data_exports
+                    WHERE status = 'PENDING' OR (status = 'RUNNING' AND started_at <= NOW() - INTERVAL '15 minutes')
+                    ORDER BY created_at
+                    LIMIT 1
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING {}
+                "#,
+                EXPORT_COLUMNS
+            ))
+            .fetch_optional(&self.pool)
+            .await?;
+
+            let Some(export) = claimed else {
+                return Ok(());
+            };
+
+            match self.generate_archive(&export).await {
+                Ok((storage_key, size_bytes)) => {
+                    let completed = sqlx::query!(
+                        r#"
+                        UPDATE driver_data_exports SET
+                            status = 'COMPLETED',
+                            storage_key = $1,
+                            size_bytes = $2,
+                            last_error = NULL,
+                            completed_at = NOW(),
+                            expires_at = NOW() + $3
+                        WHERE pk_driver_data_export_id = $4 AND status = 'RUNNING'
+                        "#,
+                        storage_key,
+                        size_bytes,
+                        self.retention as _,
+                        export.pk_driver_data_export_id
+                    )
+                    .execute(&self.pool)
+                    .await?;
+
+                    // deleted meanwhile by the erasure of the driver
+                    if completed.rows_affected() == 0 {
+                        if let Err(e) = self.storage.delete(&storage_key).await {
+                            warn!("Orphan driver data export {} left in the storage: {}", storage_key, e);
+                        }
+                        continue;
+                    }
+
+                    info!("Data export {} of driver {} generated", export.pk_driver_data_export_id, export.fk_driver_id);
+                }
+                Err(e) => {
+                    error!("Failed to generate the data export {} of driver {}: {}", export.pk_driver_data_export_id, export.fk_driver_id, e);
+                    sqlx::query!(
+                        "UPDATE driver_data_exports SET status = 'FAILED', last_error = $1 WHERE pk_driver_data_export_id = $2 AND status = 'RUNNING'",
+                        e.to_string(),
+                        export.pk_driver_data_export_id
+                    )
+                    .execute(&self.pool)
+                    .await?;
+                }
+            }
+        }
+    }
+
+    // Delete the archives past their retention period, the export rows are kept
+    pub async fn expire_exports(&self) -> Result<(), AppError> {
+        let expired = sqlx::query!(
+            r#"
+            UPDATE driver_data_exports SET status = 'EXPIRED'
+            WHERE status = 'COMPLETED' AND expires_at <= NOW()
+            RETURNING pk_driver_data_export_id, storage_key
+            "#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for export in expired {
+            if let Some(storage_key) = export.storage_key {
+                if let Err(e) = self.storage.delete(&storage_key).await {
+                    warn!("Orphan driver data export {} left in the storage: {}", storage_key, e);
+                }
+            }
+            info!("Data export {} expired", export.pk_driver_data_export_id);
+        }
+
+        Ok(())
+    }
+}
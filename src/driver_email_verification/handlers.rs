@@ -0,0 +1,65 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::{
+    driver_email_verification::{models::{ConfirmDriverEmailRequest, DriverEmailVerification}, services::DriverEmailVerificationService}, errors::app_error::AppError, middleware::AuthState, models::path::parse_uuid
+};
+
+fn validate_request<T: Validate>(req: &T) -> Result<(), AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(format!("The request content is not valid: {}", e)))
+}
+
+pub async fn get_driver_verifications(
+    Path(driver_id): Path<String>,
+    State(verification_service): State<Arc<DriverEmailVerificationService>>,
+) -> Result<Json<Vec<DriverEmailVerification>>, AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    let verifications = verification_service.get_driver_verifications(&driver_uuid).await?;
+    Ok(Json(verifications))
+}
+
+pub async fn request_verification(
+    Path(driver_id): Path<String>,
+    State(verification_service): State<Arc<DriverEmailVerificationService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<(StatusCode, Json<DriverEmailVerification>), AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    let verification = verification_service.request_verification(&driver_uuid, &auth_state.employee_id).await?;
+    Ok((StatusCode::CREATED, Json(verification)))
+}
+
+pub async fn resend_verification(
+    Path(verification_id): Path<String>,
+    State(verification_service): State<Arc<DriverEmailVerificationService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<DriverEmailVerification>, AppError> {
+    let verification_uuid = parse_uuid(&verification_id, "Driver email verification")?;
+    let verification = verification_service.resend_verification(&verification_uuid, &auth_state.employee_id).await?;
+    Ok(Json(verification))
+}
+
+pub async fn revoke_verification(
+    Path(verification_id): Path<String>,
+    State(verification_service): State<Arc<DriverEmailVerificationService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<DriverEmailVerification>, AppError> {
+    let verification_uuid = parse_uuid(&verification_id, "Driver email verification")?;
+    let verification = verification_service.revoke_verification(&verification_uuid, &auth_state.employee_id).await?;
+    Ok(Json(verification))
+}
+
+pub async fn confirm_verification(
+    State(verification_service): State<Arc<DriverEmailVerificationService>>,
+    Json(confirm_req): Json<ConfirmDriverEmailRequest>,
+) -> Result<Json<DriverEmailVerification>, AppError> {
+    validate_request(&confirm_req)?;
+    let verification = verification_service.confirm_verification(&confirm_req.token).await?;
+    Ok(Json(verification))
+}
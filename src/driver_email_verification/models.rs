@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DriverEmailVerification {
+    pub pk_driver_email_verification_id: Uuid,
+    pub fk_driver_id: Uuid,
+    pub email: String,
+    /// PENDING, CONFIRMED, REVOKED or EXPIRED
+    pub status: String,
+    pub expires_at: DateTime<Utc>,
+    pub fk_requesting_employee_id: Uuid,
+    pub sent_count: i32,
+    pub last_sent_at: DateTime<Utc>,
+    pub confirmed_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub fk_revoking_employee_id: Option<Uuid>,
+    /// last mail sent, the previous ones are not delivered anymore
+    pub fk_driver_mail_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Content of the token sent to the driver
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DriverEmailVerificationClaims {
+    pub sub: Uuid, // driver email verification id
+    pub email: String,
+    /// a resend invalidates the tokens sent before
+    pub sent_count: i32,
+    pub exp: i64,
+    pub iat: i64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ConfirmDriverEmailRequest {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+}
@@ -0,0 +1,32 @@
+use axum::{
+    middleware::{from_fn, from_fn_with_state}, routing::{get, post}, Router
+};
+use crate::{
+    driver_email_verification::{handlers::{confirm_verification, get_driver_verifications, request_verification, resend_verification, revoke_verification}, services::DriverEmailVerificationService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+};
+use std::sync::Arc;
+
+/// Called by the driver application with the token received by mail
+pub fn public_driver_email_verification_routes(
+    verification_service: Arc<DriverEmailVerificationService>,
+) -> Router {
+    Router::new()
+        .route("/drivers/email-verifications/confirm", post(confirm_verification))
+        .with_state(verification_service.clone())
+}
+
+pub fn protected_driver_email_verification_routes(
+    middleware_state: MiddlewareState,
+    verification_service: Arc<DriverEmailVerificationService>,
+) -> Router {
+    Router::new()
+        .route("/drivers/email-verifications/{id}/resend", post(resend_verification).route_layer(from_fn(with_required_permissions(vec![3, 13]))))
+        .route("/drivers/email-verifications/{id}/revoke", post(revoke_verification).route_layer(from_fn(with_required_permissions(vec![3]))))
+        .route("/drivers/{id}/email-verifications", get(get_driver_verifications).route_layer(from_fn(with_required_permissions(vec![1]))))
+        .route("/drivers/{id}/email-verifications", post(request_verification).route_layer(from_fn(with_required_permissions(vec![3, 13]))))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(verification_service.clone())
+}
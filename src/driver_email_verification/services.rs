@@ -0,0 +1,336 @@
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use sqlx::{PgExecutor, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    driver::{models::Driver, services::DriverService}, driver_email_verification::models::{DriverEmailVerification, DriverEmailVerificationClaims}, driver_mail::{models::{DriverMailTemplate, MAIL_LINK_PLACEHOLDER}, services::queue_driver_mail}, employee::models::EntityType, errors::app_error::AppError, history::{models::NewActionHistory, services::record_action}
+};
+
+/// The stored status stays PENDING once expired, EXPIRED is only stored when a new verification is requested
+const VERIFICATION_COLUMNS: &str = "pk_driver_email_verification_id, fk_driver_id, email, CASE WHEN status = 'PENDING' AND expires_at <= NOW() THEN 'EXPIRED' ELSE status END AS status, expires_at, fk_requesting_employee_id, sent_count, last_sent_at, confirmed_at, revoked_at, fk_revoking_employee_id, fk_driver_mail_id, created_at";
+
+pub struct DriverEmailVerificationService {
+    pool: PgPool,
+    driver_service: DriverService,
+    // distinct from the key of the employee tokens, a verification token is never accepted as one
+    token_secret: String,
+    token_duration_hours: i64,
+    confirmation_url: String,
+}
+
+impl DriverEmailVerificationService {
+    pub fn new(pool: PgPool) -> Self {
+        let jwt_secret = std::env::var("JWT_SECRET")
+            .expect("JWT_SECRET must be defined");
+        let token_duration_hours = std::env::var("DRIVER_EMAIL_VERIFICATION_DURATION_HOURS")
+            .ok()
+            .and_then(|hours| hours.parse().ok())
+            .unwrap_or(48);
+        // page of the driver application sending the token to the confirmation endpoint
+        let confirmation_url = std::env::var("DRIVER_EMAIL_VERIFICATION_URL")
+            .unwrap_or_else(|_| "https://app.plannify.be/verify-email".to_string());
+
+        Self {
+            driver_service: DriverService::new(pool.clone()),
+            pool,
+            token_secret: format!("{}:driver_email_verification", jwt_secret),
+            token_duration_hours,
+            confirmation_url,
+        }
+    }
+
+    pub async fn get_driver_verifications(&self, driver_id: &Uuid) -> Result<Vec<DriverEmailVerification>, AppError> {
+        self.driver_service.get_driver_by_id(driver_id).await?;
+
+        let verifications = sqlx::query_as::<_, DriverEmailVerification>(&format!(
+            "SELECT {} FROM driver_email_verifications WHERE fk_driver_id = $1 ORDER BY created_at DESC",
+            VERIFICATION_COLUMNS
+        ))
+        .bind(driver_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(verifications)
+    }
+
+    pub async fn get_verification_by_id(&self, verification_id: &Uuid) -> Result<DriverEmailVerification, AppError> {
+        sqlx::query_as::<_, DriverEmailVerification>(&format!("SELECT {} FROM driver_email_verifications WHERE pk_driver_email_verification_id = $1", VERIFICATION_COLUMNS))
+            .bind(verification_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Driver email verification not found".to_string()))
+    }
+
+    fn generate_token(&self, verification_id: &Uuid, email: &str, sent_count: i32, expires_at: DateTime<Utc>) -> Result<String, AppError> {
+        let claims = DriverEmailVerificationClaims {
+            sub: *verification_id,
+            email: email.to_string(),
+            sent_count,
+            exp: expires_at.timestamp(),
+            iat: Utc::now().timestamp(),
+        };
+
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(self.token_secret.as_ref()))
+            .map_err(|_| AppError::Internal("An error occurred while generating the verification token".to_string()))
+    }
+
+    /// Link holding the token of the pending verification whose last mail is the given one, rendered by the outbox when sending it.
+    /// None when the verification was resent, revoked or has expired meanwhile.
+    pub async fn mail_link<'e, E: PgExecutor<'e>>(&self, executor: E, mail_id: &Uuid) -> Result<Option<String>, AppError> {
+        let verification = sqlx::query!(
+            "SELECT pk_driver_email_verification_id, email, sent_count, expires_at FROM driver_email_verifications WHERE fk_driver_mail_id = $1 AND status = 'PENDING' AND expires_at > NOW()",
+            mail_id
+        )
+        .fetch_optional(executor)
+        .await?;
+
+        let Some(verification) = verification else {
+            return Ok(None);
+        };
+
+        let token = self.generate_token(&verification.pk_driver_email_verification_id, &verification.email, verification.sent_count, verification.expires_at)?;
+        Ok(Some(format!("{}?token={}", self.confirmation_url, token)))
+    }
+
+    fn verification_mail(&self, driver: &Driver) -> (String, String) {
+        match driver.language.as_str() {
+            "fr" => (
+                "Confirmez votre adresse email".to_string(),
+                format!(
+                    "Bonjour {},\n\nConfirmez votre adresse email en ouvrant ce lien, valable {} heures :\n{}\n\nSi vous n'êtes pas à l'origine de cette demande, ignorez ce message.",
+                    driver.firstname, self.token_duration_hours, MAIL_LINK_PLACEHOLDER
+                ),
+            ),
+            _ => (
+                "Confirm your email address".to_string(),
+                format!(
+                    "Hello {},\n\nConfirm your email address by opening this link, valid for {} hours:\n{}\n\nIf you did not request it, ignore this message.",
+                    driver.firstname, self.token_duration_hours, MAIL_LINK_PLACEHOLDER
+                ),
+            ),
+        }
+    }
+
+    fn ensure_can_be_verified(driver: &Driver) -> Result<(), AppError> {
+        if driver.deactivated_at.is_some() {
+            return Err(AppError::Conflict("The driver is deactivated".to_string(), "DRIVER_DEACTIVATED".to_string()));
+        }
+        if driver.verified_at.is_some() {
+            return Err(AppError::Conflict("The driver email is already verified".to_string(), "DRIVER_ALREADY_VERIFIED".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Create a verification and queue the mail whose link holds its token
+    pub async fn request_verification(&self, driver_id: &Uuid, author_id: &Uuid) -> Result<DriverEmailVerification, AppError> {
+        let driver = self.driver_service.get_driver_by_id(driver_id).await?;
+        Self::ensure_can_be_verified(&driver)?;
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            "UPDATE driver_email_verifications SET status = 'EXPIRED' WHERE fk_driver_id = $1 AND status = 'PENDING' AND expires_at <= NOW()",
+            driver_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let pending = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM driver_email_verifications WHERE fk_driver_id = $1 AND status = 'PENDING') as "exists!""#,
+            driver_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if pending {
+            return Err(AppError::Conflict("A verification is already pending, it can be resent".to_string(), "DRIVER_EMAIL_VERIFICATION_PENDING".to_string()));
+        }
+
+        let (subject, body) = self.verification_mail(&driver);
+        let mail = queue_driver_mail(&mut *tx, driver_id, &DriverMailTemplate::EMAIL_VERIFICATION, &subject, &body, author_id).await?;
+
+        let expires_at = Utc::now() + Duration::hours(self.token_duration_hours);
+        let verification = sqlx::query_as::<_, DriverEmailVerification>(&format!(
+            r#"
+            INSERT INTO driver_email_verifications (fk_driver_id, email, expires_at, fk_requesting_employee_id, fk_driver_mail_id)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            VERIFICATION_COLUMNS
+        ))
+        .bind(driver_id)
+        .bind(&driver.email)
+        .bind(expires_at)
+        .bind(author_id)
+        .bind(mail.pk_driver_mail_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 3,
+            entity_id: Some(*driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "REQUEST_DRIVER_EMAIL_VERIFICATION",
+                "verification_id": verification.pk_driver_email_verification_id,
+                "email": verification.email,
+                "mail_id": mail.pk_driver_mail_id,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(verification)
+    }
+
+    /// Send a new token for a pending verification, extending it and following a change of the driver email.
+    /// The tokens sent before are not accepted anymore and the previous mails not delivered yet are skipped.
+    pub async fn resend_verification(&self, verification_id: &Uuid, author_id: &Uuid) -> Result<DriverEmailVerification, AppError> {
+        let existing = self.get_verification_by_id(verification_id).await?;
+        let driver = self.driver_service.get_driver_by_id(&existing.fk_driver_id).await?;
+        Self::ensure_can_be_verified(&driver)?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let (subject, body) = self.verification_mail(&driver);
+        let mail = queue_driver_mail(&mut *tx, &driver.pk_driver_id, &DriverMailTemplate::EMAIL_VERIFICATION, &subject, &body, author_id).await?;
+
+        let expires_at = Utc::now() + Duration::hours(self.token_duration_hours);
+        let verification = sqlx::query_as::<_, DriverEmailVerification>(&format!(
+            r#"
+            UPDATE driver_email_verifications SET
+                email = $1,
+                expires_at = $2,
+                sent_count = sent_count + 1,
+                last_sent_at = NOW(),
+                fk_driver_mail_id = $3
+            WHERE pk_driver_email_verification_id = $4 AND status = 'PENDING'
+            RETURNING {}
+            "#,
+            VERIFICATION_COLUMNS
+        ))
+        .bind(&driver.email)
+        .bind(expires_at)
+        .bind(mail.pk_driver_mail_id)
+        .bind(verification_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("The verification is not pending anymore".to_string(), "DRIVER_EMAIL_VERIFICATION_NOT_PENDING".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 3,
+            entity_id: Some(driver.pk_driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "RESEND_DRIVER_EMAIL_VERIFICATION",
+                "verification_id": verification_id,
+                "email": verification.email,
+                "mail_id": mail.pk_driver_mail_id,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(verification)
+    }
+
+    /// The tokens already sent stop being accepted
+    pub async fn revoke_verification(&self, verification_id: &Uuid, author_id: &Uuid) -> Result<DriverEmailVerification, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let verification = sqlx::query_as::<_, DriverEmailVerification>(&format!(
+            r#"
+            UPDATE driver_email_verifications SET
+                status = 'REVOKED',
+                revoked_at = NOW(),
+                fk_revoking_employee_id = $1
+            WHERE pk_driver_email_verification_id = $2 AND status = 'PENDING'
+            RETURNING {}
+            "#,
+            VERIFICATION_COLUMNS
+        ))
+        .bind(author_id)
+        .bind(verification_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(verification) = verification else {
+            // tell a missing verification apart from a processed one
+            self.get_verification_by_id(verification_id).await?;
+            return Err(AppError::Conflict("The verification is not pending anymore".to_string(), "DRIVER_EMAIL_VERIFICATION_NOT_PENDING".to_string()));
+        };
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 3,
+            entity_id: Some(verification.fk_driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "REVOKE_DRIVER_EMAIL_VERIFICATION",
+                "verification_id": verification_id,
+                "email": verification.email,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(verification)
+    }
+
+    /// Called by the driver with the token received by mail, sets `drivers.verified_at`
+    pub async fn confirm_verification(&self, token: &str) -> Result<DriverEmailVerification, AppError> {
+        let claims = decode::<DriverEmailVerificationClaims>(
+            token,
+            &DecodingKey::from_secret(self.token_secret.as_ref()),
+            &Validation::default(),
+        )
+        .map_err(|_| AppError::Validation("The verification token is not valid or has expired".to_string()))?
+        .claims;
+
+        let mut tx = self.pool.begin().await?;
+
+        let pending = sqlx::query!(
+            r#"
+            SELECT v.email, v.sent_count, d.email as driver_email
+            FROM driver_email_verifications v
+            JOIN drivers d ON d.pk_driver_id = v.fk_driver_id
+            WHERE v.pk_driver_email_verification_id = $1 AND v.status = 'PENDING' AND v.expires_at > NOW()
+            FOR UPDATE OF v
+            "#,
+            claims.sub
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("The verification is not pending anymore".to_string(), "DRIVER_EMAIL_VERIFICATION_NOT_PENDING".to_string()))?;
+
+        if pending.sent_count != claims.sent_count {
+            return Err(AppError::Conflict("A newer verification mail has been sent".to_string(), "DRIVER_EMAIL_VERIFICATION_RESENT".to_string()));
+        }
+
+        // a token sent before a change of the driver email cannot verify the new one
+        if pending.email != claims.email || pending.driver_email != claims.email {
+            return Err(AppError::Conflict("The driver email has changed since the token was sent".to_string(), "DRIVER_EMAIL_CHANGED".to_string()));
+        }
+
+        let verification = sqlx::query_as::<_, DriverEmailVerification>(&format!(
+            "UPDATE driver_email_verifications SET status = 'CONFIRMED', confirmed_at = NOW() WHERE pk_driver_email_verification_id = $1 RETURNING {}",
+            VERIFICATION_COLUMNS
+        ))
+        .bind(claims.sub)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE drivers SET verified_at = NOW() WHERE pk_driver_id = $1",
+            verification.fk_driver_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(verification)
+    }
+}
@@ -0,0 +1,97 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    driver_erasure::{models::{CancelDriverErasureRequest, DriverErasure, DriverErasureStatus, GetAllDriverErasuresQuery, RequestDriverErasureRequest}, services::DriverErasureService}, errors::app_error::AppError, middleware::AuthState, models::{paginate::{PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}, path::parse_uuid}, pending_operation::models::PendingOperation
+};
+
+fn validate_request<T: Validate>(req: &T) -> Result<(), AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(format!("The request content is not valid: {}", e)))
+}
+
+async fn paginated_erasures(
+    driver_id: Option<&Uuid>,
+    filters: &GetAllDriverErasuresQuery,
+    erasure_service: &DriverErasureService,
+) -> Result<Json<PaginatedResponse<DriverErasure>>, AppError> {
+    if filters.page == 0 {
+        return Err(AppError::Validation("Page must be greater than 0".to_string()));
+    }
+    if filters.limit == 0 || filters.limit > PAGINATE_MAX_LIMIT {
+        return Err(AppError::Validation(format!("Limit must be between 1 and {}.", PAGINATE_MAX_LIMIT)));
+    }
+    if filters.status.as_deref().is_some_and(|status| status.parse::<DriverErasureStatus>().is_err()) {
+        return Err(AppError::Validation("Status must be SCHEDULED, CANCELLED or COMPLETED".to_string()));
+    }
+
+    let (erasures, total) = erasure_service.get_all_erasures(driver_id, filters).await?;
+
+    Ok(Json(PaginatedResponse {
+        data: erasures,
+        pagination: PaginationInfo {
+            page: filters.page,
+            limit: filters.limit,
+            total,
+        },
+    }))
+}
+
+pub async fn get_all_erasures(
+    Query(filters): Query<GetAllDriverErasuresQuery>,
+    State(erasure_service): State<Arc<DriverErasureService>>,
+) -> Result<Json<PaginatedResponse<DriverErasure>>, AppError> {
+    paginated_erasures(None, &filters, &erasure_service).await
+}
+
+pub async fn get_driver_erasures(
+    Path(driver_id): Path<String>,
+    Query(filters): Query<GetAllDriverErasuresQuery>,
+    State(erasure_service): State<Arc<DriverErasureService>>,
+) -> Result<Json<PaginatedResponse<DriverErasure>>, AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    paginated_erasures(Some(&driver_uuid), &filters, &erasure_service).await
+}
+
+pub async fn get_erasure_by_id(
+    Path(erasure_id): Path<String>,
+    State(erasure_service): State<Arc<DriverErasureService>>,
+) -> Result<Json<DriverErasure>, AppError> {
+    let erasure_uuid = parse_uuid(&erasure_id, "Driver erasure")?;
+    let erasure = erasure_service.get_erasure_by_id(&erasure_uuid).await?;
+    Ok(Json(erasure))
+}
+
+// The erasure is scheduled once a second employee approves it
+pub async fn request_erasure(
+    Path(driver_id): Path<String>,
+    State(erasure_service): State<Arc<DriverErasureService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(request): Json<RequestDriverErasureRequest>,
+) -> Result<(StatusCode, Json<PendingOperation>), AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    validate_request(&request)?;
+
+    let operation = erasure_service.request_erasure(&driver_uuid, &request, &auth_state.employee_id).await?;
+    Ok((StatusCode::ACCEPTED, Json(operation)))
+}
+
+pub async fn cancel_erasure(
+    Path(erasure_id): Path<String>,
+    State(erasure_service): State<Arc<DriverErasureService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(cancel_req): Json<CancelDriverErasureRequest>,
+) -> Result<Json<DriverErasure>, AppError> {
+    let erasure_uuid = parse_uuid(&erasure_id, "Driver erasure")?;
+    validate_request(&cancel_req)?;
+
+    let erasure = erasure_service.cancel_erasure(&erasure_uuid, &cancel_req, &auth_state.employee_id).await?;
+    Ok(Json(erasure))
+}
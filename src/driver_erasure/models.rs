@@ -0,0 +1,152 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::paginate::{default_limit, default_page, default_sort_order};
+
+/// The erasure of the personal data of a driver. Once completed, the anonymized driver and this
+/// row are kept as a tombstone along with the action history.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DriverErasure {
+    pub pk_driver_erasure_id: Uuid,
+    pub fk_driver_id: Uuid,
+    /// The approved operation that scheduled the erasure
+    pub fk_pending_operation_id: Uuid,
+    pub fk_requesting_employee_id: Uuid,
+    pub fk_approving_employee_id: Uuid,
+    /// SCHEDULED, CANCELLED or COMPLETED
+    pub status: String,
+    /// End of the grace period, the erasure can be cancelled until then
+    pub scheduled_at: DateTime<Utc>,
+    pub cancelled_at: Option<DateTime<Utc>>,
+    pub fk_cancelling_employee_id: Option<Uuid>,
+    pub cancel_reason: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Keys of the action history descriptions holding personal data of the driver: the address of the
+/// email verifications, the subject and the body of the mails and the notes of the workdays
+const PERSONAL_DATA_KEYS: [&str; 4] = ["email", "subject", "body", "notes"];
+
+/// Address replacing the one of an erased driver, unique and undeliverable
+pub fn erased_driver_email(driver_id: &Uuid) -> String {
+    format!("erased-{}@erased.invalid", driver_id)
+}
+
+/// Remove the personal data from an action history description at any depth, e.g. from the
+/// `changes` of an update. True when something was removed.
+pub fn scrub_personal_data(description: &mut Value) -> bool {
+    match description {
+        Value::Object(fields) => {
+            let mut scrubbed = false;
+            for key in PERSONAL_DATA_KEYS {
+                scrubbed |= fields.remove(key).is_some();
+            }
+            for value in fields.values_mut() {
+                scrubbed |= scrub_personal_data(value);
+            }
+            scrubbed
+        },
+        Value::Array(values) => {
+            let mut scrubbed = false;
+            for value in values {
+                scrubbed |= scrub_personal_data(value);
+            }
+            scrubbed
+        },
+        _ => false,
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum DriverErasureStatus {
+    SCHEDULED,
+    CANCELLED,
+    COMPLETED,
+}
+
+impl FromStr for DriverErasureStatus {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "SCHEDULED" => Ok(DriverErasureStatus::SCHEDULED),
+            "CANCELLED" => Ok(DriverErasureStatus::CANCELLED),
+            "COMPLETED" => Ok(DriverErasureStatus::COMPLETED),
+            _ => Err(()),
+        }
+    }
+}
+
+impl DriverErasureStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DriverErasureStatus::SCHEDULED => "SCHEDULED",
+            DriverErasureStatus::CANCELLED => "CANCELLED",
+            DriverErasureStatus::COMPLETED => "COMPLETED",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RequestDriverErasureRequest {
+    #[validate(length(min = 10, max = 1000, message = "Justification must contain between 10 and 1000 characters"))]
+    pub justification: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CancelDriverErasureRequest {
+    #[validate(length(min = 1, max = 1000, message = "Cancel reason is required and cannot be longer than 1000 characters"))]
+    pub cancel_reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetAllDriverErasuresQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    /// SCHEDULED, CANCELLED or COMPLETED
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_scrub_personal_data() {
+        let mut description = json!({
+            "action": "UPDATE_DRIVER_MAIL",
+            "mail_id": "3b3746f3-93b6-442b-a971-ab00b3c82569",
+            "changes": {"subject": {"old": "Hello Dana", "new": "Hi Dana"}, "body": {"old": "a", "new": "b"}},
+        });
+        assert!(scrub_personal_data(&mut description));
+        assert_eq!(description, json!({"action": "UPDATE_DRIVER_MAIL", "mail_id": "3b3746f3-93b6-442b-a971-ab00b3c82569", "changes": {}}));
+
+        let mut description = json!({"action": "DELETE_DRIVER_WORKDAY", "workday": {"notes": "Night shift", "break_minutes": 30}, "items": [{"email": "dana@example.com"}]});
+        assert!(scrub_personal_data(&mut description));
+        assert_eq!(description, json!({"action": "DELETE_DRIVER_WORKDAY", "workday": {"break_minutes": 30}, "items": [{}]}));
+
+        let mut description = json!({"action": "LIFT_DRIVER_SUSPENSION", "lift_reason": "Cancelled"});
+        assert!(!scrub_personal_data(&mut description));
+        assert!(!scrub_personal_data(&mut Value::Null));
+    }
+
+    #[test]
+    fn test_erased_driver_email() {
+        let driver_id = Uuid::from_u128(42);
+        assert_eq!(erased_driver_email(&driver_id), "erased-00000000-0000-0000-0000-00000000002a@erased.invalid");
+        assert_ne!(erased_driver_email(&driver_id), erased_driver_email(&Uuid::from_u128(43)));
+    }
+}
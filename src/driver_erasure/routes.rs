@@ -0,0 +1,24 @@
+use axum::{
+    middleware::{from_fn, from_fn_with_state}, routing::{get, post}, Router
+};
+use crate::{
+    driver_erasure::{handlers::{cancel_erasure, get_all_erasures, get_driver_erasures, get_erasure_by_id, request_erasure}, services::DriverErasureService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+};
+use std::sync::Arc;
+
+pub fn protected_driver_erasure_routes(
+    middleware_state: MiddlewareState,
+    erasure_service: Arc<DriverErasureService>,
+) -> Router {
+    Router::new()
+        .route("/drivers/erasures", get(get_all_erasures).route_layer(from_fn(with_required_permissions(vec![1]))))
+        .route("/drivers/erasures/{id}", get(get_erasure_by_id).route_layer(from_fn(with_required_permissions(vec![1]))))
+        .route("/drivers/erasures/{id}/cancel", post(cancel_erasure).route_layer(from_fn(with_required_permissions(vec![53]))))
+        .route("/drivers/{id}/erasures", get(get_driver_erasures).route_layer(from_fn(with_required_permissions(vec![1]))))
+        .route("/drivers/{id}/erasures", post(request_erasure).route_layer(from_fn(with_required_permissions(vec![53]))))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(erasure_service.clone())
+}
@@ -0,0 +1,392 @@
+use std::sync::Arc;
+
+use chrono::Duration;
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    driver::services::DriverService, driver_erasure::models::{erased_driver_email, scrub_personal_data, CancelDriverErasureRequest, DriverErasure, DriverErasureStatus, GetAllDriverErasuresQuery, RequestDriverErasureRequest}, employee::models::EntityType, errors::app_error::AppError, history::{models::{NewActionHistory, SYSTEM_EMPLOYEE_ID}, services::record_action}, pending_operation::{models::{PendingOperation, PendingOperationAction}, services::create_pending_operation}, storage::DocumentStorage
+};
+
+const ERASURE_COLUMNS: &str = "pk_driver_erasure_id, fk_driver_id, fk_pending_operation_id, fk_requesting_employee_id, fk_approving_employee_id, status, scheduled_at, cancelled_at, fk_cancelling_employee_id, cancel_reason, completed_at, created_at";
+
+const DEFAULT_GRACE_PERIOD_DAYS: i64 = 30;
+const PURGE_BATCH_SIZE: i64 = 100;
+
+/// Delay between the approval of an erasure and the anonymization of the driver
+fn grace_period() -> Duration {
+    let days = std::env::var("DRIVER_ERASURE_GRACE_PERIOD_DAYS")
+        .ok()
+        .and_then(|days| days.parse().ok())
+        .unwrap_or(DEFAULT_GRACE_PERIOD_DAYS);
+    Duration::days(days)
+}
+
+/// Fails when the driver is already erased or waiting for its erasure
+pub async fn ensure_driver_erasable<'e, E: PgExecutor<'e>>(executor: E, driver_id: &Uuid) -> Result<(), AppError> {
+    let status = sqlx::query_scalar!(
+        "SELECT status FROM driver_erasures WHERE fk_driver_id = $1 AND status IN ('SCHEDULED', 'COMPLETED')",
+        driver_id
+    )
+    .fetch_optional(executor)
+    .await?;
+
+    match status.as_deref().and_then(|status| status.parse::<DriverErasureStatus>().ok()) {
+        Some(DriverErasureStatus::COMPLETED) => Err(AppError::Conflict("The personal data of this driver has already been erased".to_string(), "DRIVER_ERASED".to_string())),
+        Some(_) => Err(AppError::Conflict("The erasure of this driver is already scheduled".to_string(), "DRIVER_ERASURE_SCHEDULED".to_string())),
+        None => Ok(()),
+    }
+}
+
+/// True when the personal data of the driver has been erased
+pub async fn is_driver_erased<'e, E: PgExecutor<'e>>(executor: E, driver_id: &Uuid) -> Result<bool, AppError> {
+    let erased = sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM driver_erasures WHERE fk_driver_id = $1 AND status = 'COMPLETED') as "exists!""#,
+        driver_id
+    )
+    .fetch_one(executor)
+    .await?;
+
+    Ok(erased)
+}
+
+/// Schedule the erasure approved by `operation` at the end of the grace period
+pub async fn schedule_driver_erasure(conn: &mut PgConnection, operation: &PendingOperation, driver_id: &Uuid) -> Result<DriverErasure, AppError> {
+    ensure_driver_erasable(&mut *conn, driver_id).await?;
+
+    let approving_employee_id = operation.fk_reviewing_employee_id
+        .ok_or_else(|| AppError::Internal("The erasure of a driver must be approved".to_string()))?;
+
+    let erasure = sqlx::query_as::<_, DriverErasure>(&format!(
+        r#"
+        INSERT INTO driver_erasures (fk_driver_id, fk_pending_operation_id, fk_requesting_employee_id, fk_approving_employee_id, scheduled_at)
+        VALUES ($1, $2, $3, $4, NOW() + $5)
+        RETURNING {}
+        "#,
+        ERASURE_COLUMNS
+    ))
+    .bind(driver_id)
+    .bind(operation.pk_pending_operation_id)
+    .bind(operation.fk_requesting_employee_id)
+    .bind(approving_employee_id)
+    .bind(grace_period())
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(erasure)
+}
+
+pub struct DriverErasureService {
+    pool: PgPool,
+    storage: Arc<dyn DocumentStorage>,
+    driver_service: DriverService,
+}
+
+impl DriverErasureService {
+    pub fn new(pool: PgPool, storage: Arc<dyn DocumentStorage>) -> Self {
+        Self {
+            driver_service: DriverService::new(pool.clone()),
+            pool,
+            storage,
+        }
+    }
+
+    // Store the erasure as a pending operation, it is scheduled once another employee approves it
+    pub async fn request_erasure(&self, driver_id: &Uuid, request: &RequestDriverErasureRequest, requester_id: &Uuid) -> Result<PendingOperation, AppError> {
+        self.driver_service.get_driver_by_id(driver_id).await?;
+        ensure_driver_erasable(&self.pool, driver_id).await?;
+
+        create_pending_operation(&self.pool, &PendingOperationAction::ERASE_DRIVER { fk_driver_id: *driver_id }, requester_id, request.justification.as_deref()).await
+    }
+
+    // Get all erasures, optionally restricted to one driver
+    pub async fn get_all_erasures(&self, driver_id: Option<&Uuid>, filters: &GetAllDriverErasuresQuery) -> Result<(Vec<DriverErasure>, u64), AppError> {
+        if let Some(driver_id) = driver_id {
+            self.driver_service.get_driver_by_id(driver_id).await?;
+        }
+
+        let offset = (filters.page - 1) * filters.limit;
+
+        let where_clause = "WHERE ($1::UUID IS NULL OR fk_driver_id = $1) AND ($2::VARCHAR IS NULL OR status = $2)";
+
+        let total_count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) as count FROM driver_erasures {}", where_clause))
+            .bind(driver_id)
+            .bind(&filters.status)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let order_direction = match filters.sort_order.to_lowercase().as_str() {
+            "desc" => "DESC",
+            _ => "ASC",
+        };
+
+        let erasures = sqlx::query_as::<_, DriverErasure>(&format!(
+            "SELECT {} FROM driver_erasures {} ORDER BY scheduled_at {} LIMIT $3 OFFSET $4",
+            ERASURE_COLUMNS,
+            where_clause,
+            order_direction
+        ))
+        .bind(driver_id)
+        .bind(&filters.status)
+        .bind(filters.limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((erasures, total_count as u64))
+    }
+
+    pub async fn get_erasure_by_id(&self, erasure_id: &Uuid) -> Result<DriverErasure, AppError> {
+        sqlx::query_as::<_, DriverErasure>(&format!("SELECT {} FROM driver_erasures WHERE pk_driver_erasure_id = $1", ERASURE_COLUMNS))
+            .bind(erasure_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Driver erasure not found".to_string()))
+    }
+
+    // Cancel a scheduled erasure during its grace period, the row is kept to preserve the history
+    pub async fn cancel_erasure(&self, erasure_id: &Uuid, cancel_req: &CancelDriverErasureRequest, author_id: &Uuid) -> Result<DriverErasure, AppError> {
+        let existing = self.get_erasure_by_id(erasure_id).await?;
+        if existing.status != DriverErasureStatus::SCHEDULED.as_str() {
+            return Err(AppError::Conflict("Only a scheduled erasure can be cancelled".to_string(), "DRIVER_ERASURE_NOT_SCHEDULED".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // the background task may have erased the driver meanwhile
+        let erasure = sqlx::query_as::<_, DriverErasure>(&format!(
+            r#"
+            UPDATE driver_erasures SET
+                status = 'CANCELLED',
+                cancelled_at = NOW(),
+                fk_cancelling_employee_id = $1,
+                cancel_reason = $2
+            WHERE pk_driver_erasure_id = $3 AND status = 'SCHEDULED'
+            RETURNING {}
+            "#,
+            ERASURE_COLUMNS
+        ))
+        .bind(author_id)
+        .bind(&cancel_req.cancel_reason)
+        .bind(erasure_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Only a scheduled erasure can be cancelled".to_string(), "DRIVER_ERASURE_NOT_SCHEDULED".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 53,
+            entity_id: Some(erasure.fk_driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "CANCEL_DRIVER_ERASURE",
+                "erasure_id": erasure.pk_driver_erasure_id,
+                "cancel_reason": erasure.cancel_reason,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(erasure)
+    }
+
+    // Anonymize the drivers whose grace period has passed, then delete their stored files. An erasure
+    // failing is logged without holding back the others, it is attempted again on the next run.
+    pub async fn erase_due_drivers(&self) -> Result<(), AppError> {
+        let due_erasure_ids = sqlx::query_scalar!(
+            "SELECT pk_driver_erasure_id FROM driver_erasures WHERE status = 'SCHEDULED' AND scheduled_at <= NOW()"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for erasure_id in due_erasure_ids {
+            if let Err(e) = self.erase_driver(&erasure_id).await {
+                error!("Failed to complete the driver erasure {}: {}", erasure_id, e);
+            }
+        }
+
+        self.purge_erased_files().await
+    }
+
+    // The action history needs an employee, the erasure is recorded under the system one as nobody
+    // triggers it. The stored files are added to the purge list, deleted once the erasure is committed.
+    async fn erase_driver(&self, erasure_id: &Uuid) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let completed = sqlx::query_as::<_, DriverErasure>(&format!(
+            r#"
+            UPDATE driver_erasures SET
+                status = 'COMPLETED',
+                completed_at = NOW()
+            WHERE pk_driver_erasure_id = $1 AND status = 'SCHEDULED' AND scheduled_at <= NOW()
+            RETURNING {}
+            "#,
+            ERASURE_COLUMNS
+        ))
+        .bind(erasure_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        // cancelled meanwhile
+        let Some(erasure) = completed else {
+            return Ok(());
+        };
+        let driver_id = erasure.fk_driver_id;
+
+        let storage_keys = sqlx::query_scalar!(
+            r#"
+            DELETE FROM driver_workday_documents
+            WHERE fk_driver_workday_id IN (SELECT pk_driver_workday_id FROM driver_workdays WHERE fk_driver_id = $1)
+            RETURNING storage_key
+            "#,
+            driver_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // the archives of the data exports hold the personal data as well
+        let export_storage_keys = sqlx::query_scalar!(
+            "DELETE FROM driver_data_exports WHERE fk_driver_id = $1 RETURNING storage_key",
+            driver_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let purged_storage_keys: Vec<String> = storage_keys.iter().cloned().chain(export_storage_keys.iter().flatten().cloned()).collect();
+        sqlx::query!(
+            "INSERT INTO driver_erasure_purges (fk_driver_erasure_id, storage_key) SELECT $1, UNNEST($2::VARCHAR[])",
+            erasure_id,
+            &purged_storage_keys
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        let deleted_mails = sqlx::query!("DELETE FROM driver_mails WHERE fk_driver_id = $1", driver_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        let deleted_email_verifications = sqlx::query!("DELETE FROM driver_email_verifications WHERE fk_driver_id = $1", driver_id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        // the workdays are kept for the statistics, their notes are free text
+        sqlx::query!("UPDATE driver_workdays SET notes = NULL WHERE fk_driver_id = $1", driver_id)
+            .execute(&mut *tx)
+            .await?;
+
+        // the history is kept, without the personal data recorded along the actions about the driver
+        let histories = sqlx::query!(
+            r#"SELECT created_at, description as "description!" FROM employee_action_histories WHERE fk_entity_type = 'DRIVER' AND fk_entity_id = $1 AND description IS NOT NULL"#,
+            driver_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut scrubbed_history_entries = 0;
+        for history in histories {
+            let mut description = history.description.clone();
+            if !scrub_personal_data(&mut description) {
+                continue;
+            }
+
+            // the entries have no key, an identical duplicate gets the same scrubbed description
+            let scrubbed = sqlx::query!(
+                "UPDATE employee_action_histories SET description = $1 WHERE fk_entity_type = 'DRIVER' AND fk_entity_id = $2 AND created_at = $3 AND description = $4",
+                description,
+                driver_id,
+                history.created_at,
+                history.description
+            )
+            .execute(&mut *tx)
+            .await?;
+            scrubbed_history_entries += scrubbed.rows_affected();
+        }
+
+        // the row stays as a tombstone, the password hash can match no password
+        sqlx::query!(
+            r#"
+            UPDATE "drivers" SET
+                firstname = 'Erased',
+                lastname = 'Driver',
+                gender = NULL,
+                email = $2,
+                password_hash = '!',
+                phone_number = NULL,
+                is_searchable = false,
+                allow_request_professional_agreement = false,
+                rest_json = NULL,
+                mail_preferences = 0,
+                verified_at = NULL,
+                deactivated_at = COALESCE(deactivated_at, NOW())
+            WHERE pk_driver_id = $1
+            "#,
+            driver_id,
+            erased_driver_email(&driver_id)
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: SYSTEM_EMPLOYEE_ID,
+            authorization_type_id: 53,
+            entity_id: Some(driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "ERASE_DRIVER",
+                "erasure_id": erasure.pk_driver_erasure_id,
+                "requesting_employee_id": erasure.fk_requesting_employee_id,
+                "approving_employee_id": erasure.fk_approving_employee_id,
+                "deleted_documents": storage_keys.len(),
+                "deleted_mails": deleted_mails,
+                "deleted_email_verifications": deleted_email_verifications,
+                "deleted_data_exports": export_storage_keys.len(),
+                "scrubbed_history_entries": scrubbed_history_entries,
+                "automatic": true,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        info!("Personal data of driver {} erased", driver_id);
+
+        Ok(())
+    }
+
+    // Delete the stored files of the erased drivers, a file the storage fails to delete stays in the
+    // purge list until a later run succeeds
+    async fn purge_erased_files(&self) -> Result<(), AppError> {
+        let purges = sqlx::query!(
+            "SELECT pk_driver_erasure_purge_id, storage_key FROM driver_erasure_purges ORDER BY attempts, created_at LIMIT $1",
+            PURGE_BATCH_SIZE
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for purge in purges {
+            match self.storage.delete(&purge.storage_key).await {
+                Ok(()) => {
+                    sqlx::query!("DELETE FROM driver_erasure_purges WHERE pk_driver_erasure_purge_id = $1", purge.pk_driver_erasure_purge_id)
+                        .execute(&self.pool)
+                        .await?;
+                },
+                Err(e) => {
+                    let error: String = e.to_string().chars().take(1000).collect();
+                    warn!("Stored file {} of an erased driver left to delete: {}", purge.storage_key, error);
+
+                    sqlx::query!(
+                        "UPDATE driver_erasure_purges SET attempts = attempts + 1, last_error = $1, updated_at = NOW() WHERE pk_driver_erasure_purge_id = $2",
+                        error,
+                        purge.pk_driver_erasure_purge_id
+                    )
+                    .execute(&self.pool)
+                    .await?;
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,98 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::{
+    driver_mail::{models::{CreateDriverMailRequest, DriverMail, DriverMailStatus, GetAllDriverMailsQuery, UpdateDriverMailRequest}, services::DriverMailService}, errors::app_error::AppError, middleware::AuthState, models::{paginate::{PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}, path::parse_uuid}
+};
+
+fn validate_request<T: Validate>(req: &T) -> Result<(), AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(format!("The request content is not valid: {}", e)))
+}
+
+pub async fn get_driver_mails(
+    Path(driver_id): Path<String>,
+    Query(filters): Query<GetAllDriverMailsQuery>,
+    State(mail_service): State<Arc<DriverMailService>>,
+) -> Result<Json<PaginatedResponse<DriverMail>>, AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    if filters.page == 0 {
+        return Err(AppError::Validation("Page must be greater than 0".to_string()));
+    }
+    if filters.limit == 0 || filters.limit > PAGINATE_MAX_LIMIT {
+        return Err(AppError::Validation(format!("Limit must be between 1 and {}.", PAGINATE_MAX_LIMIT)));
+    }
+    if filters.status.as_deref().is_some_and(|status| status.parse::<DriverMailStatus>().is_err()) {
+        return Err(AppError::Validation("Status must be PENDING, SENDING, SENT, FAILED or SKIPPED".to_string()));
+    }
+
+    let (mails, total) = mail_service.get_driver_mails(&driver_uuid, &filters).await?;
+
+    Ok(Json(PaginatedResponse {
+        data: mails,
+        pagination: PaginationInfo {
+            page: filters.page,
+            limit: filters.limit,
+            total,
+        },
+    }))
+}
+
+pub async fn get_mail_by_id(
+    Path(mail_id): Path<String>,
+    State(mail_service): State<Arc<DriverMailService>>,
+) -> Result<Json<DriverMail>, AppError> {
+    let mail_uuid = parse_uuid(&mail_id, "Driver mail")?;
+    let mail = mail_service.get_mail_by_id(&mail_uuid).await?;
+    Ok(Json(mail))
+}
+
+pub async fn create_mail(
+    Path(driver_id): Path<String>,
+    State(mail_service): State<Arc<DriverMailService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<CreateDriverMailRequest>,
+) -> Result<(StatusCode, Json<DriverMail>), AppError> {
+    validate_request(&create_req)?;
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    let mail = mail_service.create_mail(&driver_uuid, &create_req, &auth_state.employee_id).await?;
+    Ok((StatusCode::CREATED, Json(mail)))
+}
+
+pub async fn update_mail(
+    Path(mail_id): Path<String>,
+    State(mail_service): State<Arc<DriverMailService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(update_req): Json<UpdateDriverMailRequest>,
+) -> Result<Json<DriverMail>, AppError> {
+    validate_request(&update_req)?;
+    let mail_uuid = parse_uuid(&mail_id, "Driver mail")?;
+    let mail = mail_service.update_mail(&mail_uuid, &update_req, &auth_state.employee_id).await?;
+    Ok(Json(mail))
+}
+
+pub async fn retry_mail(
+    Path(mail_id): Path<String>,
+    State(mail_service): State<Arc<DriverMailService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<DriverMail>, AppError> {
+    let mail_uuid = parse_uuid(&mail_id, "Driver mail")?;
+    let mail = mail_service.retry_mail(&mail_uuid, &auth_state.employee_id).await?;
+    Ok(Json(mail))
+}
+
+pub async fn delete_mail(
+    Path(mail_id): Path<String>,
+    State(mail_service): State<Arc<DriverMailService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<StatusCode, AppError> {
+    let mail_uuid = parse_uuid(&mail_id, "Driver mail")?;
+    mail_service.delete_mail(&mail_uuid, &auth_state.employee_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
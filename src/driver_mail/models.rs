@@ -0,0 +1,253 @@
+use std::str::FromStr;
+
+use bitflags::bitflags;
+use chrono::{DateTime, Utc};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::{error::BoxDynError, postgres::{PgTypeInfo, PgValueRef}, FromRow, Postgres};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{driver::models::Driver, models::paginate::{default_limit, default_page, default_sort_order}};
+
+bitflags! {
+    /// Optional mail categories the driver opted into, stored in `drivers.mail_preferences`.
+    /// Serialized as the list of the category names, the bits unknown to the API are dropped when reading and writing.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+    pub struct MailPreferences: i32 {
+        const NEWSLETTER = 1;
+        const PROMOTION = 1 << 1;
+    }
+}
+
+impl FromStr for MailPreferences {
+    type Err = ();
+    /// Comma separated category names, e.g. `NEWSLETTER,PROMOTION`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .try_fold(MailPreferences::empty(), |preferences, name| {
+                MailPreferences::from_name(name).map(|flag| preferences | flag).ok_or(())
+            })
+    }
+}
+
+impl Serialize for MailPreferences {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter_names().map(|(name, _)| name))
+    }
+}
+
+impl<'de> Deserialize<'de> for MailPreferences {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .try_fold(MailPreferences::empty(), |preferences, name| {
+                MailPreferences::from_name(name)
+                    .map(|flag| preferences | flag)
+                    .ok_or_else(|| de::Error::custom(format!("unknown mail category `{}`, expected NEWSLETTER or PROMOTION", name)))
+            })
+    }
+}
+
+impl sqlx::Type<Postgres> for MailPreferences {
+    fn type_info() -> PgTypeInfo {
+        <i32 as sqlx::Type<Postgres>>::type_info()
+    }
+}
+
+impl<'r> sqlx::Decode<'r, Postgres> for MailPreferences {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        Ok(MailPreferences::from_bits_truncate(<i32 as sqlx::Decode<Postgres>>::decode(value)?))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[allow(non_camel_case_types)]
+pub enum DriverMailTemplate {
+    /// About the account or the activity of the driver, always sent
+    SERVICE_INFORMATION,
+    NEWSLETTER,
+    PROMOTION,
+    /// Link confirming the email address of the driver, always sent
+    EMAIL_VERIFICATION,
+    /// Link choosing a new password, always sent
+    PASSWORD_RESET,
+}
+
+impl FromStr for DriverMailTemplate {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "SERVICE_INFORMATION" => Ok(DriverMailTemplate::SERVICE_INFORMATION),
+            "NEWSLETTER" => Ok(DriverMailTemplate::NEWSLETTER),
+            "PROMOTION" => Ok(DriverMailTemplate::PROMOTION),
+            "EMAIL_VERIFICATION" => Ok(DriverMailTemplate::EMAIL_VERIFICATION),
+            "PASSWORD_RESET" => Ok(DriverMailTemplate::PASSWORD_RESET),
+            _ => Err(()),
+        }
+    }
+}
+
+impl DriverMailTemplate {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DriverMailTemplate::SERVICE_INFORMATION => "SERVICE_INFORMATION",
+            DriverMailTemplate::NEWSLETTER => "NEWSLETTER",
+            DriverMailTemplate::PROMOTION => "PROMOTION",
+            DriverMailTemplate::EMAIL_VERIFICATION => "EMAIL_VERIFICATION",
+            DriverMailTemplate::PASSWORD_RESET => "PASSWORD_RESET",
+        }
+    }
+
+    /// The other templates are only sent by the API itself, e.g. by the email verification
+    pub fn is_manual(&self) -> bool {
+        !matches!(self, DriverMailTemplate::EMAIL_VERIFICATION | DriverMailTemplate::PASSWORD_RESET)
+    }
+
+    /// Category the driver must have opted into, None when the mail cannot be declined
+    pub fn preference_flag(&self) -> Option<MailPreferences> {
+        match self {
+            DriverMailTemplate::SERVICE_INFORMATION | DriverMailTemplate::EMAIL_VERIFICATION | DriverMailTemplate::PASSWORD_RESET => None,
+            DriverMailTemplate::NEWSLETTER => Some(MailPreferences::NEWSLETTER),
+            DriverMailTemplate::PROMOTION => Some(MailPreferences::PROMOTION),
+        }
+    }
+
+    pub fn is_accepted_by(&self, mail_preferences: MailPreferences) -> bool {
+        self.preference_flag().is_none_or(|flag| mail_preferences.contains(flag))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum DriverMailStatus {
+    /// Waiting for the outbox to deliver it
+    PENDING,
+    /// Claimed by the outbox, the SMTP server is being contacted
+    SENDING,
+    SENT,
+    /// Every delivery attempt failed, or the outbox was interrupted while sending it
+    FAILED,
+    /// The driver opted out of the mail category before the delivery
+    SKIPPED,
+}
+
+impl FromStr for DriverMailStatus {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PENDING" => Ok(DriverMailStatus::PENDING),
+            "SENDING" => Ok(DriverMailStatus::SENDING),
+            "SENT" => Ok(DriverMailStatus::SENT),
+            "FAILED" => Ok(DriverMailStatus::FAILED),
+            "SKIPPED" => Ok(DriverMailStatus::SKIPPED),
+            _ => Err(()),
+        }
+    }
+}
+
+impl DriverMailStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DriverMailStatus::PENDING => "PENDING",
+            DriverMailStatus::SENDING => "SENDING",
+            DriverMailStatus::SENT => "SENT",
+            DriverMailStatus::FAILED => "FAILED",
+            DriverMailStatus::SKIPPED => "SKIPPED",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DriverMail {
+    pub pk_driver_mail_id: Uuid,
+    pub fk_driver_id: Uuid,
+    /// SERVICE_INFORMATION, NEWSLETTER, PROMOTION, EMAIL_VERIFICATION or PASSWORD_RESET
+    pub template: String,
+    /// Rendered for the driver
+    pub subject: String,
+    /// Rendered for the driver
+    pub body: String,
+    /// PENDING, SENDING, SENT, FAILED or SKIPPED
+    pub status: String,
+    pub recipient_email: Option<String>,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub fk_sending_employee_id: Uuid,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Placeholder of the link of the system mails, replaced when sending so that the token it holds is never stored
+pub const MAIL_LINK_PLACEHOLDER: &str = "{{link}}";
+
+/// Replace the `{{firstname}}`, `{{lastname}}` and `{{email}}` placeholders with the driver values
+pub fn render_mail_text(text: &str, driver: &Driver) -> String {
+    text.replace("{{firstname}}", &driver.firstname)
+        .replace("{{lastname}}", &driver.lastname)
+        .replace("{{email}}", &driver.email)
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateDriverMailRequest {
+    pub template: DriverMailTemplate,
+    /// May hold the `{{firstname}}`, `{{lastname}}` and `{{email}}` placeholders
+    #[validate(length(min = 1, max = 200, message = "Subject is required and cannot be longer than 200 characters"))]
+    pub subject: String,
+    /// May hold the `{{firstname}}`, `{{lastname}}` and `{{email}}` placeholders
+    #[validate(length(min = 1, max = 20000, message = "Body is required and cannot be longer than 20000 characters"))]
+    pub body: String,
+}
+
+/// Only the mails not sent yet can be updated
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateDriverMailRequest {
+    #[validate(length(min = 1, max = 200, message = "Subject cannot be empty and cannot be longer than 200 characters"))]
+    pub subject: Option<String>,
+    #[validate(length(min = 1, max = 20000, message = "Body cannot be empty and cannot be longer than 20000 characters"))]
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetAllDriverMailsQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_respects_mail_preferences() {
+        assert!(DriverMailTemplate::SERVICE_INFORMATION.is_accepted_by(MailPreferences::empty()));
+        assert!(!DriverMailTemplate::NEWSLETTER.is_accepted_by(MailPreferences::empty()));
+        assert!(DriverMailTemplate::NEWSLETTER.is_accepted_by(MailPreferences::NEWSLETTER));
+        assert!(!DriverMailTemplate::PROMOTION.is_accepted_by(MailPreferences::NEWSLETTER));
+        assert!(DriverMailTemplate::PROMOTION.is_accepted_by(MailPreferences::NEWSLETTER | MailPreferences::PROMOTION));
+        // bits unknown to the API do not opt into anything
+        assert!(!DriverMailTemplate::NEWSLETTER.is_accepted_by(MailPreferences::from_bits_retain(32)));
+    }
+
+    #[test]
+    fn test_mail_preferences_use_category_names() {
+        let preferences = MailPreferences::from_bits_retain(32) | MailPreferences::PROMOTION;
+        assert_eq!(serde_json::to_value(preferences).unwrap(), serde_json::json!(["PROMOTION"]));
+
+        let parsed: MailPreferences = serde_json::from_value(serde_json::json!(["NEWSLETTER", "PROMOTION"])).unwrap();
+        assert_eq!(parsed, MailPreferences::all());
+        assert!(serde_json::from_value::<MailPreferences>(serde_json::json!(["SPAM"])).is_err());
+        assert!(serde_json::from_value::<MailPreferences>(serde_json::json!(3)).is_err());
+
+        assert_eq!("NEWSLETTER, PROMOTION".parse::<MailPreferences>(), Ok(MailPreferences::all()));
+        assert_eq!("".parse::<MailPreferences>(), Ok(MailPreferences::empty()));
+        assert!("NEWSLETTER,SPAM".parse::<MailPreferences>().is_err());
+    }
+}
@@ -0,0 +1,25 @@
+use axum::{
+    middleware::{from_fn, from_fn_with_state}, routing::{delete, get, post, put}, Router
+};
+use crate::{
+    driver_mail::{handlers::{create_mail, delete_mail, get_driver_mails, get_mail_by_id, retry_mail, update_mail}, services::DriverMailService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+};
+use std::sync::Arc;
+
+pub fn protected_driver_mail_routes(
+    middleware_state: MiddlewareState,
+    mail_service: Arc<DriverMailService>,
+) -> Router {
+    Router::new()
+        .route("/drivers/mails/{id}", get(get_mail_by_id).route_layer(from_fn(with_required_permissions(vec![12]))))
+        .route("/drivers/mails/{id}", put(update_mail).patch(update_mail).route_layer(from_fn(with_required_permissions(vec![14]))))
+        .route("/drivers/mails/{id}", delete(delete_mail).route_layer(from_fn(with_required_permissions(vec![15]))))
+        .route("/drivers/mails/{id}/retry", post(retry_mail).route_layer(from_fn(with_required_permissions(vec![13]))))
+        .route("/drivers/{id}/mails", get(get_driver_mails).route_layer(from_fn(with_required_permissions(vec![12]))))
+        .route("/drivers/{id}/mails", post(create_mail).route_layer(from_fn(with_required_permissions(vec![13]))))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(mail_service.clone())
+}
@@ -0,0 +1,410 @@
+use std::sync::Arc;
+
+use sqlx::{PgExecutor, PgPool};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    driver::services::DriverService, driver_email_verification::services::DriverEmailVerificationService, driver_mail::models::{render_mail_text, CreateDriverMailRequest, DriverMail, DriverMailStatus, DriverMailTemplate, GetAllDriverMailsQuery, MailPreferences, UpdateDriverMailRequest, MAIL_LINK_PLACEHOLDER}, driver_password_reset::services::DriverPasswordResetService, employee::models::EntityType, errors::app_error::AppError, history::{models::{FieldChanges, NewActionHistory}, services::record_action}, mailer::Mailer
+};
+
+pub const MAIL_COLUMNS: &str = "pk_driver_mail_id, fk_driver_id, template, subject, body, status, recipient_email, attempts, last_error, fk_sending_employee_id, sent_at, created_at, updated_at";
+
+/// A mail still failing after this many attempts is marked FAILED
+const MAX_DELIVERY_ATTEMPTS: i32 = 5;
+const DELIVERY_BATCH_SIZE: i64 = 50;
+/// A mail claimed for longer than this is considered interrupted
+const SENDING_TIMEOUT_MINUTES: i32 = 10;
+
+/// Queue a mail in the outbox, the subject and the body being already rendered.
+///
+/// Takes any executor so the mail can be queued in the same transaction as the change it is about.
+/// The caller is responsible for checking the mail preferences of the driver.
+pub async fn queue_driver_mail<'e, E: PgExecutor<'e>>(executor: E, driver_id: &Uuid, template: &DriverMailTemplate, subject: &str, body: &str, author_id: &Uuid) -> Result<DriverMail, AppError> {
+    let mail = sqlx::query_as::<_, DriverMail>(&format!(
+        r#"
+        INSERT INTO driver_mails (fk_driver_id, template, subject, body, fk_sending_employee_id)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING {}
+        "#,
+        MAIL_COLUMNS
+    ))
+    .bind(driver_id)
+    .bind(template.as_str())
+    .bind(subject)
+    .bind(body)
+    .bind(author_id)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(mail)
+}
+
+pub struct DriverMailService {
+    pool: PgPool,
+    driver_service: DriverService,
+    email_verification_service: DriverEmailVerificationService,
+    password_reset_service: DriverPasswordResetService,
+    mailer: Arc<Mailer>,
+}
+
+impl DriverMailService {
+    pub fn new(pool: PgPool, mailer: Arc<Mailer>) -> Self {
+        Self {
+            driver_service: DriverService::new(pool.clone()),
+            email_verification_service: DriverEmailVerificationService::new(pool.clone()),
+            password_reset_service: DriverPasswordResetService::new(pool.clone()),
+            pool,
+            mailer,
+        }
+    }
+
+    pub async fn get_driver_mails(&self, driver_id: &Uuid, filters: &GetAllDriverMailsQuery) -> Result<(Vec<DriverMail>, u64), AppError> {
+        self.driver_service.get_driver_by_id(driver_id).await?;
+
+        let offset = (filters.page - 1) * filters.limit;
+
+        let where_clause = "WHERE fk_driver_id = $1 AND ($2::VARCHAR IS NULL OR status = $2)";
+
+        let total_count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) as count FROM driver_mails {}", where_clause))
+            .bind(driver_id)
+            .bind(&filters.status)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let order_direction = match filters.sort_order.to_lowercase().as_str() {
+            "desc" => "DESC",
+            _ => "ASC",
+        };
+
+        let mails = sqlx::query_as::<_, DriverMail>(&format!(
+            "SELECT {} FROM driver_mails {} ORDER BY created_at {} LIMIT $3 OFFSET $4",
+            MAIL_COLUMNS,
+            where_clause,
+            order_direction
+        ))
+        .bind(driver_id)
+        .bind(&filters.status)
+        .bind(filters.limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((mails, total_count as u64))
+    }
+
+    pub async fn get_mail_by_id(&self, mail_id: &Uuid) -> Result<DriverMail, AppError> {
+        sqlx::query_as::<_, DriverMail>(&format!("SELECT {} FROM driver_mails WHERE pk_driver_mail_id = $1", MAIL_COLUMNS))
+            .bind(mail_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Driver mail not found".to_string()))
+    }
+
+    /// The mail is queued in the outbox, `deliver_pending_mails` sends it
+    pub async fn create_mail(&self, driver_id: &Uuid, create_req: &CreateDriverMailRequest, author_id: &Uuid) -> Result<DriverMail, AppError> {
+        let driver = self.driver_service.get_driver_by_id(driver_id).await?;
+        if driver.deactivated_at.is_some() {
+            return Err(AppError::Conflict("The driver is deactivated".to_string(), "DRIVER_DEACTIVATED".to_string()));
+        }
+        if !create_req.template.is_manual() {
+            return Err(AppError::Validation(format!("The {} mails are only sent by the API itself", create_req.template.as_str())));
+        }
+        if !create_req.template.is_accepted_by(driver.mail_preferences) {
+            return Err(AppError::Forbidden("The driver did not opt into these mails".to_string(), "DRIVER_MAIL_OPTED_OUT".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let mail = queue_driver_mail(
+            &mut *tx,
+            driver_id,
+            &create_req.template,
+            &render_mail_text(&create_req.subject, &driver),
+            &render_mail_text(&create_req.body, &driver),
+            author_id,
+        ).await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 13,
+            entity_id: Some(*driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "CREATE_DRIVER_MAIL",
+                "mail_id": mail.pk_driver_mail_id,
+                "template": mail.template,
+                "subject": mail.subject,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(mail)
+    }
+
+    fn ensure_manual(mail: &DriverMail) -> Result<(), AppError> {
+        match mail.template.parse::<DriverMailTemplate>() {
+            Ok(template) if template.is_manual() => Ok(()),
+            _ => Err(AppError::Conflict(format!("The {} mails are only managed by the API itself", mail.template), "DRIVER_MAIL_NOT_MANUAL".to_string())),
+        }
+    }
+
+    /// A failed mail is queued again once updated
+    pub async fn update_mail(&self, mail_id: &Uuid, update_req: &UpdateDriverMailRequest, author_id: &Uuid) -> Result<DriverMail, AppError> {
+        let existing = self.get_mail_by_id(mail_id).await?;
+        Self::ensure_manual(&existing)?;
+        let driver = self.driver_service.get_driver_by_id(&existing.fk_driver_id).await?;
+
+        let subject = update_req.subject.as_ref().map(|subject| render_mail_text(subject, &driver));
+        let body = update_req.body.as_ref().map(|body| render_mail_text(body, &driver));
+
+        let mut changes = FieldChanges::new();
+        changes.track("subject", &existing.subject, subject.as_ref());
+        changes.track("body", &existing.body, body.as_ref());
+
+        if changes.is_empty() {
+            return Ok(existing);
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // the status is checked again in case the outbox sent the mail meanwhile
+        let mail = sqlx::query_as::<_, DriverMail>(&format!(
+            r#"
+            UPDATE driver_mails SET
+                subject = COALESCE($1, subject),
+                body = COALESCE($2, body),
+                status = 'PENDING',
+                attempts = 0,
+                last_error = NULL,
+                updated_at = NOW()
+            WHERE pk_driver_mail_id = $3 AND status IN ('PENDING', 'FAILED')
+            RETURNING {}
+            "#,
+            MAIL_COLUMNS
+        ))
+        .bind(&subject)
+        .bind(&body)
+        .bind(mail_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Only the mails not sent yet can be updated".to_string(), "DRIVER_MAIL_ALREADY_PROCESSED".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 14,
+            entity_id: Some(mail.fk_driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "UPDATE_DRIVER_MAIL",
+                "mail_id": mail_id,
+                "changes": changes.into_value(),
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(mail)
+    }
+
+    /// Queue a failed mail again without changing it
+    pub async fn retry_mail(&self, mail_id: &Uuid, author_id: &Uuid) -> Result<DriverMail, AppError> {
+        let existing = self.get_mail_by_id(mail_id).await?;
+        Self::ensure_manual(&existing)?;
+        if existing.status != DriverMailStatus::FAILED.as_str() {
+            return Err(AppError::Conflict("Only the failed mails can be retried".to_string(), "DRIVER_MAIL_NOT_FAILED".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let mail = sqlx::query_as::<_, DriverMail>(&format!(
+            "UPDATE driver_mails SET status = 'PENDING', attempts = 0, last_error = NULL, updated_at = NOW() WHERE pk_driver_mail_id = $1 AND status = 'FAILED' RETURNING {}",
+            MAIL_COLUMNS
+        ))
+        .bind(mail_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Only the failed mails can be retried".to_string(), "DRIVER_MAIL_NOT_FAILED".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 13,
+            entity_id: Some(mail.fk_driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "RETRY_DRIVER_MAIL",
+                "mail_id": mail_id,
+                "last_error": existing.last_error,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(mail)
+    }
+
+    /// A mail that has not been sent is deleted, pending or failed
+    pub async fn delete_mail(&self, mail_id: &Uuid, author_id: &Uuid) -> Result<(), AppError> {
+        let existing = self.get_mail_by_id(mail_id).await?;
+        Self::ensure_manual(&existing)?;
+
+        let mut tx = self.pool.begin().await?;
+
+        // the status is checked in the query in case the outbox claimed the mail meanwhile
+        let deleted = sqlx::query!("DELETE FROM driver_mails WHERE pk_driver_mail_id = $1 AND status IN ('PENDING', 'FAILED')", mail_id)
+            .execute(&mut *tx)
+            .await?;
+        if deleted.rows_affected() == 0 {
+            return Err(AppError::Conflict("Only the mails not sent yet can be deleted".to_string(), "DRIVER_MAIL_ALREADY_PROCESSED".to_string()));
+        }
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 15,
+            entity_id: Some(existing.fk_driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "DELETE_DRIVER_MAIL",
+                "mail_id": mail_id,
+                "template": existing.template,
+                "subject": existing.subject,
+                "status": existing.status,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    /// Send the pending mails of the outbox, returns how many were processed
+    pub async fn deliver_pending_mails(&self) -> Result<usize, AppError> {
+        // a mail still SENDING long after being claimed was interrupted (e.g. by a restart) and may
+        // have been delivered, it is failed rather than sent twice, an employee can retry it
+        let interrupted = sqlx::query!(
+            "UPDATE driver_mails SET status = 'FAILED', last_error = $1, updated_at = NOW() WHERE status = 'SENDING' AND updated_at < NOW() - make_interval(mins => $2)",
+            "The delivery was interrupted, the mail may have been sent",
+            SENDING_TIMEOUT_MINUTES
+        )
+        .execute(&self.pool)
+        .await?;
+        if interrupted.rows_affected() > 0 {
+            warn!("{} driver mail(s) interrupted while sending marked as FAILED", interrupted.rows_affected());
+        }
+
+        let mail_ids = sqlx::query_scalar!(
+            "SELECT pk_driver_mail_id FROM driver_mails WHERE status = 'PENDING' ORDER BY created_at LIMIT $1",
+            DELIVERY_BATCH_SIZE
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut processed = 0;
+        for mail_id in mail_ids {
+            match self.deliver_mail(&mail_id).await {
+                Ok(true) => processed += 1,
+                Ok(false) => {},
+                Err(e) => warn!("Failed to process driver mail {}: {}", mail_id, e),
+            }
+        }
+
+        Ok(processed)
+    }
+
+    // The mail is claimed as SENDING and committed before contacting the SMTP server, so that no
+    // row lock nor transaction is held during the delivery. False when another worker claimed it
+    // or it is not pending anymore.
+    async fn deliver_mail(&self, mail_id: &Uuid) -> Result<bool, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let Some(mail) = sqlx::query!(
+            r#"
+            SELECT m.template, m.subject, m.body, m.attempts, d.email, d.mail_preferences as "mail_preferences: MailPreferences", d.deactivated_at
+            FROM driver_mails m
+            JOIN drivers d ON d.pk_driver_id = m.fk_driver_id
+            WHERE m.pk_driver_mail_id = $1 AND m.status = 'PENDING'
+            FOR UPDATE OF m SKIP LOCKED
+            "#,
+            mail_id
+        )
+        .fetch_optional(&mut *tx)
+        .await? else {
+            return Ok(false);
+        };
+
+        // the preferences may have changed since the mail was queued
+        let template = mail.template.parse::<DriverMailTemplate>()
+            .map_err(|_| AppError::Internal(format!("Unknown driver mail template: {}", mail.template)))?;
+        // the link of a system mail holds a token, it is rendered here so that it is never stored
+        let link = match template {
+            DriverMailTemplate::EMAIL_VERIFICATION => self.email_verification_service.mail_link(&mut *tx, mail_id).await?,
+            DriverMailTemplate::PASSWORD_RESET => self.password_reset_service.mail_link(&mut *tx, mail_id).await?,
+            _ => None,
+        };
+        let skip_reason = if mail.deactivated_at.is_some() {
+            Some("The driver is deactivated")
+        } else if !template.is_accepted_by(mail.mail_preferences) {
+            Some("The driver opted out of these mails")
+        } else if !template.is_manual() && link.is_none() {
+            Some("The request of the mail was replaced or is not pending anymore")
+        } else {
+            None
+        };
+
+        if let Some(reason) = skip_reason {
+            sqlx::query!(
+                "UPDATE driver_mails SET status = 'SKIPPED', last_error = $1, updated_at = NOW() WHERE pk_driver_mail_id = $2",
+                reason,
+                mail_id
+            )
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+
+            return Ok(true);
+        }
+
+        sqlx::query!(
+            "UPDATE driver_mails SET status = 'SENDING', attempts = attempts + 1, updated_at = NOW() WHERE pk_driver_mail_id = $1",
+            mail_id
+        )
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        let body = match &link {
+            Some(link) => mail.body.replace(MAIL_LINK_PLACEHOLDER, link),
+            None => mail.body,
+        };
+
+        let attempts = mail.attempts + 1;
+        match self.mailer.send(&mail.email, &mail.subject, &body).await {
+            Ok(()) => {
+                sqlx::query!(
+                    "UPDATE driver_mails SET status = 'SENT', recipient_email = $1, last_error = NULL, sent_at = NOW(), updated_at = NOW() WHERE pk_driver_mail_id = $2 AND status = 'SENDING'",
+                    mail.email,
+                    mail_id
+                )
+                .execute(&self.pool)
+                .await?;
+            },
+            Err(e) => {
+                let status = if attempts >= MAX_DELIVERY_ATTEMPTS { DriverMailStatus::FAILED } else { DriverMailStatus::PENDING };
+                let error: String = e.to_string().chars().take(1000).collect();
+                warn!("Delivery attempt {} of driver mail {} failed: {}", attempts, mail_id, error);
+
+                sqlx::query!(
+                    "UPDATE driver_mails SET status = $1, last_error = $2, updated_at = NOW() WHERE pk_driver_mail_id = $3 AND status = 'SENDING'",
+                    status.as_str(),
+                    error,
+                    mail_id
+                )
+                .execute(&self.pool)
+                .await?;
+            },
+        }
+
+        Ok(true)
+    }
+}
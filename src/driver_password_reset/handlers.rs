@@ -0,0 +1,65 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::{
+    driver_password_reset::{models::{ChangeTemporaryDriverPasswordRequest, ConfirmDriverPasswordResetRequest, CreateDriverPasswordResetRequest, CreatedDriverPasswordReset, DriverPasswordReset}, services::DriverPasswordResetService}, errors::app_error::AppError, middleware::AuthState, models::path::parse_uuid
+};
+
+fn validate_request<T: Validate>(req: &T) -> Result<(), AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(format!("The request content is not valid: {}", e)))
+}
+
+pub async fn get_driver_resets(
+    Path(driver_id): Path<String>,
+    State(reset_service): State<Arc<DriverPasswordResetService>>,
+) -> Result<Json<Vec<DriverPasswordReset>>, AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    let resets = reset_service.get_driver_resets(&driver_uuid).await?;
+    Ok(Json(resets))
+}
+
+pub async fn request_reset(
+    Path(driver_id): Path<String>,
+    State(reset_service): State<Arc<DriverPasswordResetService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<CreateDriverPasswordResetRequest>,
+) -> Result<(StatusCode, Json<CreatedDriverPasswordReset>), AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    let reset = reset_service.request_reset(&driver_uuid, &create_req.method, &auth_state.employee_id).await?;
+    Ok((StatusCode::CREATED, Json(reset)))
+}
+
+pub async fn revoke_reset(
+    Path(reset_id): Path<String>,
+    State(reset_service): State<Arc<DriverPasswordResetService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<DriverPasswordReset>, AppError> {
+    let reset_uuid = parse_uuid(&reset_id, "Driver password reset")?;
+    let reset = reset_service.revoke_reset(&reset_uuid, &auth_state.employee_id).await?;
+    Ok(Json(reset))
+}
+
+pub async fn confirm_reset(
+    State(reset_service): State<Arc<DriverPasswordResetService>>,
+    Json(confirm_req): Json<ConfirmDriverPasswordResetRequest>,
+) -> Result<Json<DriverPasswordReset>, AppError> {
+    validate_request(&confirm_req)?;
+    let reset = reset_service.confirm_reset(&confirm_req.token, &confirm_req.password).await?;
+    Ok(Json(reset))
+}
+
+pub async fn change_temporary_password(
+    State(reset_service): State<Arc<DriverPasswordResetService>>,
+    Json(change_req): Json<ChangeTemporaryDriverPasswordRequest>,
+) -> Result<Json<DriverPasswordReset>, AppError> {
+    validate_request(&change_req)?;
+    let reset = reset_service.change_temporary_password(&change_req.email, &change_req.temporary_password, &change_req.password).await?;
+    Ok(Json(reset))
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+/// Characters of the temporary passwords, without the ones easily confused when read over the phone
+const TEMPORARY_PASSWORD_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz23456789";
+const TEMPORARY_PASSWORD_LENGTH: usize = 12;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DriverPasswordReset {
+    pub pk_driver_password_reset_id: Uuid,
+    pub fk_driver_id: Uuid,
+    /// LINK or TEMPORARY_PASSWORD
+    pub method: String,
+    /// PENDING, COMPLETED, REVOKED or EXPIRED
+    pub status: String,
+    pub expires_at: DateTime<Utc>,
+    pub fk_requesting_employee_id: Uuid,
+    /// Mail holding the link, None for a temporary password
+    pub fk_driver_mail_id: Option<Uuid>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub fk_revoking_employee_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[allow(non_camel_case_types)]
+pub enum DriverPasswordResetMethod {
+    /// Mail a link to choose a new password, the current one keeps working meanwhile
+    LINK,
+    /// Replace the password by a temporary one, given once to the employee and changed by the driver
+    TEMPORARY_PASSWORD,
+}
+
+impl DriverPasswordResetMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DriverPasswordResetMethod::LINK => "LINK",
+            DriverPasswordResetMethod::TEMPORARY_PASSWORD => "TEMPORARY_PASSWORD",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateDriverPasswordResetRequest {
+    pub method: DriverPasswordResetMethod,
+}
+
+/// The temporary password is only returned here, it cannot be read again
+#[derive(Debug, Serialize)]
+pub struct CreatedDriverPasswordReset {
+    #[serde(flatten)]
+    pub reset: DriverPasswordReset,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temporary_password: Option<String>,
+}
+
+/// Content of the token sent to the driver
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DriverPasswordResetClaims {
+    pub sub: Uuid, // driver password reset id
+    pub exp: i64,
+    pub iat: i64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ConfirmDriverPasswordResetRequest {
+    #[validate(length(min = 1, message = "Token is required"))]
+    pub token: String,
+
+    #[validate(length(min = 8, message = "Password must contain at least 8 characters"))]
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ChangeTemporaryDriverPasswordRequest {
+    #[validate(email(message = "Invalid email format"))]
+    pub email: String,
+
+    #[validate(length(min = 1, message = "Temporary password is required"))]
+    pub temporary_password: String,
+
+    #[validate(length(min = 8, message = "Password must contain at least 8 characters"))]
+    pub password: String,
+}
+
+pub fn generate_temporary_password() -> String {
+    let mut rng = rand::thread_rng();
+    (0..TEMPORARY_PASSWORD_LENGTH)
+        .map(|_| TEMPORARY_PASSWORD_ALPHABET[rng.gen_range(0..TEMPORARY_PASSWORD_ALPHABET.len())] as char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_temporary_password() {
+        let password = generate_temporary_password();
+        assert_eq!(password.len(), TEMPORARY_PASSWORD_LENGTH);
+        assert!(password.bytes().all(|c| TEMPORARY_PASSWORD_ALPHABET.contains(&c)));
+        assert_ne!(password, generate_temporary_password());
+    }
+}
@@ -0,0 +1,32 @@
+use axum::{
+    middleware::{from_fn, from_fn_with_state}, routing::{get, post}, Router
+};
+use crate::{
+    driver_password_reset::{handlers::{change_temporary_password, confirm_reset, get_driver_resets, request_reset, revoke_reset}, services::DriverPasswordResetService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+};
+use std::sync::Arc;
+
+/// Called by the driver application, with the token received by mail or the temporary password
+pub fn public_driver_password_reset_routes(
+    reset_service: Arc<DriverPasswordResetService>,
+) -> Router {
+    Router::new()
+        .route("/drivers/password-resets/confirm", post(confirm_reset))
+        .route("/drivers/password-resets/temporary-password", post(change_temporary_password))
+        .with_state(reset_service.clone())
+}
+
+pub fn protected_driver_password_reset_routes(
+    middleware_state: MiddlewareState,
+    reset_service: Arc<DriverPasswordResetService>,
+) -> Router {
+    Router::new()
+        .route("/drivers/password-resets/{id}/revoke", post(revoke_reset).route_layer(from_fn(with_required_permissions(vec![3]))))
+        .route("/drivers/{id}/password-resets", get(get_driver_resets).route_layer(from_fn(with_required_permissions(vec![1]))))
+        .route("/drivers/{id}/password-resets", post(request_reset).route_layer(from_fn(with_required_permissions(vec![3]))))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(reset_service.clone())
+}
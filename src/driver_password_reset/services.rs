@@ -0,0 +1,408 @@
+use bcrypt::{hash, verify, DEFAULT_COST};
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use sqlx::{PgConnection, PgExecutor, PgPool};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    driver::{models::Driver, services::DriverService}, driver_mail::{models::{DriverMailTemplate, MAIL_LINK_PLACEHOLDER}, services::queue_driver_mail}, driver_password_reset::models::{generate_temporary_password, CreatedDriverPasswordReset, DriverPasswordReset, DriverPasswordResetClaims, DriverPasswordResetMethod}, employee::models::EntityType, errors::app_error::AppError, history::{models::{NewActionHistory, SYSTEM_EMPLOYEE_ID}, services::record_action}
+};
+
+/// The stored status of a link stays PENDING once expired, EXPIRED is only stored when a new reset is requested.
+/// An expired temporary password is stored EXPIRED by `expire_temporary_passwords`.
+const RESET_COLUMNS: &str = "pk_driver_password_reset_id, fk_driver_id, method, CASE WHEN status = 'PENDING' AND expires_at <= NOW() THEN 'EXPIRED' ELSE status END AS status, expires_at, fk_requesting_employee_id, fk_driver_mail_id, completed_at, revoked_at, fk_revoking_employee_id, created_at";
+
+fn hash_password(password: &str) -> Result<String, AppError> {
+    hash(password, DEFAULT_COST)
+        .map_err(|_| AppError::Internal("Failed to hash password".to_string()))
+}
+
+/// The temporary password replaced the previous one, once revoked or expired the driver has no
+/// password anymore: the hash can match no password and a new reset is needed
+async fn invalidate_password(conn: &mut PgConnection, driver_id: &Uuid) -> Result<(), AppError> {
+    sqlx::query!("UPDATE \"drivers\" SET password_hash = '!' WHERE pk_driver_id = $1", driver_id)
+        .execute(conn)
+        .await?;
+
+    Ok(())
+}
+
+pub struct DriverPasswordResetService {
+    pool: PgPool,
+    driver_service: DriverService,
+    // distinct from the key of the employee and email verification tokens
+    token_secret: String,
+    duration_hours: i64,
+    reset_url: String,
+}
+
+impl DriverPasswordResetService {
+    pub fn new(pool: PgPool) -> Self {
+        let jwt_secret = std::env::var("JWT_SECRET")
+            .expect("JWT_SECRET must be defined");
+        // validity of the link and of the temporary password
+        let duration_hours = std::env::var("DRIVER_PASSWORD_RESET_DURATION_HOURS")
+            .ok()
+            .and_then(|hours| hours.parse().ok())
+            .unwrap_or(24);
+        // page of the driver application sending the token and the new password to the confirmation endpoint
+        let reset_url = std::env::var("DRIVER_PASSWORD_RESET_URL")
+            .unwrap_or_else(|_| "https://app.plannify.be/reset-password".to_string());
+
+        Self {
+            driver_service: DriverService::new(pool.clone()),
+            pool,
+            token_secret: format!("{}:driver_password_reset", jwt_secret),
+            duration_hours,
+            reset_url,
+        }
+    }
+
+    pub async fn get_driver_resets(&self, driver_id: &Uuid) -> Result<Vec<DriverPasswordReset>, AppError> {
+        self.driver_service.get_driver_by_id(driver_id).await?;
+
+        let resets = sqlx::query_as::<_, DriverPasswordReset>(&format!(
+            "SELECT {} FROM driver_password_resets WHERE fk_driver_id = $1 ORDER BY created_at DESC",
+            RESET_COLUMNS
+        ))
+        .bind(driver_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(resets)
+    }
+
+    pub async fn get_reset_by_id(&self, reset_id: &Uuid) -> Result<DriverPasswordReset, AppError> {
+        sqlx::query_as::<_, DriverPasswordReset>(&format!("SELECT {} FROM driver_password_resets WHERE pk_driver_password_reset_id = $1", RESET_COLUMNS))
+            .bind(reset_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Driver password reset not found".to_string()))
+    }
+
+    fn generate_token(&self, reset_id: &Uuid, expires_at: DateTime<Utc>) -> Result<String, AppError> {
+        let claims = DriverPasswordResetClaims {
+            sub: *reset_id,
+            exp: expires_at.timestamp(),
+            iat: Utc::now().timestamp(),
+        };
+
+        encode(&Header::default(), &claims, &EncodingKey::from_secret(self.token_secret.as_ref()))
+            .map_err(|_| AppError::Internal("An error occurred while generating the password reset token".to_string()))
+    }
+
+    /// Link holding the token of the pending reset mailed by the given mail, rendered by the outbox when sending it.
+    /// None when the reset was replaced, revoked or has expired meanwhile.
+    pub async fn mail_link<'e, E: PgExecutor<'e>>(&self, executor: E, mail_id: &Uuid) -> Result<Option<String>, AppError> {
+        let reset = sqlx::query!(
+            "SELECT pk_driver_password_reset_id, expires_at FROM driver_password_resets WHERE fk_driver_mail_id = $1 AND method = 'LINK' AND status = 'PENDING' AND expires_at > NOW()",
+            mail_id
+        )
+        .fetch_optional(executor)
+        .await?;
+
+        let Some(reset) = reset else {
+            return Ok(None);
+        };
+
+        let token = self.generate_token(&reset.pk_driver_password_reset_id, reset.expires_at)?;
+        Ok(Some(format!("{}?token={}", self.reset_url, token)))
+    }
+
+    fn reset_mail(&self, driver: &Driver) -> (String, String) {
+        match driver.language.as_str() {
+            "fr" => (
+                "Réinitialisez votre mot de passe".to_string(),
+                format!(
+                    "Bonjour {},\n\nChoisissez un nouveau mot de passe en ouvrant ce lien, valable {} heures :\n{}\n\nSi vous n'êtes pas à l'origine de cette demande, ignorez ce message, votre mot de passe actuel reste valable.",
+                    driver.firstname, self.duration_hours, MAIL_LINK_PLACEHOLDER
+                ),
+            ),
+            _ => (
+                "Reset your password".to_string(),
+                format!(
+                    "Hello {},\n\nChoose a new password by opening this link, valid for {} hours:\n{}\n\nIf you did not request it, ignore this message, your current password keeps working.",
+                    driver.firstname, self.duration_hours, MAIL_LINK_PLACEHOLDER
+                ),
+            ),
+        }
+    }
+
+    /// Replace the pending reset of the driver, if any, by a new one, a replaced temporary password stops working.
+    /// A temporary password is returned once and replaces the current password straight away
+    pub async fn request_reset(&self, driver_id: &Uuid, method: &DriverPasswordResetMethod, author_id: &Uuid) -> Result<CreatedDriverPasswordReset, AppError> {
+        let driver = self.driver_service.get_driver_by_id(driver_id).await?;
+        // covers the erased drivers as well
+        if driver.deactivated_at.is_some() {
+            return Err(AppError::Conflict("The driver is deactivated".to_string(), "DRIVER_DEACTIVATED".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let replaced_resets = sqlx::query!(
+            r#"
+            UPDATE driver_password_resets SET
+                status = CASE WHEN expires_at <= NOW() THEN 'EXPIRED' ELSE 'REVOKED' END,
+                revoked_at = CASE WHEN expires_at <= NOW() THEN NULL ELSE NOW() END,
+                fk_revoking_employee_id = CASE WHEN expires_at <= NOW() THEN NULL ELSE $1::UUID END
+            WHERE fk_driver_id = $2 AND status = 'PENDING'
+            RETURNING pk_driver_password_reset_id, method
+            "#,
+            author_id,
+            driver_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // a new temporary password overwrites the replaced one anyway
+        let replaces_temporary_password = replaced_resets.iter().any(|replaced| replaced.method == DriverPasswordResetMethod::TEMPORARY_PASSWORD.as_str());
+        if replaces_temporary_password && *method == DriverPasswordResetMethod::LINK {
+            invalidate_password(&mut tx, driver_id).await?;
+        }
+        let replaced_reset_ids: Vec<Uuid> = replaced_resets.iter().map(|replaced| replaced.pk_driver_password_reset_id).collect();
+
+        let expires_at = Utc::now() + Duration::hours(self.duration_hours);
+        let reset = sqlx::query_as::<_, DriverPasswordReset>(&format!(
+            r#"
+            INSERT INTO driver_password_resets (fk_driver_id, method, expires_at, fk_requesting_employee_id)
+            VALUES ($1, $2, $3, $4)
+            RETURNING {}
+            "#,
+            RESET_COLUMNS
+        ))
+        .bind(driver_id)
+        .bind(method.as_str())
+        .bind(expires_at)
+        .bind(author_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let (reset, temporary_password) = match method {
+            DriverPasswordResetMethod::LINK => {
+                let (subject, body) = self.reset_mail(&driver);
+                let mail = queue_driver_mail(&mut *tx, driver_id, &DriverMailTemplate::PASSWORD_RESET, &subject, &body, author_id).await?;
+
+                let reset = sqlx::query_as::<_, DriverPasswordReset>(&format!(
+                    "UPDATE driver_password_resets SET fk_driver_mail_id = $1 WHERE pk_driver_password_reset_id = $2 RETURNING {}",
+                    RESET_COLUMNS
+                ))
+                .bind(mail.pk_driver_mail_id)
+                .bind(reset.pk_driver_password_reset_id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+                (reset, None)
+            }
+            DriverPasswordResetMethod::TEMPORARY_PASSWORD => {
+                let temporary_password = generate_temporary_password();
+                sqlx::query!(
+                    "UPDATE \"drivers\" SET password_hash = $1 WHERE pk_driver_id = $2",
+                    hash_password(&temporary_password)?,
+                    driver_id
+                )
+                .execute(&mut *tx)
+                .await?;
+
+                (reset, Some(temporary_password))
+            }
+        };
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 3,
+            entity_id: Some(*driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "REQUEST_DRIVER_PASSWORD_RESET",
+                "reset_id": reset.pk_driver_password_reset_id,
+                "method": reset.method,
+                "mail_id": reset.fk_driver_mail_id,
+                "replaced_reset_ids": replaced_reset_ids,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(CreatedDriverPasswordReset { reset, temporary_password })
+    }
+
+    /// The link or the temporary password stops being accepted, a temporary password is not restored to the previous one
+    /// but invalidated
+    pub async fn revoke_reset(&self, reset_id: &Uuid, author_id: &Uuid) -> Result<DriverPasswordReset, AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let reset = sqlx::query_as::<_, DriverPasswordReset>(&format!(
+            r#"
+            UPDATE driver_password_resets SET
+                status = 'REVOKED',
+                revoked_at = NOW(),
+                fk_revoking_employee_id = $1
+            WHERE pk_driver_password_reset_id = $2 AND status = 'PENDING' AND expires_at > NOW()
+            RETURNING {}
+            "#,
+            RESET_COLUMNS
+        ))
+        .bind(author_id)
+        .bind(reset_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(reset) = reset else {
+            // tell a missing reset apart from a processed one
+            self.get_reset_by_id(reset_id).await?;
+            return Err(AppError::Conflict("The password reset is not pending anymore".to_string(), "DRIVER_PASSWORD_RESET_NOT_PENDING".to_string()));
+        };
+
+        if reset.method == DriverPasswordResetMethod::TEMPORARY_PASSWORD.as_str() {
+            invalidate_password(&mut tx, &reset.fk_driver_id).await?;
+        }
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 3,
+            entity_id: Some(reset.fk_driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "REVOKE_DRIVER_PASSWORD_RESET",
+                "reset_id": reset_id,
+                "method": reset.method,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(reset)
+    }
+
+    /// Called by the driver with the token received by mail
+    pub async fn confirm_reset(&self, token: &str, password: &str) -> Result<DriverPasswordReset, AppError> {
+        let claims = decode::<DriverPasswordResetClaims>(
+            token,
+            &DecodingKey::from_secret(self.token_secret.as_ref()),
+            &Validation::default(),
+        )
+        .map_err(|_| AppError::Validation("The password reset token is not valid or has expired".to_string()))?
+        .claims;
+
+        let password_hash = hash_password(password)?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let reset = sqlx::query_as::<_, DriverPasswordReset>(&format!(
+            r#"
+            UPDATE driver_password_resets SET status = 'COMPLETED', completed_at = NOW()
+            WHERE pk_driver_password_reset_id = $1 AND method = 'LINK' AND status = 'PENDING' AND expires_at > NOW()
+            -- a deactivated or erased driver cannot get a password back
+            AND EXISTS (SELECT 1 FROM drivers WHERE pk_driver_id = fk_driver_id AND deactivated_at IS NULL)
+            RETURNING {}
+            "#,
+            RESET_COLUMNS
+        ))
+        .bind(claims.sub)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("The password reset is not pending anymore".to_string(), "DRIVER_PASSWORD_RESET_NOT_PENDING".to_string()))?;
+
+        sqlx::query!(
+            "UPDATE \"drivers\" SET password_hash = $1 WHERE pk_driver_id = $2",
+            password_hash,
+            reset.fk_driver_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(reset)
+    }
+
+    /// Called by the driver application when the driver logs in with a temporary password
+    pub async fn change_temporary_password(&self, email: &str, temporary_password: &str, password: &str) -> Result<DriverPasswordReset, AppError> {
+        let invalid_credentials = || AppError::Validation("Invalid email or temporary password".to_string());
+
+        if password == temporary_password {
+            return Err(AppError::Validation("The new password must differ from the temporary one".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let pending = sqlx::query!(
+            r#"
+            SELECT r.pk_driver_password_reset_id, d.password_hash
+            FROM driver_password_resets r
+            JOIN drivers d ON d.pk_driver_id = r.fk_driver_id
+            WHERE d.email = $1 AND d.deactivated_at IS NULL AND r.method = 'TEMPORARY_PASSWORD' AND r.status = 'PENDING' AND r.expires_at > NOW()
+            FOR UPDATE OF r
+            "#,
+            email
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(invalid_credentials)?;
+
+        if !verify(temporary_password, &pending.password_hash).map_err(|_| invalid_credentials())? {
+            return Err(invalid_credentials());
+        }
+
+        let reset = sqlx::query_as::<_, DriverPasswordReset>(&format!(
+            "UPDATE driver_password_resets SET status = 'COMPLETED', completed_at = NOW() WHERE pk_driver_password_reset_id = $1 RETURNING {}",
+            RESET_COLUMNS
+        ))
+        .bind(pending.pk_driver_password_reset_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            "UPDATE \"drivers\" SET password_hash = $1 WHERE pk_driver_id = $2",
+            hash_password(password)?,
+            reset.fk_driver_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(reset)
+    }
+
+    /// Invalidate the temporary passwords not changed in time, recorded under the system employee
+    pub async fn expire_temporary_passwords(&self) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+
+        let expired = sqlx::query!(
+            r#"
+            UPDATE driver_password_resets SET status = 'EXPIRED'
+            WHERE method = 'TEMPORARY_PASSWORD' AND status = 'PENDING' AND expires_at <= NOW()
+            RETURNING pk_driver_password_reset_id, fk_driver_id
+            "#
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for reset in &expired {
+            invalidate_password(&mut tx, &reset.fk_driver_id).await?;
+
+            record_action(&mut *tx, &NewActionHistory {
+                employee_id: SYSTEM_EMPLOYEE_ID,
+                authorization_type_id: 3,
+                entity_id: Some(reset.fk_driver_id),
+                entity_type: EntityType::DRIVER,
+                description: serde_json::json!({
+                    "action": "EXPIRE_DRIVER_PASSWORD_RESET",
+                    "reset_id": reset.pk_driver_password_reset_id,
+                    "method": DriverPasswordResetMethod::TEMPORARY_PASSWORD.as_str(),
+                    "automatic": true,
+                }),
+            }).await?;
+        }
+
+        tx.commit().await?;
+
+        if !expired.is_empty() {
+            info!("{} expired driver temporary password(s) invalidated", expired.len());
+        }
+
+        Ok(())
+    }
+}
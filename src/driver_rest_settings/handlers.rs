@@ -0,0 +1,59 @@
+use axum::{
+    body::Bytes,
+    extract::{Path, State},
+    http::{header, HeaderMap, StatusCode},
+    Extension,
+    Json,
+};
+use serde_json::Value;
+use std::sync::Arc;
+
+use crate::{
+    driver_rest_settings::{models::{DriverRestSettings, DriverRestSettingsPatch}, services::DriverRestSettingsService}, errors::app_error::AppError, middleware::AuthState, models::path::parse_uuid
+};
+
+pub async fn get_rest_settings(
+    Path(driver_id): Path<String>,
+    State(settings_service): State<Arc<DriverRestSettingsService>>,
+) -> Result<Json<DriverRestSettings>, AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    let settings = settings_service.get_settings(&driver_uuid).await?;
+    Ok(Json(settings))
+}
+
+pub async fn replace_rest_settings(
+    Path(driver_id): Path<String>,
+    State(settings_service): State<Arc<DriverRestSettingsService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(settings): Json<Value>,
+) -> Result<Json<DriverRestSettings>, AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    let settings = settings_service.replace_settings(&driver_uuid, settings, &auth_state.employee_id).await?;
+    Ok(Json(settings))
+}
+
+/// RFC 7396 merge patch or RFC 6902 JSON patch, selected by the content type
+pub async fn patch_rest_settings(
+    Path(driver_id): Path<String>,
+    State(settings_service): State<Arc<DriverRestSettingsService>>,
+    Extension(auth_state): Extension<AuthState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Json<DriverRestSettings>, AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    let content_type = headers.get(header::CONTENT_TYPE).and_then(|value| value.to_str().ok());
+    let patch = DriverRestSettingsPatch::from_body(content_type, &body)?;
+
+    let settings = settings_service.patch_settings(&driver_uuid, &patch, &auth_state.employee_id).await?;
+    Ok(Json(settings))
+}
+
+pub async fn delete_rest_settings(
+    Path(driver_id): Path<String>,
+    State(settings_service): State<Arc<DriverRestSettingsService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<StatusCode, AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    settings_service.delete_settings(&driver_uuid, &auth_state.employee_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
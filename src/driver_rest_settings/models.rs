@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use validator::{Validate, ValidationError};
+
+use crate::errors::app_error::AppError;
+
+/// Version written in `drivers.rest_json` by this API
+pub const DRIVER_REST_SETTINGS_VERSION: u32 = 2;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub enum RestDay {
+    MONDAY,
+    TUESDAY,
+    WEDNESDAY,
+    THURSDAY,
+    FRIDAY,
+    SATURDAY,
+    SUNDAY,
+}
+
+/// Rest settings of a driver, stored in `drivers.rest_json` and shared with the mobile application.
+/// The missing fields take the default values, based on the EU driving time rules.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Validate)]
+#[serde(default, deny_unknown_fields)]
+pub struct DriverRestSettings {
+    pub version: u32,
+    /// Minimum rest between two workdays
+    #[validate(range(min = 540, max = 1440, message = "Daily rest must be between 540 and 1440 minutes"))]
+    pub daily_rest_minutes: u32,
+    #[validate(range(min = 1440, max = 10080, message = "Weekly rest must be between 1440 and 10080 minutes"))]
+    pub weekly_rest_minutes: u32,
+    /// Driving time after which a break is due
+    #[validate(range(min = 60, max = 600, message = "Break delay must be between 60 and 600 minutes"))]
+    pub break_after_minutes: u32,
+    #[validate(range(min = 15, max = 120, message = "Break must be between 15 and 120 minutes"))]
+    pub break_minutes: u32,
+    /// Days the driver does not work
+    #[validate(custom(function = "validate_rest_days"))]
+    pub rest_days: Vec<RestDay>,
+    /// The mobile application reminds the driver when a break is due
+    pub break_reminders: bool,
+}
+
+impl Default for DriverRestSettings {
+    fn default() -> Self {
+        Self {
+            version: DRIVER_REST_SETTINGS_VERSION,
+            daily_rest_minutes: 11 * 60,
+            weekly_rest_minutes: 45 * 60,
+            break_after_minutes: 270,
+            break_minutes: 45,
+            rest_days: Vec::new(),
+            break_reminders: false,
+        }
+    }
+}
+
+fn validate_rest_days(rest_days: &[RestDay]) -> Result<(), ValidationError> {
+    let mut seen = Vec::with_capacity(rest_days.len());
+    for day in rest_days {
+        if seen.contains(day) {
+            return Err(ValidationError::new("rest_days").with_message("Rest days cannot contain a day twice".into()));
+        }
+        seen.push(*day);
+    }
+    Ok(())
+}
+
+/// Unversioned shape written before the settings were typed, durations in hours
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DriverRestSettingsV1 {
+    daily_rest_hours: Option<f64>,
+    weekly_rest_hours: Option<f64>,
+    break_after_hours: Option<f64>,
+    break_minutes: Option<u32>,
+    rest_days: Option<Vec<RestDay>>,
+    break_reminders: Option<bool>,
+}
+
+impl From<DriverRestSettingsV1> for DriverRestSettings {
+    fn from(v1: DriverRestSettingsV1) -> Self {
+        let defaults = DriverRestSettings::default();
+        let minutes = |hours: Option<f64>, default: u32| hours.map(|hours| (hours * 60.0).round().max(0.0) as u32).unwrap_or(default);
+
+        Self {
+            version: DRIVER_REST_SETTINGS_VERSION,
+            daily_rest_minutes: minutes(v1.daily_rest_hours, defaults.daily_rest_minutes),
+            weekly_rest_minutes: minutes(v1.weekly_rest_hours, defaults.weekly_rest_minutes),
+            break_after_minutes: minutes(v1.break_after_hours, defaults.break_after_minutes),
+            break_minutes: v1.break_minutes.unwrap_or(defaults.break_minutes),
+            rest_days: v1.rest_days.unwrap_or(defaults.rest_days),
+            break_reminders: v1.break_reminders.unwrap_or(defaults.break_reminders),
+        }
+    }
+}
+
+impl DriverRestSettings {
+    /// Read any known version of the settings, upgraded to the current one and validated
+    pub fn parse(value: Value) -> Result<Self, AppError> {
+        if !value.is_object() {
+            return Err(AppError::Validation("Rest settings must be a JSON object".to_string()));
+        }
+
+        let settings = match value.get("version") {
+            None => serde_json::from_value::<DriverRestSettingsV1>(value).map(DriverRestSettings::from),
+            Some(version) if version.as_u64() == Some(DRIVER_REST_SETTINGS_VERSION as u64) => serde_json::from_value::<DriverRestSettings>(value),
+            Some(version) => return Err(AppError::Validation(format!("Rest settings version {} is not supported", version))),
+        }
+        .map_err(|e| AppError::Validation(format!("The rest settings are not valid: {}", e)))?;
+
+        settings.validate()
+            .map_err(|e| AppError::Validation(format!("The rest settings are not valid: {}", e)))?;
+
+        Ok(settings)
+    }
+
+    pub fn to_value(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+}
+
+/// The two partial edit formats accepted by `PATCH /drivers/{id}/rest-settings`
+#[derive(Debug)]
+pub enum DriverRestSettingsPatch {
+    /// RFC 7396, `application/merge-patch+json`
+    Merge(Value),
+    /// RFC 6902, `application/json-patch+json`
+    Json(json_patch::Patch),
+}
+
+impl DriverRestSettingsPatch {
+    pub const MERGE_CONTENT_TYPE: &'static str = "application/merge-patch+json";
+    pub const JSON_CONTENT_TYPE: &'static str = "application/json-patch+json";
+
+    pub fn from_body(content_type: Option<&str>, body: &[u8]) -> Result<Self, AppError> {
+        let media_type = content_type
+            .and_then(|content_type| content_type.split(';').next())
+            .map(|media_type| media_type.trim().to_ascii_lowercase());
+
+        match media_type.as_deref() {
+            Some(Self::MERGE_CONTENT_TYPE) => Ok(DriverRestSettingsPatch::Merge(serde_json::from_slice(body)?)),
+            Some(Self::JSON_CONTENT_TYPE) => Ok(DriverRestSettingsPatch::Json(serde_json::from_slice(body)?)),
+            _ => Err(AppError::Validation(format!("Content type must be {} or {}", Self::MERGE_CONTENT_TYPE, Self::JSON_CONTENT_TYPE))),
+        }
+    }
+
+    /// Apply the patch to the current settings, the result is parsed like a full replacement
+    pub fn apply(&self, current: &DriverRestSettings) -> Result<DriverRestSettings, AppError> {
+        let mut document = current.to_value();
+        match self {
+            DriverRestSettingsPatch::Merge(patch) => json_patch::merge(&mut document, patch),
+            DriverRestSettingsPatch::Json(patch) => json_patch::patch(&mut document, patch)
+                .map_err(|e| AppError::Validation(format!("The JSON patch cannot be applied: {}", e)))?,
+        }
+        DriverRestSettings::parse(document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_upgrades_the_unversioned_shape() {
+        let settings = DriverRestSettings::parse(json!({ "daily_rest_hours": 9, "break_after_hours": 4.5, "rest_days": ["SUNDAY"] })).unwrap();
+        assert_eq!(settings.version, DRIVER_REST_SETTINGS_VERSION);
+        assert_eq!(settings.daily_rest_minutes, 540);
+        assert_eq!(settings.break_after_minutes, 270);
+        assert_eq!(settings.weekly_rest_minutes, DriverRestSettings::default().weekly_rest_minutes);
+        assert_eq!(settings.rest_days, vec![RestDay::SUNDAY]);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_settings() {
+        assert!(DriverRestSettings::parse(json!([])).is_err());
+        assert!(DriverRestSettings::parse(json!({ "version": 3 })).is_err());
+        assert!(DriverRestSettings::parse(json!({ "version": 2, "unknown": true })).is_err());
+        assert!(DriverRestSettings::parse(json!({ "version": 2, "break_minutes": 5 })).is_err());
+        assert!(DriverRestSettings::parse(json!({ "version": 2, "rest_days": ["MONDAY", "MONDAY"] })).is_err());
+    }
+
+    #[test]
+    fn test_patches_keep_the_other_fields() {
+        let current = DriverRestSettings { break_reminders: true, ..DriverRestSettings::default() };
+
+        let merge = DriverRestSettingsPatch::from_body(Some("application/merge-patch+json"), br#"{ "break_minutes": 30 }"#).unwrap();
+        let merged = merge.apply(&current).unwrap();
+        assert_eq!(merged.break_minutes, 30);
+        assert!(merged.break_reminders);
+
+        let patch = DriverRestSettingsPatch::from_body(
+            Some("application/json-patch+json; charset=utf-8"),
+            br#"[{ "op": "add", "path": "/rest_days/-", "value": "SATURDAY" }, { "op": "test", "path": "/break_reminders", "value": true }]"#,
+        ).unwrap();
+        let patched = patch.apply(&current).unwrap();
+        assert_eq!(patched.rest_days, vec![RestDay::SATURDAY]);
+        assert!(patched.break_reminders);
+
+        let failing = DriverRestSettingsPatch::from_body(Some("application/json-patch+json"), br#"[{ "op": "test", "path": "/break_minutes", "value": 1 }]"#).unwrap();
+        assert!(failing.apply(&current).is_err());
+
+        assert!(DriverRestSettingsPatch::from_body(Some("application/json"), b"{}").is_err());
+    }
+}
@@ -0,0 +1,21 @@
+use axum::{
+    middleware::{from_fn, from_fn_with_state}, routing::{get, put}, Router
+};
+use crate::{
+    driver_rest_settings::{handlers::{delete_rest_settings, get_rest_settings, patch_rest_settings, replace_rest_settings}, services::DriverRestSettingsService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+};
+use std::sync::Arc;
+
+pub fn protected_driver_rest_settings_routes(
+    middleware_state: MiddlewareState,
+    settings_service: Arc<DriverRestSettingsService>,
+) -> Router {
+    Router::new()
+        .route("/drivers/{id}/rest-settings", get(get_rest_settings).route_layer(from_fn(with_required_permissions(vec![1]))))
+        .route("/drivers/{id}/rest-settings", put(replace_rest_settings).patch(patch_rest_settings).delete(delete_rest_settings).route_layer(from_fn(with_required_permissions(vec![3]))))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(settings_service.clone())
+}
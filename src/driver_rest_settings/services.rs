@@ -0,0 +1,127 @@
+use serde_json::Value;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    driver_erasure::services::is_driver_erased, driver_rest_settings::models::{DriverRestSettings, DriverRestSettingsPatch}, employee::models::EntityType, errors::app_error::AppError, history::{models::{FieldChanges, NewActionHistory}, services::record_action}
+};
+
+/// Settings stored for a driver, upgraded to the current version
+fn stored_settings(rest_json: Option<Value>) -> Result<Option<DriverRestSettings>, AppError> {
+    match rest_json {
+        None | Some(Value::Null) => Ok(None),
+        Some(value) => DriverRestSettings::parse(value)
+            .map(Some)
+            .map_err(|_| AppError::Conflict("The stored rest settings are not valid, they must be replaced".to_string(), "DRIVER_REST_SETTINGS_INVALID".to_string())),
+    }
+}
+
+async fn store_settings(
+    mut tx: Transaction<'_, Postgres>,
+    driver_id: &Uuid,
+    old_value: &Value,
+    new: Option<&DriverRestSettings>,
+    author_id: &Uuid,
+) -> Result<(), AppError> {
+    let new_value = new.map(DriverRestSettings::to_value).unwrap_or(Value::Null);
+
+    if *old_value == new_value {
+        return Ok(());
+    }
+
+    let mut changes = FieldChanges::new();
+    let fields = old_value.as_object().into_iter().chain(new_value.as_object()).flat_map(|fields| fields.keys());
+    for field in fields {
+        changes.track(field, old_value.get(field).unwrap_or(&Value::Null), Some(new_value.get(field).unwrap_or(&Value::Null)));
+    }
+    // the stored value was not an object
+    if changes.is_empty() {
+        changes.track("rest_json", old_value, Some(&new_value));
+    }
+
+    sqlx::query!(
+        "UPDATE \"drivers\" SET rest_json = $1 WHERE pk_driver_id = $2",
+        new.map(|_| &new_value),
+        driver_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    record_action(&mut *tx, &NewActionHistory {
+        employee_id: *author_id,
+        authorization_type_id: 3,
+        entity_id: Some(*driver_id),
+        entity_type: EntityType::DRIVER,
+        description: serde_json::json!({
+            "action": "UPDATE_DRIVER_REST_SETTINGS",
+            "changes": changes.into_value(),
+        }),
+    }).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub struct DriverRestSettingsService {
+    pool: PgPool,
+}
+
+impl DriverRestSettingsService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_settings(&self, driver_id: &Uuid) -> Result<DriverRestSettings, AppError> {
+        let rest_json = sqlx::query_scalar!("SELECT rest_json FROM \"drivers\" WHERE pk_driver_id = $1", driver_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Driver not found".to_string()))?;
+
+        stored_settings(rest_json)?
+            .ok_or_else(|| AppError::NotFound("Driver has no rest settings".to_string()))
+    }
+
+    // Lock the driver row, the mobile application may write the settings meanwhile
+    async fn lock_settings(&self, tx: &mut Transaction<'_, Postgres>, driver_id: &Uuid) -> Result<Value, AppError> {
+        let rest_json = sqlx::query_scalar!("SELECT rest_json FROM \"drivers\" WHERE pk_driver_id = $1 FOR UPDATE", driver_id)
+            .fetch_optional(&mut **tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Driver not found".to_string()))?;
+
+        if is_driver_erased(&mut **tx, driver_id).await? {
+            return Err(AppError::Conflict("The personal data of this driver has been erased".to_string(), "DRIVER_ERASED".to_string()));
+        }
+
+        Ok(rest_json.unwrap_or(Value::Null))
+    }
+
+    // Replace the settings wholesale, any known version is accepted and upgraded
+    pub async fn replace_settings(&self, driver_id: &Uuid, value: Value, author_id: &Uuid) -> Result<DriverRestSettings, AppError> {
+        let settings = DriverRestSettings::parse(value)?;
+
+        let mut tx = self.pool.begin().await?;
+        let stored = self.lock_settings(&mut tx, driver_id).await?;
+        store_settings(tx, driver_id, &stored, Some(&settings), author_id).await?;
+
+        Ok(settings)
+    }
+
+    // Change some fields only, a driver without settings is patched from the default ones
+    pub async fn patch_settings(&self, driver_id: &Uuid, patch: &DriverRestSettingsPatch, author_id: &Uuid) -> Result<DriverRestSettings, AppError> {
+        let mut tx = self.pool.begin().await?;
+        let stored = self.lock_settings(&mut tx, driver_id).await?;
+        let current = stored_settings(Some(stored.clone()))?.unwrap_or_default();
+
+        let settings = patch.apply(&current)?;
+        store_settings(tx, driver_id, &stored, Some(&settings), author_id).await?;
+
+        Ok(settings)
+    }
+
+    pub async fn delete_settings(&self, driver_id: &Uuid, author_id: &Uuid) -> Result<(), AppError> {
+        let mut tx = self.pool.begin().await?;
+        let stored = self.lock_settings(&mut tx, driver_id).await?;
+        store_settings(tx, driver_id, &stored, None, author_id).await
+    }
+}
@@ -0,0 +1,109 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    driver_suspension::{models::{CreateDriverSuspensionRequest, DriverSuspension, DriverSuspensionStatus, GetAllDriverSuspensionsQuery, LiftDriverSuspensionRequest, UpdateDriverSuspensionRequest}, services::DriverSuspensionService}, errors::app_error::AppError, middleware::AuthState, models::{paginate::{PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}, path::parse_uuid}
+};
+
+fn validate_request<T: Validate>(req: &T) -> Result<(), AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(format!("The request content is not valid: {}", e)))
+}
+
+async fn paginated_suspensions(
+    driver_id: Option<&Uuid>,
+    filters: &GetAllDriverSuspensionsQuery,
+    suspension_service: &DriverSuspensionService,
+) -> Result<Json<PaginatedResponse<DriverSuspension>>, AppError> {
+    if filters.page == 0 {
+        return Err(AppError::Validation("Page must be greater than 0".to_string()));
+    }
+    if filters.limit == 0 || filters.limit > PAGINATE_MAX_LIMIT {
+        return Err(AppError::Validation(format!("Limit must be between 1 and {}.", PAGINATE_MAX_LIMIT)));
+    }
+    if filters.status.as_deref().is_some_and(|status| status.parse::<DriverSuspensionStatus>().is_err()) {
+        return Err(AppError::Validation("Status must be UPCOMING, ACTIVE or EXPIRED".to_string()));
+    }
+
+    let (suspensions, total) = suspension_service.get_all_suspensions(driver_id, filters).await?;
+
+    Ok(Json(PaginatedResponse {
+        data: suspensions,
+        pagination: PaginationInfo {
+            page: filters.page,
+            limit: filters.limit,
+            total,
+        },
+    }))
+}
+
+pub async fn get_all_suspensions(
+    Query(filters): Query<GetAllDriverSuspensionsQuery>,
+    State(suspension_service): State<Arc<DriverSuspensionService>>,
+) -> Result<Json<PaginatedResponse<DriverSuspension>>, AppError> {
+    paginated_suspensions(None, &filters, &suspension_service).await
+}
+
+pub async fn get_driver_suspensions(
+    Path(driver_id): Path<String>,
+    Query(filters): Query<GetAllDriverSuspensionsQuery>,
+    State(suspension_service): State<Arc<DriverSuspensionService>>,
+) -> Result<Json<PaginatedResponse<DriverSuspension>>, AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    paginated_suspensions(Some(&driver_uuid), &filters, &suspension_service).await
+}
+
+pub async fn get_suspension_by_id(
+    Path(suspension_id): Path<String>,
+    State(suspension_service): State<Arc<DriverSuspensionService>>,
+) -> Result<Json<DriverSuspension>, AppError> {
+    let suspension_uuid = parse_uuid(&suspension_id, "Driver suspension")?;
+    let suspension = suspension_service.get_suspension_by_id(&suspension_uuid).await?;
+    Ok(Json(suspension))
+}
+
+pub async fn create_suspension(
+    Path(driver_id): Path<String>,
+    State(suspension_service): State<Arc<DriverSuspensionService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<CreateDriverSuspensionRequest>,
+) -> Result<(StatusCode, Json<DriverSuspension>), AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    validate_request(&create_req)?;
+
+    let suspension = suspension_service.create_suspension(&driver_uuid, &create_req, &auth_state.employee_id).await?;
+    Ok((StatusCode::CREATED, Json(suspension)))
+}
+
+pub async fn update_suspension(
+    Path(suspension_id): Path<String>,
+    State(suspension_service): State<Arc<DriverSuspensionService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(update_req): Json<UpdateDriverSuspensionRequest>,
+) -> Result<Json<DriverSuspension>, AppError> {
+    let suspension_uuid = parse_uuid(&suspension_id, "Driver suspension")?;
+    validate_request(&update_req)?;
+
+    let suspension = suspension_service.update_suspension(&suspension_uuid, &update_req, &auth_state.employee_id).await?;
+    Ok(Json(suspension))
+}
+
+pub async fn lift_suspension(
+    Path(suspension_id): Path<String>,
+    State(suspension_service): State<Arc<DriverSuspensionService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(lift_req): Json<LiftDriverSuspensionRequest>,
+) -> Result<Json<DriverSuspension>, AppError> {
+    let suspension_uuid = parse_uuid(&suspension_id, "Driver suspension")?;
+    validate_request(&lift_req)?;
+
+    let suspension = suspension_service.lift_suspension(&suspension_uuid, &lift_req, &auth_state.employee_id).await?;
+    Ok(Json(suspension))
+}
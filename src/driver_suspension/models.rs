@@ -0,0 +1,86 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{nullable::nullable, paginate::{default_limit, default_page, default_sort_order}};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DriverSuspension {
+    pub pk_driver_suspension_id: Uuid,
+    pub fk_driver_id: Uuid,
+    pub fk_suspending_employee_id: Uuid,
+    pub reason: String,
+    pub start_at: DateTime<Utc>,
+    pub end_at: Option<DateTime<Utc>>,
+    /// Set to `end_at` when the suspension was lifted automatically
+    pub lifted_at: Option<DateTime<Utc>>,
+    /// None when the suspension was lifted automatically
+    pub fk_lifting_employee_id: Option<Uuid>,
+    pub lift_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum DriverSuspensionStatus {
+    /// Not started yet
+    UPCOMING,
+    ACTIVE,
+    /// Lifted or past its end
+    EXPIRED,
+}
+
+impl FromStr for DriverSuspensionStatus {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "UPCOMING" => Ok(DriverSuspensionStatus::UPCOMING),
+            "ACTIVE" => Ok(DriverSuspensionStatus::ACTIVE),
+            "EXPIRED" => Ok(DriverSuspensionStatus::EXPIRED),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateDriverSuspensionRequest {
+    #[validate(length(min = 1, max = 1000, message = "Reason is required and cannot be longer than 1000 characters"))]
+    pub reason: String,
+
+    /// Defaults to now
+    pub start_at: Option<DateTime<Utc>>,
+    /// The suspension is lifted automatically at its end, no end means it lasts until it is lifted
+    pub end_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateDriverSuspensionRequest {
+    #[validate(length(min = 1, max = 1000, message = "Reason cannot be empty and cannot be longer than 1000 characters"))]
+    pub reason: Option<String>,
+
+    /// `null` makes the suspension open-ended again
+    #[serde(default, deserialize_with = "nullable")]
+    pub end_at: Option<Option<DateTime<Utc>>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct LiftDriverSuspensionRequest {
+    #[validate(length(min = 1, max = 1000, message = "Lift reason is required and cannot be longer than 1000 characters"))]
+    pub lift_reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetAllDriverSuspensionsQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    /// UPCOMING, ACTIVE or EXPIRED
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+}
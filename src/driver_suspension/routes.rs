@@ -0,0 +1,25 @@
+use axum::{
+    middleware::{from_fn, from_fn_with_state}, routing::{get, post, put}, Router
+};
+use crate::{
+    driver_suspension::{handlers::{create_suspension, get_all_suspensions, get_driver_suspensions, get_suspension_by_id, lift_suspension, update_suspension}, services::DriverSuspensionService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+};
+use std::sync::Arc;
+
+pub fn protected_driver_suspension_routes(
+    middleware_state: MiddlewareState,
+    suspension_service: Arc<DriverSuspensionService>,
+) -> Router {
+    Router::new()
+        .route("/drivers/suspensions", get(get_all_suspensions).route_layer(from_fn(with_required_permissions(vec![16]))))
+        .route("/drivers/suspensions/{id}", get(get_suspension_by_id).route_layer(from_fn(with_required_permissions(vec![16]))))
+        .route("/drivers/suspensions/{id}", put(update_suspension).patch(update_suspension).route_layer(from_fn(with_required_permissions(vec![18]))))
+        .route("/drivers/suspensions/{id}/lift", post(lift_suspension).route_layer(from_fn(with_required_permissions(vec![19]))))
+        .route("/drivers/{id}/suspensions", get(get_driver_suspensions).route_layer(from_fn(with_required_permissions(vec![16]))))
+        .route("/drivers/{id}/suspensions", post(create_suspension).route_layer(from_fn(with_required_permissions(vec![17]))))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(suspension_service.clone())
+}
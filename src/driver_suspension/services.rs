@@ -0,0 +1,282 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    driver::services::DriverService, driver_suspension::models::{CreateDriverSuspensionRequest, DriverSuspension, DriverSuspensionStatus, GetAllDriverSuspensionsQuery, LiftDriverSuspensionRequest, UpdateDriverSuspensionRequest}, employee::models::EntityType, errors::app_error::AppError, history::{models::{FieldChanges, NewActionHistory, SYSTEM_EMPLOYEE_ID}, services::record_action}
+};
+
+pub const SUSPENSION_COLUMNS: &str = "pk_driver_suspension_id, fk_driver_id, fk_suspending_employee_id, reason, start_at, end_at, lifted_at, fk_lifting_employee_id, lift_reason, created_at";
+
+/// SQL condition matching the driver suspensions currently in effect
+pub const ACTIVE_DRIVER_SUSPENSION_CONDITION: &str = "(lifted_at IS NULL AND start_at <= NOW() AND (end_at IS NULL OR end_at > NOW()))";
+
+fn status_condition(status: &DriverSuspensionStatus) -> &'static str {
+    match status {
+        DriverSuspensionStatus::UPCOMING => "(lifted_at IS NULL AND start_at > NOW())",
+        DriverSuspensionStatus::ACTIVE => ACTIVE_DRIVER_SUSPENSION_CONDITION,
+        DriverSuspensionStatus::EXPIRED => "(lifted_at IS NOT NULL OR end_at <= NOW())",
+    }
+}
+
+pub struct DriverSuspensionService {
+    pool: PgPool,
+    driver_service: DriverService,
+}
+
+impl DriverSuspensionService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            driver_service: DriverService::new(pool.clone()),
+            pool,
+        }
+    }
+
+    // Get all suspensions, optionally restricted to one driver
+    pub async fn get_all_suspensions(&self, driver_id: Option<&Uuid>, filters: &GetAllDriverSuspensionsQuery) -> Result<(Vec<DriverSuspension>, u64), AppError> {
+        if let Some(driver_id) = driver_id {
+            self.driver_service.get_driver_by_id(driver_id).await?;
+        }
+
+        let offset = (filters.page - 1) * filters.limit;
+
+        let status = filters.status.as_deref().and_then(|status| status.parse::<DriverSuspensionStatus>().ok());
+        let where_clause = format!(
+            "WHERE ($1::UUID IS NULL OR fk_driver_id = $1) AND {}",
+            status.as_ref().map(status_condition).unwrap_or("TRUE")
+        );
+
+        let total_count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) as count FROM driver_suspensions {}", where_clause))
+            .bind(driver_id)
+            .fetch_one(&self.pool)
+            .await?;
+
+        // Determine sort order (default to ASC for chronological order)
+        let order_direction = match filters.sort_order.to_lowercase().as_str() {
+            "desc" => "DESC",
+            _ => "ASC",
+        };
+
+        let suspensions = sqlx::query_as::<_, DriverSuspension>(&format!(
+            "SELECT {} FROM driver_suspensions {} ORDER BY start_at {} LIMIT $2 OFFSET $3",
+            SUSPENSION_COLUMNS,
+            where_clause,
+            order_direction
+        ))
+        .bind(driver_id)
+        .bind(filters.limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((suspensions, total_count as u64))
+    }
+
+    pub async fn get_suspension_by_id(&self, suspension_id: &Uuid) -> Result<DriverSuspension, AppError> {
+        sqlx::query_as::<_, DriverSuspension>(&format!("SELECT {} FROM driver_suspensions WHERE pk_driver_suspension_id = $1", SUSPENSION_COLUMNS))
+            .bind(suspension_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Driver suspension not found".to_string()))
+    }
+
+    pub async fn create_suspension(&self, driver_id: &Uuid, create_req: &CreateDriverSuspensionRequest, author_id: &Uuid) -> Result<DriverSuspension, AppError> {
+        let driver = self.driver_service.get_driver_by_id(driver_id).await?;
+        if driver.deactivated_at.is_some() {
+            return Err(AppError::Conflict("A deactivated driver cannot be suspended".to_string(), "DRIVER_DEACTIVATED".to_string()));
+        }
+
+        let start_at = create_req.start_at.unwrap_or_else(Utc::now);
+        if create_req.end_at.is_some_and(|end_at| end_at <= start_at) {
+            return Err(AppError::Validation("The suspension end must be after its start".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let suspension = sqlx::query_as::<_, DriverSuspension>(&format!(
+            r#"
+            INSERT INTO driver_suspensions (fk_driver_id, fk_suspending_employee_id, reason, start_at, end_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING {}
+            "#,
+            SUSPENSION_COLUMNS
+        ))
+        .bind(driver_id)
+        .bind(author_id)
+        .bind(&create_req.reason)
+        .bind(start_at)
+        .bind(create_req.end_at)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 17,
+            entity_id: Some(*driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "SUSPEND_DRIVER",
+                "suspension_id": suspension.pk_driver_suspension_id,
+                "reason": suspension.reason,
+                "start_at": suspension.start_at,
+                "end_at": suspension.end_at,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(suspension)
+    }
+
+    pub async fn update_suspension(&self, suspension_id: &Uuid, update_req: &UpdateDriverSuspensionRequest, author_id: &Uuid) -> Result<DriverSuspension, AppError> {
+        let existing = self.get_suspension_by_id(suspension_id).await?;
+        if existing.lifted_at.is_some() {
+            return Err(AppError::Conflict("A lifted suspension cannot be modified".to_string(), "DRIVER_SUSPENSION_LIFTED".to_string()));
+        }
+
+        if update_req.end_at.flatten().is_some_and(|end_at| end_at <= existing.start_at) {
+            return Err(AppError::Validation("The suspension end must be after its start".to_string()));
+        }
+
+        let mut changes = FieldChanges::new();
+        changes.track("reason", &existing.reason, update_req.reason.as_ref());
+        changes.track("end_at", &existing.end_at, update_req.end_at.as_ref());
+
+        if changes.is_empty() {
+            return Ok(existing);
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // the background task may have lifted it meanwhile
+        let suspension = sqlx::query_as::<_, DriverSuspension>(&format!(
+            r#"
+            UPDATE driver_suspensions SET
+                reason = COALESCE($1, reason),
+                end_at = CASE WHEN $2 THEN $3 ELSE end_at END
+            WHERE pk_driver_suspension_id = $4 AND lifted_at IS NULL
+            RETURNING {}
+            "#,
+            SUSPENSION_COLUMNS
+        ))
+        .bind(&update_req.reason)
+        .bind(update_req.end_at.is_some())
+        .bind(update_req.end_at.flatten())
+        .bind(suspension_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("A lifted suspension cannot be modified".to_string(), "DRIVER_SUSPENSION_LIFTED".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 18,
+            entity_id: Some(suspension.fk_driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "UPDATE_DRIVER_SUSPENSION",
+                "suspension_id": suspension.pk_driver_suspension_id,
+                "changes": changes.into_value(),
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(suspension)
+    }
+
+    // Lift a suspension, the row is kept to preserve the history
+    pub async fn lift_suspension(&self, suspension_id: &Uuid, lift_req: &LiftDriverSuspensionRequest, author_id: &Uuid) -> Result<DriverSuspension, AppError> {
+        let existing = self.get_suspension_by_id(suspension_id).await?;
+        if existing.lifted_at.is_some() {
+            return Err(AppError::Conflict("Driver suspension has already been lifted".to_string(), "DRIVER_SUSPENSION_LIFTED".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let suspension = sqlx::query_as::<_, DriverSuspension>(&format!(
+            r#"
+            UPDATE driver_suspensions SET
+                lifted_at = NOW(),
+                fk_lifting_employee_id = $1,
+                lift_reason = $2
+            WHERE pk_driver_suspension_id = $3 AND lifted_at IS NULL
+            RETURNING {}
+            "#,
+            SUSPENSION_COLUMNS
+        ))
+        .bind(author_id)
+        .bind(&lift_req.lift_reason)
+        .bind(suspension_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Driver suspension has already been lifted".to_string(), "DRIVER_SUSPENSION_LIFTED".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 19,
+            entity_id: Some(suspension.fk_driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "LIFT_DRIVER_SUSPENSION",
+                "suspension_id": suspension.pk_driver_suspension_id,
+                "lift_reason": suspension.lift_reason,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(suspension)
+    }
+
+    // Lift the suspensions whose end has passed, recorded under the system employee
+    pub async fn lift_due_suspensions(&self) -> Result<(), AppError> {
+        let due_suspension_ids = sqlx::query_scalar!(
+            "SELECT pk_driver_suspension_id FROM driver_suspensions WHERE lifted_at IS NULL AND end_at <= NOW()"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for suspension_id in due_suspension_ids {
+            let mut tx = self.pool.begin().await?;
+
+            let lifted = sqlx::query_as::<_, DriverSuspension>(&format!(
+                r#"
+                UPDATE driver_suspensions SET
+                    lifted_at = end_at,
+                    lift_reason = 'Ended automatically'
+                WHERE pk_driver_suspension_id = $1 AND lifted_at IS NULL AND end_at <= NOW()
+                RETURNING {}
+                "#,
+                SUSPENSION_COLUMNS
+            ))
+            .bind(suspension_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            // lifted or extended meanwhile
+            let Some(suspension) = lifted else {
+                continue;
+            };
+
+            record_action(&mut *tx, &NewActionHistory {
+                employee_id: SYSTEM_EMPLOYEE_ID,
+                authorization_type_id: 19,
+                entity_id: Some(suspension.fk_driver_id),
+                entity_type: EntityType::DRIVER,
+                description: serde_json::json!({
+                    "action": "LIFT_DRIVER_SUSPENSION",
+                    "suspension_id": suspension.pk_driver_suspension_id,
+                    "lift_reason": suspension.lift_reason,
+                    "automatic": true,
+                }),
+            }).await?;
+
+            tx.commit().await?;
+
+            info!("Driver suspension {} of driver {} lifted automatically", suspension_id, suspension.fk_driver_id);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::{
+    driver_workday::{models::{CreateDriverWorkdayRequest, DriverWorkday, GetAllDriverWorkdaysQuery, UpdateDriverWorkdayRequest}, services::DriverWorkdayService}, errors::app_error::AppError, middleware::AuthState, models::{paginate::{PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}, path::parse_uuid}
+};
+
+fn validate_request<T: Validate>(req: &T) -> Result<(), AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(format!("The request content is not valid: {}", e)))
+}
+
+pub async fn get_driver_workdays(
+    Path(driver_id): Path<String>,
+    Query(filters): Query<GetAllDriverWorkdaysQuery>,
+    State(workday_service): State<Arc<DriverWorkdayService>>,
+) -> Result<Json<PaginatedResponse<DriverWorkday>>, AppError> {
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    if filters.page == 0 {
+        return Err(AppError::Validation("Page must be greater than 0".to_string()));
+    }
+    if filters.limit == 0 || filters.limit > PAGINATE_MAX_LIMIT {
+        return Err(AppError::Validation(format!("Limit must be between 1 and {}.", PAGINATE_MAX_LIMIT)));
+    }
+    if let (Some(from), Some(to)) = (filters.from, filters.to) {
+        if from > to {
+            return Err(AppError::Validation("The start of the date range must be before its end".to_string()));
+        }
+    }
+
+    let (workdays, total) = workday_service.get_driver_workdays(&driver_uuid, &filters).await?;
+
+    Ok(Json(PaginatedResponse {
+        data: workdays,
+        pagination: PaginationInfo {
+            page: filters.page,
+            limit: filters.limit,
+            total,
+        },
+    }))
+}
+
+pub async fn get_workday_by_id(
+    Path(workday_id): Path<String>,
+    State(workday_service): State<Arc<DriverWorkdayService>>,
+) -> Result<Json<DriverWorkday>, AppError> {
+    let workday_uuid = parse_uuid(&workday_id, "Driver workday")?;
+    let workday = workday_service.get_workday_by_id(&workday_uuid).await?;
+    Ok(Json(workday))
+}
+
+pub async fn create_workday(
+    Path(driver_id): Path<String>,
+    State(workday_service): State<Arc<DriverWorkdayService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<CreateDriverWorkdayRequest>,
+) -> Result<(StatusCode, Json<DriverWorkday>), AppError> {
+    validate_request(&create_req)?;
+    let driver_uuid = parse_uuid(&driver_id, "Driver")?;
+    let workday = workday_service.create_workday(&driver_uuid, &create_req, &auth_state.employee_id).await?;
+    Ok((StatusCode::CREATED, Json(workday)))
+}
+
+pub async fn update_workday(
+    Path(workday_id): Path<String>,
+    State(workday_service): State<Arc<DriverWorkdayService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(update_req): Json<UpdateDriverWorkdayRequest>,
+) -> Result<Json<DriverWorkday>, AppError> {
+    validate_request(&update_req)?;
+    let workday_uuid = parse_uuid(&workday_id, "Driver workday")?;
+    let workday = workday_service.update_workday(&workday_uuid, &update_req, &auth_state.employee_id).await?;
+    Ok(Json(workday))
+}
+
+pub async fn delete_workday(
+    Path(workday_id): Path<String>,
+    State(workday_service): State<Arc<DriverWorkdayService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<StatusCode, AppError> {
+    let workday_uuid = parse_uuid(&workday_id, "Driver workday")?;
+    workday_service.delete_workday(&workday_uuid, &auth_state.employee_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
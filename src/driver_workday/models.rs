@@ -0,0 +1,97 @@
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::paginate::{default_limit, default_page, default_sort_order};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DriverWorkday {
+    pub pk_driver_workday_id: Uuid,
+    pub fk_driver_id: Uuid,
+    pub workday_date: NaiveDate,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    /// The workday ends on the day after `workday_date`
+    pub is_overnight: bool,
+    pub break_minutes: i32,
+    pub rest_minutes: i32,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Minutes between the start and the end of a workday, breaks excluded.
+/// None when the times do not match the overnight flag.
+pub fn worked_minutes(start_time: NaiveTime, end_time: NaiveTime, is_overnight: bool, break_minutes: i32) -> Option<i64> {
+    let minutes = (end_time - start_time).num_minutes();
+    let span = match (is_overnight, minutes > 0) {
+        (false, true) => minutes,
+        (true, false) => minutes + 24 * 60,
+        _ => return None,
+    };
+    Some(span - i64::from(break_minutes))
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateDriverWorkdayRequest {
+    pub workday_date: NaiveDate,
+    pub start_time: NaiveTime,
+    pub end_time: NaiveTime,
+    /// Defaults to false
+    pub is_overnight: Option<bool>,
+    #[validate(range(min = 0, message = "Break minutes cannot be negative"))]
+    pub break_minutes: Option<i32>,
+    #[validate(range(min = 0, message = "Rest minutes cannot be negative"))]
+    pub rest_minutes: Option<i32>,
+    #[validate(length(min = 1, max = 1000, message = "Notes cannot be empty and cannot be longer than 1000 characters"))]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateDriverWorkdayRequest {
+    pub workday_date: Option<NaiveDate>,
+    pub start_time: Option<NaiveTime>,
+    pub end_time: Option<NaiveTime>,
+    pub is_overnight: Option<bool>,
+    #[validate(range(min = 0, message = "Break minutes cannot be negative"))]
+    pub break_minutes: Option<i32>,
+    #[validate(range(min = 0, message = "Rest minutes cannot be negative"))]
+    pub rest_minutes: Option<i32>,
+    #[validate(length(min = 1, max = 1000, message = "Notes cannot be empty and cannot be longer than 1000 characters"))]
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetAllDriverWorkdaysQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    /// First workday date included
+    #[serde(default)]
+    pub from: Option<NaiveDate>,
+    /// Last workday date included
+    #[serde(default)]
+    pub to: Option<NaiveDate>,
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worked_minutes() {
+        let time = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+
+        assert_eq!(worked_minutes(time(8, 0), time(17, 30), false, 30), Some(540));
+        assert_eq!(worked_minutes(time(22, 0), time(6, 0), true, 0), Some(480));
+        // the flag must match the times
+        assert_eq!(worked_minutes(time(22, 0), time(6, 0), false, 0), None);
+        assert_eq!(worked_minutes(time(8, 0), time(17, 0), true, 0), None);
+        assert_eq!(worked_minutes(time(8, 0), time(8, 0), false, 0), None);
+    }
+}
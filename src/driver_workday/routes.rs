@@ -0,0 +1,24 @@
+use axum::{
+    middleware::{from_fn, from_fn_with_state}, routing::{delete, get, post, put}, Router
+};
+use crate::{
+    driver_workday::{handlers::{create_workday, delete_workday, get_driver_workdays, get_workday_by_id, update_workday}, services::DriverWorkdayService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+};
+use std::sync::Arc;
+
+pub fn protected_driver_workday_routes(
+    middleware_state: MiddlewareState,
+    workday_service: Arc<DriverWorkdayService>,
+) -> Router {
+    Router::new()
+        .route("/drivers/workdays/{id}", get(get_workday_by_id).route_layer(from_fn(with_required_permissions(vec![5]))))
+        .route("/drivers/workdays/{id}", put(update_workday).patch(update_workday).route_layer(from_fn(with_required_permissions(vec![7]))))
+        .route("/drivers/workdays/{id}", delete(delete_workday).route_layer(from_fn(with_required_permissions(vec![8]))))
+        .route("/drivers/{id}/workdays", get(get_driver_workdays).route_layer(from_fn(with_required_permissions(vec![5]))))
+        .route("/drivers/{id}/workdays", post(create_workday).route_layer(from_fn(with_required_permissions(vec![6]))))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(workday_service.clone())
+}
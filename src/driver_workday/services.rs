@@ -0,0 +1,262 @@
+use chrono::{NaiveDate, NaiveTime};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    driver::services::DriverService, driver_workday::models::{worked_minutes, CreateDriverWorkdayRequest, DriverWorkday, GetAllDriverWorkdaysQuery, UpdateDriverWorkdayRequest}, employee::models::EntityType, errors::app_error::AppError, history::{models::{FieldChanges, NewActionHistory}, services::record_action}
+};
+
+pub const WORKDAY_COLUMNS: &str = "pk_driver_workday_id, fk_driver_id, workday_date, start_time, end_time, is_overnight, break_minutes, rest_minutes, notes, created_at, updated_at";
+
+pub struct DriverWorkdayService {
+    pool: PgPool,
+    driver_service: DriverService,
+}
+
+impl DriverWorkdayService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            driver_service: DriverService::new(pool.clone()),
+            pool,
+        }
+    }
+
+    pub async fn get_driver_workdays(&self, driver_id: &Uuid, filters: &GetAllDriverWorkdaysQuery) -> Result<(Vec<DriverWorkday>, u64), AppError> {
+        self.driver_service.get_driver_by_id(driver_id).await?;
+
+        let offset = (filters.page - 1) * filters.limit;
+
+        let where_clause = "WHERE fk_driver_id = $1 AND ($2::DATE IS NULL OR workday_date >= $2) AND ($3::DATE IS NULL OR workday_date <= $3)";
+
+        let total_count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) as count FROM driver_workdays {}", where_clause))
+            .bind(driver_id)
+            .bind(filters.from)
+            .bind(filters.to)
+            .fetch_one(&self.pool)
+            .await?;
+
+        // Determine sort order (default to ASC for chronological order)
+        let order_direction = match filters.sort_order.to_lowercase().as_str() {
+            "desc" => "DESC",
+            _ => "ASC",
+        };
+
+        let workdays = sqlx::query_as::<_, DriverWorkday>(&format!(
+            "SELECT {} FROM driver_workdays {} ORDER BY workday_date {} LIMIT $4 OFFSET $5",
+            WORKDAY_COLUMNS,
+            where_clause,
+            order_direction
+        ))
+        .bind(driver_id)
+        .bind(filters.from)
+        .bind(filters.to)
+        .bind(filters.limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((workdays, total_count as u64))
+    }
+
+    pub async fn get_workday_by_id(&self, workday_id: &Uuid) -> Result<DriverWorkday, AppError> {
+        sqlx::query_as::<_, DriverWorkday>(&format!("SELECT {} FROM driver_workdays WHERE pk_driver_workday_id = $1", WORKDAY_COLUMNS))
+            .bind(workday_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Driver workday not found".to_string()))
+    }
+
+    async fn workday_date_exists_except_workday(&self, driver_id: &Uuid, workday_date: &NaiveDate, workday_id: Option<&Uuid>) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM driver_workdays
+                WHERE fk_driver_id = $1 AND workday_date = $2 AND ($3::UUID IS NULL OR pk_driver_workday_id != $3)
+            ) as "exists!"
+            "#,
+            driver_id,
+            workday_date,
+            workday_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    // Check the times and the breaks of a workday once every change is applied
+    fn validate_times(start_time: NaiveTime, end_time: NaiveTime, is_overnight: bool, break_minutes: i32) -> Result<(), AppError> {
+        let worked = worked_minutes(start_time, end_time, is_overnight, break_minutes)
+            .ok_or_else(|| AppError::Validation("The end time must be after the start time, or before it for an overnight workday".to_string()))?;
+        if worked <= 0 {
+            return Err(AppError::Validation("The breaks cannot last as long as the workday".to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub async fn create_workday(&self, driver_id: &Uuid, create_req: &CreateDriverWorkdayRequest, author_id: &Uuid) -> Result<DriverWorkday, AppError> {
+        self.driver_service.get_driver_by_id(driver_id).await?;
+
+        let is_overnight = create_req.is_overnight.unwrap_or(false);
+        let break_minutes = create_req.break_minutes.unwrap_or(0);
+        Self::validate_times(create_req.start_time, create_req.end_time, is_overnight, break_minutes)?;
+
+        if self.workday_date_exists_except_workday(driver_id, &create_req.workday_date, None).await? {
+            return Err(AppError::Conflict("The driver already has a workday on this date".to_string(), "DRIVER_WORKDAY_EXISTS".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let workday = sqlx::query_as::<_, DriverWorkday>(&format!(
+            r#"
+            INSERT INTO driver_workdays (fk_driver_id, workday_date, start_time, end_time, is_overnight, break_minutes, rest_minutes, notes)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            RETURNING {}
+            "#,
+            WORKDAY_COLUMNS
+        ))
+        .bind(driver_id)
+        .bind(create_req.workday_date)
+        .bind(create_req.start_time)
+        .bind(create_req.end_time)
+        .bind(is_overnight)
+        .bind(break_minutes)
+        .bind(create_req.rest_minutes.unwrap_or(0))
+        .bind(&create_req.notes)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 6,
+            entity_id: Some(*driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "CREATE_DRIVER_WORKDAY",
+                "workday_id": workday.pk_driver_workday_id,
+                "workday_date": workday.workday_date,
+                "start_time": workday.start_time,
+                "end_time": workday.end_time,
+                "is_overnight": workday.is_overnight,
+                "break_minutes": workday.break_minutes,
+                "rest_minutes": workday.rest_minutes,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(workday)
+    }
+
+    pub async fn update_workday(&self, workday_id: &Uuid, update_req: &UpdateDriverWorkdayRequest, author_id: &Uuid) -> Result<DriverWorkday, AppError> {
+        let existing = self.get_workday_by_id(workday_id).await?;
+
+        let mut changes = FieldChanges::new();
+        changes.track("workday_date", &existing.workday_date, update_req.workday_date.as_ref());
+        changes.track("start_time", &existing.start_time, update_req.start_time.as_ref());
+        changes.track("end_time", &existing.end_time, update_req.end_time.as_ref());
+        changes.track("is_overnight", &existing.is_overnight, update_req.is_overnight.as_ref());
+        changes.track("break_minutes", &existing.break_minutes, update_req.break_minutes.as_ref());
+        changes.track("rest_minutes", &existing.rest_minutes, update_req.rest_minutes.as_ref());
+        changes.track("notes", &existing.notes, update_req.notes.as_ref().map(|_| &update_req.notes));
+
+        if changes.is_empty() {
+            return Ok(existing);
+        }
+
+        Self::validate_times(
+            update_req.start_time.unwrap_or(existing.start_time),
+            update_req.end_time.unwrap_or(existing.end_time),
+            update_req.is_overnight.unwrap_or(existing.is_overnight),
+            update_req.break_minutes.unwrap_or(existing.break_minutes),
+        )?;
+
+        if let Some(workday_date) = update_req.workday_date {
+            if self.workday_date_exists_except_workday(&existing.fk_driver_id, &workday_date, Some(workday_id)).await? {
+                return Err(AppError::Conflict("The driver already has a workday on this date".to_string(), "DRIVER_WORKDAY_EXISTS".to_string()));
+            }
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let workday = sqlx::query_as::<_, DriverWorkday>(&format!(
+            r#"
+            UPDATE driver_workdays SET
+                workday_date = COALESCE($1, workday_date),
+                start_time = COALESCE($2, start_time),
+                end_time = COALESCE($3, end_time),
+                is_overnight = COALESCE($4, is_overnight),
+                break_minutes = COALESCE($5, break_minutes),
+                rest_minutes = COALESCE($6, rest_minutes),
+                notes = COALESCE($7, notes),
+                updated_at = NOW()
+            WHERE pk_driver_workday_id = $8
+            RETURNING {}
+            "#,
+            WORKDAY_COLUMNS
+        ))
+        .bind(update_req.workday_date)
+        .bind(update_req.start_time)
+        .bind(update_req.end_time)
+        .bind(update_req.is_overnight)
+        .bind(update_req.break_minutes)
+        .bind(update_req.rest_minutes)
+        .bind(&update_req.notes)
+        .bind(workday_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 7,
+            entity_id: Some(workday.fk_driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "UPDATE_DRIVER_WORKDAY",
+                "workday_id": workday_id,
+                "changes": changes.into_value(),
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(workday)
+    }
+
+    // The deleted workday is kept in the action history
+    pub async fn delete_workday(&self, workday_id: &Uuid, author_id: &Uuid) -> Result<(), AppError> {
+        let existing = self.get_workday_by_id(workday_id).await?;
+
+        let has_documents = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM driver_workday_documents WHERE fk_driver_workday_id = $1) as "exists!""#,
+            workday_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        if has_documents {
+            return Err(AppError::Conflict("The documents of the workday must be deleted first".to_string(), "DRIVER_WORKDAY_HAS_DOCUMENTS".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!("DELETE FROM driver_workdays WHERE pk_driver_workday_id = $1", workday_id)
+            .execute(&mut *tx)
+            .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 8,
+            entity_id: Some(existing.fk_driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "DELETE_DRIVER_WORKDAY",
+                "workday": existing,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+}
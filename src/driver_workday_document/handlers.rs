@@ -0,0 +1,88 @@
+use axum::{
+    extract::{multipart::MultipartError, Multipart, Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+
+use crate::{
+    driver_workday_document::{models::{sanitize_file_name, DriverWorkdayDocument}, services::DriverWorkdayDocumentService}, errors::app_error::AppError, middleware::AuthState, models::path::parse_uuid
+};
+
+fn multipart_error(e: MultipartError, max_size_bytes: usize) -> AppError {
+    if e.status() == StatusCode::PAYLOAD_TOO_LARGE {
+        AppError::Validation(format!("The document cannot be larger than {} bytes", max_size_bytes))
+    } else {
+        AppError::Validation(format!("The multipart request is not valid: {}", e.body_text()))
+    }
+}
+
+pub async fn get_workday_documents(
+    Path(workday_id): Path<String>,
+    State(document_service): State<Arc<DriverWorkdayDocumentService>>,
+) -> Result<Json<Vec<DriverWorkdayDocument>>, AppError> {
+    let workday_uuid = parse_uuid(&workday_id, "Driver workday")?;
+    let documents = document_service.get_workday_documents(&workday_uuid).await?;
+    Ok(Json(documents))
+}
+
+pub async fn get_document_by_id(
+    Path(document_id): Path<String>,
+    State(document_service): State<Arc<DriverWorkdayDocumentService>>,
+) -> Result<Json<DriverWorkdayDocument>, AppError> {
+    let document_uuid = parse_uuid(&document_id, "Driver workday document")?;
+    let document = document_service.get_document_by_id(&document_uuid).await?;
+    Ok(Json(document))
+}
+
+pub async fn download_document(
+    Path(document_id): Path<String>,
+    State(document_service): State<Arc<DriverWorkdayDocumentService>>,
+) -> Result<Response, AppError> {
+    let document_uuid = parse_uuid(&document_id, "Driver workday document")?;
+    let (document, content) = document_service.get_document_content(&document_uuid).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, document.content_type),
+            (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{}\"", document.file_name)),
+        ],
+        content,
+    ).into_response())
+}
+
+/// Multipart upload, the document is the `file` field
+pub async fn upload_document(
+    Path(workday_id): Path<String>,
+    State(document_service): State<Arc<DriverWorkdayDocumentService>>,
+    Extension(auth_state): Extension<AuthState>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<DriverWorkdayDocument>), AppError> {
+    let workday_uuid = parse_uuid(&workday_id, "Driver workday")?;
+    let max_size_bytes = document_service.max_size_bytes();
+
+    while let Some(field) = multipart.next_field().await.map_err(|e| multipart_error(e, max_size_bytes))? {
+        if field.name() != Some("file") {
+            continue;
+        }
+        let file_name = sanitize_file_name(field.file_name());
+        let content = field.bytes().await.map_err(|e| multipart_error(e, max_size_bytes))?;
+
+        let document = document_service.upload_document(&workday_uuid, file_name, content, &auth_state.employee_id).await?;
+        return Ok((StatusCode::CREATED, Json(document)));
+    }
+
+    Err(AppError::Validation("The document must be sent in the file field".to_string()))
+}
+
+pub async fn delete_document(
+    Path(document_id): Path<String>,
+    State(document_service): State<Arc<DriverWorkdayDocumentService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<StatusCode, AppError> {
+    let document_uuid = parse_uuid(&document_id, "Driver workday document")?;
+    document_service.delete_document(&document_uuid, &auth_state.employee_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,67 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+/// Content types accepted for the workday documents, sniffed from the file content
+pub const ALLOWED_DOCUMENT_CONTENT_TYPES: [&str; 4] = ["application/pdf", "image/jpeg", "image/png", "image/webp"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct DriverWorkdayDocument {
+    pub pk_driver_workday_document_id: Uuid,
+    pub fk_driver_workday_id: Uuid,
+    pub file_name: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Location of the content in the document storage
+    #[serde(skip_serializing)]
+    pub storage_key: String,
+    pub fk_uploading_employee_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Content type of a document, None when it is not one of the allowed types
+pub fn sniff_content_type(content: &[u8]) -> Option<&'static str> {
+    infer::get(content)
+        .map(|kind| kind.mime_type())
+        .filter(|mime_type| ALLOWED_DOCUMENT_CONTENT_TYPES.contains(mime_type))
+}
+
+/// Keep the last component of an uploaded file name, without the characters
+/// that could break the Content-Disposition header
+pub fn sanitize_file_name(file_name: Option<&str>) -> String {
+    let name: String = file_name
+        .and_then(|name| name.rsplit(['/', '\\']).next())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(255)
+        .collect();
+
+    match name.trim() {
+        "" => "document".to_string(),
+        name => name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_content_type() {
+        assert_eq!(sniff_content_type(b"%PDF-1.7\n%\xe2\xe3\xcf\xd3"), Some("application/pdf"));
+        assert_eq!(sniff_content_type(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"), Some("image/png"));
+        // recognized, but not allowed
+        assert_eq!(sniff_content_type(b"PK\x03\x04\x14\0\0\0\x08\0"), None);
+        assert_eq!(sniff_content_type(b"plain text"), None);
+    }
+
+    #[test]
+    fn test_sanitize_file_name() {
+        assert_eq!(sanitize_file_name(Some("C:\\scans\\ticket \"42\".pdf")), "ticket 42.pdf");
+        assert_eq!(sanitize_file_name(Some("../../etc/passwd")), "passwd");
+        assert_eq!(sanitize_file_name(Some("  ")), "document");
+        assert_eq!(sanitize_file_name(None), "document");
+    }
+}
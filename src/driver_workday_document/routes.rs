@@ -0,0 +1,29 @@
+use axum::{
+    extract::DefaultBodyLimit, middleware::{from_fn, from_fn_with_state}, routing::{delete, get, post}, Router
+};
+use crate::{
+    driver_workday_document::{handlers::{delete_document, download_document, get_document_by_id, get_workday_documents, upload_document}, services::DriverWorkdayDocumentService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+};
+use std::sync::Arc;
+
+// Room for the multipart boundaries and headers around the document
+const MULTIPART_OVERHEAD_BYTES: usize = 16 * 1024;
+
+pub fn protected_driver_workday_document_routes(
+    middleware_state: MiddlewareState,
+    document_service: Arc<DriverWorkdayDocumentService>,
+) -> Router {
+    let upload_body_limit = document_service.max_size_bytes() + MULTIPART_OVERHEAD_BYTES;
+
+    Router::new()
+        .route("/drivers/workdays/{id}/documents", get(get_workday_documents).route_layer(from_fn(with_required_permissions(vec![9]))))
+        .route("/drivers/workdays/{id}/documents", post(upload_document).layer(DefaultBodyLimit::max(upload_body_limit)).route_layer(from_fn(with_required_permissions(vec![10]))))
+        .route("/drivers/workdays/documents/{id}", get(get_document_by_id).route_layer(from_fn(with_required_permissions(vec![9]))))
+        .route("/drivers/workdays/documents/{id}", delete(delete_document).route_layer(from_fn(with_required_permissions(vec![11]))))
+        .route("/drivers/workdays/documents/{id}/download", get(download_document).route_layer(from_fn(with_required_permissions(vec![9]))))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(document_service.clone())
+}
@@ -0,0 +1,176 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use sqlx::PgPool;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    driver_workday::services::DriverWorkdayService, driver_workday_document::models::{sniff_content_type, DriverWorkdayDocument, ALLOWED_DOCUMENT_CONTENT_TYPES}, employee::models::EntityType, errors::app_error::AppError, history::{models::NewActionHistory, services::record_action}, storage::DocumentStorage
+};
+
+pub const DOCUMENT_COLUMNS: &str = "pk_driver_workday_document_id, fk_driver_workday_id, file_name, content_type, size_bytes, storage_key, fk_uploading_employee_id, created_at";
+
+const DEFAULT_MAX_DOCUMENT_SIZE_BYTES: usize = 10 * 1024 * 1024;
+
+pub struct DriverWorkdayDocumentService {
+    pool: PgPool,
+    storage: Arc<dyn DocumentStorage>,
+    workday_service: DriverWorkdayService,
+    max_size_bytes: usize,
+}
+
+impl DriverWorkdayDocumentService {
+    pub fn new(pool: PgPool, storage: Arc<dyn DocumentStorage>) -> Self {
+        let max_size_bytes = std::env::var("DOCUMENT_MAX_SIZE_BYTES")
+            .ok()
+            .and_then(|size| size.parse().ok())
+            .unwrap_or(DEFAULT_MAX_DOCUMENT_SIZE_BYTES);
+
+        Self {
+            workday_service: DriverWorkdayService::new(pool.clone()),
+            pool,
+            storage,
+            max_size_bytes,
+        }
+    }
+
+    pub fn max_size_bytes(&self) -> usize {
+        self.max_size_bytes
+    }
+
+    pub async fn get_workday_documents(&self, workday_id: &Uuid) -> Result<Vec<DriverWorkdayDocument>, AppError> {
+        self.workday_service.get_workday_by_id(workday_id).await?;
+
+        let documents = sqlx::query_as::<_, DriverWorkdayDocument>(&format!(
+            "SELECT {} FROM driver_workday_documents WHERE fk_driver_workday_id = $1 ORDER BY created_at",
+            DOCUMENT_COLUMNS
+        ))
+        .bind(workday_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(documents)
+    }
+
+    pub async fn get_document_by_id(&self, document_id: &Uuid) -> Result<DriverWorkdayDocument, AppError> {
+        sqlx::query_as::<_, DriverWorkdayDocument>(&format!("SELECT {} FROM driver_workday_documents WHERE pk_driver_workday_document_id = $1", DOCUMENT_COLUMNS))
+            .bind(document_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Driver workday document not found".to_string()))
+    }
+
+    pub async fn get_document_content(&self, document_id: &Uuid) -> Result<(DriverWorkdayDocument, Bytes), AppError> {
+        let document = self.get_document_by_id(document_id).await?;
+        let content = self.storage.get(&document.storage_key).await?;
+        Ok((document, content))
+    }
+
+    pub async fn upload_document(&self, workday_id: &Uuid, file_name: String, content: Bytes, author_id: &Uuid) -> Result<DriverWorkdayDocument, AppError> {
+        if content.is_empty() {
+            return Err(AppError::Validation("The document cannot be empty".to_string()));
+        }
+        if content.len() > self.max_size_bytes {
+            return Err(AppError::Validation(format!("The document cannot be larger than {} bytes", self.max_size_bytes)));
+        }
+        // the declared content type is not trusted
+        let content_type = sniff_content_type(&content).ok_or_else(|| AppError::Validation(format!(
+            "The document type is not supported, allowed types: {}",
+            ALLOWED_DOCUMENT_CONTENT_TYPES.join(", ")
+        )))?;
+
+        let workday = self.workday_service.get_workday_by_id(workday_id).await?;
+
+        let document_id = Uuid::new_v4();
+        let storage_key = format!("drivers/{}/workdays/{}/{}", workday.fk_driver_id, workday_id, document_id);
+        let size_bytes = content.len() as i64;
+
+        // the content is stored first, a row never points to a missing file
+        self.storage.put(&storage_key, content, content_type).await?;
+
+        let inserted: Result<DriverWorkdayDocument, AppError> = async {
+            let mut tx = self.pool.begin().await?;
+
+            let document = sqlx::query_as::<_, DriverWorkdayDocument>(&format!(
+                r#"
+                INSERT INTO driver_workday_documents (pk_driver_workday_document_id, fk_driver_workday_id, file_name, content_type, size_bytes, storage_key, fk_uploading_employee_id)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING {}
+                "#,
+                DOCUMENT_COLUMNS
+            ))
+            .bind(document_id)
+            .bind(workday_id)
+            .bind(&file_name)
+            .bind(content_type)
+            .bind(size_bytes)
+            .bind(&storage_key)
+            .bind(author_id)
+            .fetch_one(&mut *tx)
+            .await?;
+
+            record_action(&mut *tx, &NewActionHistory {
+                employee_id: *author_id,
+                authorization_type_id: 10,
+                entity_id: Some(workday.fk_driver_id),
+                entity_type: EntityType::DRIVER,
+                description: serde_json::json!({
+                    "action": "UPLOAD_DRIVER_WORKDAY_DOCUMENT",
+                    "workday_id": workday_id,
+                    "document_id": document_id,
+                    "file_name": file_name,
+                    "content_type": content_type,
+                    "size_bytes": size_bytes,
+                }),
+            }).await?;
+
+            tx.commit().await?;
+
+            Ok(document)
+        }.await;
+
+        if inserted.is_err() {
+            if let Err(e) = self.storage.delete(&storage_key).await {
+                warn!("Orphan driver workday document {} left in the storage: {}", storage_key, e);
+            }
+        }
+
+        inserted
+    }
+
+    pub async fn delete_document(&self, document_id: &Uuid, author_id: &Uuid) -> Result<(), AppError> {
+        let existing = self.get_document_by_id(document_id).await?;
+        let workday = self.workday_service.get_workday_by_id(&existing.fk_driver_workday_id).await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!("DELETE FROM driver_workday_documents WHERE pk_driver_workday_document_id = $1", document_id)
+            .execute(&mut *tx)
+            .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 11,
+            entity_id: Some(workday.fk_driver_id),
+            entity_type: EntityType::DRIVER,
+            description: serde_json::json!({
+                "action": "DELETE_DRIVER_WORKDAY_DOCUMENT",
+                "workday_id": existing.fk_driver_workday_id,
+                "document_id": document_id,
+                "file_name": existing.file_name,
+                "content_type": existing.content_type,
+                "size_bytes": existing.size_bytes,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        // the row is gone, a failure only leaves an unreachable file behind
+        if let Err(e) = self.storage.delete(&existing.storage_key).await {
+            warn!("Orphan driver workday document {} left in the storage: {}", existing.storage_key, e);
+        }
+
+        Ok(())
+    }
+}
@@ -1,13 +1,40 @@
 use axum::{
     extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
     Json,
 };
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
 
 use crate::{
-    employee::{models::{Employee, EmployeeAccreditation, EmployeeAuthorization, EmployeeLevel, EmployeeLevelWithAuthorizations, GetAllEmployeesQuery}, services::EmployeeService}, errors::app_error::AppError, models::paginate::{PaginateQuery, PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}
+    employee::{models::{AuthorizationHolders, BreakGlassCreate, Employee, EmployeeAccreditation, EmployeeAccreditationCreate, EmployeeAccreditationRevoke, EmployeeAccreditationUpdate, EmployeeAuthorization, EmployeeEffectivePermissions, EmployeeLevel, EmployeeLevelCompareQuery, EmployeeLevelComparison, EmployeeLevelCreate, EmployeeLevelMatrix, EmployeeLevelMatrixQuery, EmployeeLevelUpdate, EmployeeLevelWithAuthorizations, EmployeeUpdate, GetAllEmployeesQuery, PointInTimeQuery}, services::{EmployeeService, ADMIN_LEVEL_LABEL}}, errors::app_error::AppError, middleware::AuthState, models::{csv::csv_line, paginate::{PaginateQuery, PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}}
 };
 
+/// "Update an employee" authorization type
+const UPDATE_EMPLOYEE_PERMISSION: i32 = 22;
+/// "Update their own profile" authorization type, held by every employee
+const UPDATE_OWN_PROFILE_PERMISSION: i32 = 55;
+
+/// Accreditations are identified by their recipient and their creation date
+fn parse_accreditation_key(employee_id: &str, created_at: &str) -> Result<(Uuid, DateTime<Utc>), AppError> {
+    let employee_uuid = employee_id.parse::<Uuid>()
+        .map_err(|_| AppError::Validation("Employee ID is not valid".to_string()))?;
+    let created_at = DateTime::parse_from_rfc3339(created_at)
+        .map_err(|_| AppError::Validation("Accreditation creation date is not a valid RFC 3339 date".to_string()))?
+        .with_timezone(&Utc);
+
+    Ok((employee_uuid, created_at))
+}
+
+fn validate_request<T: Validate>(req: &T) -> Result<(), AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(format!("The request content is not valid: {}", e)))
+}
+
 pub async fn get_all_employees(
     Query(filters): Query<GetAllEmployeesQuery>,
     State(employee_service): State<Arc<EmployeeService>>,
@@ -32,6 +59,54 @@ pub async fn get_employee_by_id(
     Ok(Json(employee))
 }
 
+pub async fn update_employee(
+    Path(employee_id): Path<String>,
+    State(employee_service): State<Arc<EmployeeService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(update_req): Json<EmployeeUpdate>,
+) -> Result<Json<Employee>, AppError> {
+    let employee_uuid = employee_id.parse::<Uuid>()
+        .map_err(|_| AppError::Validation("Employee ID is not valid".to_string()))?;
+
+    validate_request(&update_req)?;
+
+    // without the update permission, an employee can only edit a limited set of their own fields
+    let authorization_type_id = if auth_state.authorizations.contains(&UPDATE_EMPLOYEE_PERMISSION) {
+        UPDATE_EMPLOYEE_PERMISSION
+    } else if auth_state.employee_id == employee_uuid && update_req.is_self_editable() {
+        UPDATE_OWN_PROFILE_PERMISSION
+    } else {
+        return Err(AppError::InsufficientPermissions(vec![UPDATE_EMPLOYEE_PERMISSION]));
+    };
+
+    let employee = employee_service.update_employee(&employee_uuid, &update_req, &auth_state.employee_id, authorization_type_id).await?;
+    Ok(Json(employee))
+}
+
+pub async fn deactivate_employee(
+    Path(employee_id): Path<String>,
+    State(employee_service): State<Arc<EmployeeService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<StatusCode, AppError> {
+    let employee_uuid = employee_id.parse::<Uuid>()
+        .map_err(|_| AppError::Validation("Employee ID is not valid".to_string()))?;
+
+    employee_service.deactivate_employee(&employee_uuid, &auth_state.employee_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn reactivate_employee(
+    Path(employee_id): Path<String>,
+    State(employee_service): State<Arc<EmployeeService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<Employee>, AppError> {
+    let employee_uuid = employee_id.parse::<Uuid>()
+        .map_err(|_| AppError::Validation("Employee ID is not valid".to_string()))?;
+
+    let employee = employee_service.reactivate_employee(&employee_uuid, &auth_state.employee_id).await?;
+    Ok(Json(employee))
+}
+
 pub async fn get_employee_all_accreditations(
     Query(filters): Query<PaginateQuery>,
     Path(employee_id): Path<String>,
@@ -72,6 +147,82 @@ pub async fn get_all_levels(
     Ok(Json(levels))
 }
 
+pub async fn get_level_matrix(
+    Query(query): Query<EmployeeLevelMatrixQuery>,
+    State(employee_service): State<Arc<EmployeeService>>,
+) -> Result<Response, AppError> {
+    let matrix = employee_service.get_employee_level_matrix().await?;
+
+    match query.format.as_deref() {
+        None | Some("json") => Ok(Json(matrix).into_response()),
+        Some("csv") => Ok((
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+                (header::CONTENT_DISPOSITION, "attachment; filename=\"permission_matrix.csv\""),
+            ],
+            level_matrix_to_csv(&matrix),
+        ).into_response()),
+        Some(_) => Err(AppError::Validation("Format must be 'json' or 'csv'".to_string())),
+    }
+}
+
+/// One row per authorization type and one column per level, granted cells contain `X`
+fn level_matrix_to_csv(matrix: &EmployeeLevelMatrix) -> String {
+    let mut header_row = vec!["category_name_code", "authorization_feature_code", "pk_employee_authorization_id", "crud_type", "description"];
+    header_row.extend(matrix.levels.iter().map(|level| level.level_label.as_str()));
+
+    let mut csv = csv_line(header_row);
+    for category in &matrix.categories {
+        for feature in &category.features {
+            for authorization in &feature.authorizations {
+                let mut row = vec![
+                    category.category_name_code.clone(),
+                    feature.authorization_feature_code.clone(),
+                    authorization.pk_employee_authorization_id.to_string(),
+                    authorization.crud_type.as_str().to_string(),
+                    authorization.description.clone(),
+                ];
+                row.extend(matrix.levels.iter().map(|level| {
+                    if authorization.granted_level_ids.contains(&level.pk_employee_level_id) { "X".to_string() } else { String::new() }
+                }));
+                csv.push_str(&csv_line(row));
+            }
+        }
+    }
+
+    csv
+}
+
+pub async fn compare_levels(
+    Query(query): Query<EmployeeLevelCompareQuery>,
+    State(employee_service): State<Arc<EmployeeService>>,
+) -> Result<Json<EmployeeLevelComparison>, AppError> {
+    let comparison = employee_service.compare_employee_levels(query.a, query.b).await?;
+    Ok(Json(comparison))
+}
+
+pub async fn get_employee_permissions_at(
+    Path(employee_id): Path<String>,
+    Query(query): Query<PointInTimeQuery>,
+    State(employee_service): State<Arc<EmployeeService>>,
+) -> Result<Json<EmployeeEffectivePermissions>, AppError> {
+    let employee_uuid = employee_id.parse::<Uuid>()
+        .map_err(|_| AppError::Validation("Employee ID is not valid".to_string()))?;
+    let at = query.at.unwrap_or_else(Utc::now);
+    let permissions = employee_service.get_employee_permissions_at(&employee_uuid, &at).await?;
+    Ok(Json(permissions))
+}
+
+pub async fn get_authorization_holders(
+    Path(authorization_id): Path<i32>,
+    Query(query): Query<PointInTimeQuery>,
+    State(employee_service): State<Arc<EmployeeService>>,
+) -> Result<Json<AuthorizationHolders>, AppError> {
+    let at = query.at.unwrap_or_else(Utc::now);
+    let holders = employee_service.get_authorization_holders_at(authorization_id, &at).await?;
+    Ok(Json(holders))
+}
+
 pub async fn get_level_by_id(
     Path(level_id): Path<i32>,
     State(employee_service): State<Arc<EmployeeService>>,
@@ -80,6 +231,56 @@ pub async fn get_level_by_id(
     Ok(Json(level))
 }
 
+pub async fn create_level(
+    State(employee_service): State<Arc<EmployeeService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<EmployeeLevelCreate>,
+) -> Result<(StatusCode, Json<EmployeeLevel>), AppError> {
+    validate_request(&create_req)?;
+
+    let level = employee_service.create_employee_level(&create_req, &auth_state.employee_id).await?;
+    Ok((StatusCode::CREATED, Json(level)))
+}
+
+pub async fn update_level(
+    Path(level_id): Path<i32>,
+    State(employee_service): State<Arc<EmployeeService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(update_req): Json<EmployeeLevelUpdate>,
+) -> Result<Json<EmployeeLevel>, AppError> {
+    validate_request(&update_req)?;
+
+    let level = employee_service.update_employee_level(level_id, &update_req, &auth_state.employee_id).await?;
+    Ok(Json(level))
+}
+
+pub async fn delete_level(
+    Path(level_id): Path<i32>,
+    State(employee_service): State<Arc<EmployeeService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<StatusCode, AppError> {
+    employee_service.delete_employee_level(level_id, &auth_state.employee_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn add_level_authorization(
+    Path((level_id, authorization_type_id)): Path<(i32, i32)>,
+    State(employee_service): State<Arc<EmployeeService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<EmployeeLevelWithAuthorizations>, AppError> {
+    let level = employee_service.add_employee_level_authorization(level_id, authorization_type_id, &auth_state.employee_id).await?;
+    Ok(Json(level))
+}
+
+pub async fn remove_level_authorization(
+    Path((level_id, authorization_type_id)): Path<(i32, i32)>,
+    State(employee_service): State<Arc<EmployeeService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<EmployeeLevelWithAuthorizations>, AppError> {
+    let level = employee_service.remove_employee_level_authorization(level_id, authorization_type_id, &auth_state.employee_id).await?;
+    Ok(Json(level))
+}
+
 pub async fn get_all_accreditations(
     Query(filters): Query<PaginateQuery>,
     State(employee_service): State<Arc<EmployeeService>>,
@@ -103,4 +304,95 @@ pub async fn get_all_accreditations(
     };
 
     Ok(Json(response))
-}
\ No newline at end of file
+}
+
+pub async fn get_all_break_glass_accreditations(
+    Query(filters): Query<PaginateQuery>,
+    State(employee_service): State<Arc<EmployeeService>>,
+) -> Result<Json<PaginatedResponse<EmployeeAccreditation>>, AppError> {
+    if filters.page == 0 {
+        return Err(AppError::Validation("Page must be greater than 0".to_string()));
+    }
+    if filters.limit == 0 || filters.limit > PAGINATE_MAX_LIMIT {
+        return Err(AppError::Validation(format!("Limit must be between 1 and {}.", PAGINATE_MAX_LIMIT)));
+    }
+
+    let (accreditations, total) = employee_service.get_all_break_glass_accreditations(&filters).await?;
+
+    let response = PaginatedResponse {
+        data: accreditations,
+        pagination: PaginationInfo {
+            total,
+            page: filters.page,
+            limit: filters.limit,
+        },
+    };
+
+    Ok(Json(response))
+}
+
+pub async fn create_break_glass(
+    State(employee_service): State<Arc<EmployeeService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<BreakGlassCreate>,
+) -> Result<(StatusCode, Json<EmployeeAccreditation>), AppError> {
+    validate_request(&create_req)?;
+
+    let accreditation = employee_service.create_break_glass(&auth_state.employee_id, &create_req).await?;
+    Ok((StatusCode::CREATED, Json(accreditation)))
+}
+
+pub async fn create_employee_accreditation(
+    Path(employee_id): Path<String>,
+    State(employee_service): State<Arc<EmployeeService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<EmployeeAccreditationCreate>,
+) -> Result<Response, AppError> {
+    let employee_uuid = employee_id.parse::<Uuid>()
+        .map_err(|_| AppError::Validation("Employee ID is not valid".to_string()))?;
+
+    validate_request(&create_req)?;
+
+    // the ADMIN level is only granted once a second employee approves it
+    let level = employee_service.get_employee_level_by_id(create_req.fk_employee_level_id).await?;
+    if level.level_label == ADMIN_LEVEL_LABEL {
+        let operation = employee_service.request_admin_accreditation(&employee_uuid, &create_req, &auth_state.employee_id).await?;
+        return Ok((StatusCode::ACCEPTED, Json(operation)).into_response());
+    }
+
+    let accreditation = employee_service.create_employee_accreditation(&employee_uuid, &create_req, &auth_state.employee_id).await?;
+    Ok((StatusCode::CREATED, Json(accreditation)).into_response())
+}
+
+pub async fn update_employee_accreditation(
+    Path((employee_id, created_at)): Path<(String, String)>,
+    State(employee_service): State<Arc<EmployeeService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(update_req): Json<EmployeeAccreditationUpdate>,
+) -> Result<Response, AppError> {
+    let (employee_uuid, created_at) = parse_accreditation_key(&employee_id, &created_at)?;
+    validate_request(&update_req)?;
+
+    // extending an ADMIN accreditation needs the approval of a second employee, like granting it
+    let existing = employee_service.get_employee_accreditation(&employee_uuid, &created_at).await?;
+    if existing.employee_level.level_label == ADMIN_LEVEL_LABEL && existing.is_extended_by(&update_req) {
+        let operation = employee_service.request_admin_accreditation_update(&employee_uuid, &created_at, &update_req, &auth_state.employee_id).await?;
+        return Ok((StatusCode::ACCEPTED, Json(operation)).into_response());
+    }
+
+    let accreditation = employee_service.update_employee_accreditation(&employee_uuid, &created_at, &update_req, &auth_state.employee_id).await?;
+    Ok(Json(accreditation).into_response())
+}
+
+pub async fn revoke_employee_accreditation(
+    Path((employee_id, created_at)): Path<(String, String)>,
+    State(employee_service): State<Arc<EmployeeService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(revoke_req): Json<EmployeeAccreditationRevoke>,
+) -> Result<Json<EmployeeAccreditation>, AppError> {
+    let (employee_uuid, created_at) = parse_accreditation_key(&employee_id, &created_at)?;
+    validate_request(&revoke_req)?;
+
+    let accreditation = employee_service.revoke_employee_accreditation(&employee_uuid, &created_at, &revoke_req, &auth_state.employee_id).await?;
+    Ok(Json(accreditation))
+}
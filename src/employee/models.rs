@@ -4,9 +4,9 @@ use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-use crate::models::paginate::{default_limit, default_page, default_sort_order};
+use crate::models::{nullable::nullable, paginate::{default_limit, default_page, default_sort_order}};
 
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, Validate)]
 pub struct Employee {
@@ -94,6 +94,51 @@ pub struct EmployeeCreate {
     pub professional_email_password: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Validate)]
+pub struct EmployeeUpdate {
+    #[validate(length(min = 1, max = 255, message = "Firstname cannot be empty and cannot be longer than 255 characters"))]
+    pub firstname: Option<String>,
+
+    #[validate(length(min = 1, max = 255, message = "Lastname cannot be empty and cannot be longer than 255 characters"))]
+    pub lastname: Option<String>,
+
+    /// null removes the gender
+    #[serde(default, deserialize_with = "nullable")]
+    #[validate(custom(function = "validate_gender"))]
+    pub gender: Option<Option<String>>,
+
+    #[validate(email(message = "Invalid personal email format"))]
+    #[validate(length(max = 255, message = "Personal email cannot be longer than 255 characters"))]
+    pub personal_email: Option<String>,
+
+    /// null removes the phone number
+    #[serde(default, deserialize_with = "nullable")]
+    #[validate(length(max = 20, message = "Phone number cannot be longer than 20 characters"))]
+    pub phone_number: Option<Option<String>>,
+
+    #[validate(email(message = "Invalid professional email format"))]
+    #[validate(length(max = 255, message = "Professional email cannot be longer than 255 characters"))]
+    pub professional_email: Option<String>,
+}
+
+fn validate_gender(gender: &str) -> Result<(), ValidationError> {
+    match gender {
+        "M" | "F" | "O" => Ok(()),
+        _ => Err(ValidationError::new("gender").with_message("Gender must be 'M', 'F' or 'O'".into())),
+    }
+}
+
+impl EmployeeUpdate {
+    /// Whether the update only touches the fields an employee may edit on their own profile
+    /// without the "Update an employee" permission
+    pub fn is_self_editable(&self) -> bool {
+        self.firstname.is_none()
+            && self.lastname.is_none()
+            && self.gender.is_none()
+            && self.professional_email.is_none()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Validate)]
 pub struct EmployeeLoginRequest {
     #[validate(email)]
@@ -119,6 +164,10 @@ pub struct EmployeeWithPermissions {
 pub enum EntityType {
     DRIVER,
     EMPLOYEE,
+    #[allow(non_camel_case_types)]
+    EMPLOYEE_LEVEL,
+    #[allow(non_camel_case_types)]
+    EMPLOYEE_TEAM,
 }
 
 impl FromStr for EntityType {
@@ -127,11 +176,24 @@ impl FromStr for EntityType {
         match s {
             "DRIVER" => Ok(EntityType::DRIVER),
             "EMPLOYEE" => Ok(EntityType::EMPLOYEE),
+            "EMPLOYEE_LEVEL" => Ok(EntityType::EMPLOYEE_LEVEL),
+            "EMPLOYEE_TEAM" => Ok(EntityType::EMPLOYEE_TEAM),
             _ => Err(()),
         }
     }
 }
 
+impl EntityType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntityType::DRIVER => "DRIVER",
+            EntityType::EMPLOYEE => "EMPLOYEE",
+            EntityType::EMPLOYEE_LEVEL => "EMPLOYEE_LEVEL",
+            EntityType::EMPLOYEE_TEAM => "EMPLOYEE_TEAM",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub enum CrudType {
     C,
@@ -153,6 +215,17 @@ impl FromStr for CrudType {
     }
 }
 
+impl CrudType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CrudType::C => "C",
+            CrudType::R => "R",
+            CrudType::U => "U",
+            CrudType::D => "D",
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 pub struct EmployeeAuthorization {
     pub pk_employee_authorization_id: i32,
@@ -165,13 +238,29 @@ pub struct EmployeeAuthorization {
     pub category_index: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, FromRow, PartialEq)]
 pub struct EmployeeLevel {
     pub pk_employee_level_id: i32,
     pub level_index: i32,
     pub level_label: String,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct EmployeeLevelCreate {
+    #[validate(range(min = 1, message = "Level index must be higher than 0"))]
+    pub level_index: i32,
+    #[validate(length(min = 1, max = 255, message = "Level label is required and cannot be longer than 255 characters"))]
+    pub level_label: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct EmployeeLevelUpdate {
+    #[validate(range(min = 1, message = "Level index must be higher than 0"))]
+    pub level_index: Option<i32>,
+    #[validate(length(min = 1, max = 255, message = "Level label cannot be empty and cannot be longer than 255 characters"))]
+    pub level_label: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct EmployeeLevelWithAuthorizations {
     pub pk_employee_level_id: i32,
@@ -180,6 +269,163 @@ pub struct EmployeeLevelWithAuthorizations {
     pub authorizations: Vec<EmployeeAuthorization>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct EmployeeLevelMatrixQuery {
+    /// `json` (default) or `csv`
+    #[serde(default)]
+    pub format: Option<String>,
+}
+
+/// Levels × authorization types grid, grouped like `EmployeeAuthorization`
+#[derive(Debug, Serialize)]
+pub struct EmployeeLevelMatrix {
+    pub levels: Vec<EmployeeLevel>,
+    pub categories: Vec<EmployeeLevelMatrixCategory>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmployeeLevelMatrixCategory {
+    pub category_name_code: String,
+    pub category_entity_type: EntityType,
+    pub category_index: i32,
+    pub features: Vec<EmployeeLevelMatrixFeature>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmployeeLevelMatrixFeature {
+    pub authorization_feature_code: String,
+    pub authorization_index: i32,
+    pub authorizations: Vec<EmployeeLevelMatrixAuthorization>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmployeeLevelMatrixAuthorization {
+    pub pk_employee_authorization_id: i32,
+    pub crud_type: CrudType,
+    pub description: String,
+    /// Ids of the levels granted this authorization type
+    pub granted_level_ids: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EmployeeLevelCompareQuery {
+    pub a: i32,
+    pub b: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmployeeLevelComparison {
+    pub level_a: EmployeeLevel,
+    pub level_b: EmployeeLevel,
+    /// Authorizations granted to level A but not to level B
+    pub only_in_a: Vec<EmployeeAuthorization>,
+    /// Authorizations granted to level B but not to level A
+    pub only_in_b: Vec<EmployeeAuthorization>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PointInTimeQuery {
+    /// Defaults to now
+    #[serde(default)]
+    pub at: Option<DateTime<Utc>>,
+}
+
+/// Where an authorization held by an employee comes from
+#[derive(Debug, Serialize, PartialEq)]
+#[serde(tag = "source")]
+pub enum PermissionSource {
+    /// A personal accreditation of the employee
+    ACCREDITATION {
+        employee_level: EmployeeLevel,
+    },
+    /// An accreditation of a team the employee is a member of
+    TEAM {
+        pk_employee_team_id: Uuid,
+        team_name: String,
+        employee_level: EmployeeLevel,
+    },
+    /// A delegation from a colleague holding the authorization
+    DELEGATION {
+        pk_employee_permission_delegation_id: Uuid,
+        fk_delegator_employee_id: Uuid,
+    },
+}
+
+/// One way an employee held an authorization, as read from the database
+#[derive(Debug, FromRow)]
+pub struct PermissionGrant {
+    pub fk_employee_id: Uuid,
+    pub fk_employee_authorization_type_id: i32,
+    /// ACCREDITATION, TEAM or DELEGATION
+    pub source: String,
+    pub pk_employee_level_id: Option<i32>,
+    pub level_index: Option<i32>,
+    pub level_label: Option<String>,
+    pub pk_employee_team_id: Option<Uuid>,
+    pub team_name: Option<String>,
+    pub pk_employee_permission_delegation_id: Option<Uuid>,
+    pub fk_delegator_employee_id: Option<Uuid>,
+}
+
+impl PermissionGrant {
+    /// None when the columns expected for the source are missing
+    pub fn to_source(&self) -> Option<PermissionSource> {
+        let employee_level = match (self.pk_employee_level_id, self.level_index, &self.level_label) {
+            (Some(pk_employee_level_id), Some(level_index), Some(level_label)) => Some(EmployeeLevel { pk_employee_level_id, level_index, level_label: level_label.clone() }),
+            _ => None,
+        };
+
+        match self.source.as_str() {
+            "ACCREDITATION" => Some(PermissionSource::ACCREDITATION { employee_level: employee_level? }),
+            "TEAM" => Some(PermissionSource::TEAM {
+                pk_employee_team_id: self.pk_employee_team_id?,
+                team_name: self.team_name.clone()?,
+                employee_level: employee_level?,
+            }),
+            "DELEGATION" => Some(PermissionSource::DELEGATION {
+                pk_employee_permission_delegation_id: self.pk_employee_permission_delegation_id?,
+                fk_delegator_employee_id: self.fk_delegator_employee_id?,
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// An authorization with every grant it was held through
+#[derive(Debug, Serialize)]
+pub struct EffectiveAuthorization {
+    #[serde(flatten)]
+    pub authorization: EmployeeAuthorization,
+    pub sources: Vec<PermissionSource>,
+}
+
+/// Permissions an employee held at a given date, with where each of them came from
+#[derive(Debug, Serialize)]
+pub struct EmployeeEffectivePermissions {
+    pub pk_employee_id: Uuid,
+    pub at: DateTime<Utc>,
+    /// A suspended employee holds no permission, whatever their accreditations
+    pub suspended: bool,
+    /// Levels held through personal accreditations
+    pub employee_levels: Vec<EmployeeLevel>,
+    pub authorizations: Vec<EffectiveAuthorization>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AuthorizationHolder {
+    pub employee: LightEmployee,
+    #[serde(flatten)]
+    pub source: PermissionSource,
+}
+
+/// Employees who held an authorization type at a given date
+#[derive(Debug, Serialize)]
+pub struct AuthorizationHolders {
+    pub pk_employee_authorization_id: i32,
+    pub at: DateTime<Utc>,
+    pub holders: Vec<AuthorizationHolder>,
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct EmployeeAccreditation {
     pub recipient_employee: LightEmployee,
@@ -188,4 +434,126 @@ pub struct EmployeeAccreditation {
     pub start_at: DateTime<Utc>,
     pub end_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
-}
\ No newline at end of file
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub revoking_employee: Option<LightEmployee>,
+    pub revocation_reason: Option<String>,
+    /// Self-granted temporary elevation, see `BreakGlassCreate`
+    pub is_break_glass: bool,
+    pub justification: Option<String>,
+}
+
+impl EmployeeAccreditation {
+    /// Whether the update makes the accreditation cover a period it did not cover yet
+    pub fn is_extended_by(&self, update: &EmployeeAccreditationUpdate) -> bool {
+        update.start_at.is_some_and(|start_at| start_at < self.start_at)
+            || update.end_at.is_some_and(|end_at| self.end_at.is_some_and(|existing_end_at| end_at > existing_end_at))
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct EmployeeAccreditationCreate {
+    #[validate(range(min = 1, message = "Employee level ID is not valid"))]
+    pub fk_employee_level_id: i32,
+    pub start_at: DateTime<Utc>,
+    /// No end means the accreditation lasts until it is ended
+    pub end_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct EmployeeAccreditationUpdate {
+    /// Can only be changed while the accreditation has not started yet
+    pub start_at: Option<DateTime<Utc>>,
+    /// Set it to now to end the accreditation early
+    pub end_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct EmployeeAccreditationRevoke {
+    #[validate(length(min = 1, max = 1000, message = "Revocation reason is required and cannot be longer than 1000 characters"))]
+    pub revocation_reason: String,
+}
+
+/// Temporary elevation an employee grants themselves during an incident
+#[derive(Debug, Deserialize, Validate)]
+pub struct BreakGlassCreate {
+    #[validate(range(min = 1, message = "Employee level ID is not valid"))]
+    pub fk_employee_level_id: i32,
+    #[validate(range(min = 1, max = 240, message = "Duration must be between 1 and 240 minutes"))]
+    pub duration_minutes: i64,
+    #[validate(length(min = 10, max = 1000, message = "Justification must contain between 10 and 1000 characters"))]
+    pub justification: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(body: serde_json::Value) -> EmployeeUpdate {
+        serde_json::from_value(body).unwrap()
+    }
+
+    #[test]
+    fn test_update_tells_null_apart_from_missing_fields() {
+        let missing = update(serde_json::json!({ "firstname": "Anna" }));
+        assert_eq!(missing.gender, None);
+        assert_eq!(missing.phone_number, None);
+
+        let cleared = update(serde_json::json!({ "gender": null, "phone_number": null }));
+        assert_eq!(cleared.gender, Some(None));
+        assert_eq!(cleared.phone_number, Some(None));
+
+        let set = update(serde_json::json!({ "gender": "F", "phone_number": "+32470000000" }));
+        assert_eq!(set.gender, Some(Some("F".to_string())));
+        assert_eq!(set.phone_number, Some(Some("+32470000000".to_string())));
+    }
+
+    #[test]
+    fn test_update_validation() {
+        for gender in ["M", "F", "O"] {
+            assert!(update(serde_json::json!({ "gender": gender })).validate().is_ok());
+        }
+        assert!(update(serde_json::json!({ "gender": "X" })).validate().is_err());
+        assert!(update(serde_json::json!({ "gender": "male" })).validate().is_err());
+        assert!(update(serde_json::json!({ "gender": null })).validate().is_ok());
+        assert!(update(serde_json::json!({ "phone_number": "0".repeat(21) })).validate().is_err());
+        assert!(update(serde_json::json!({ "personal_email": "not an email" })).validate().is_err());
+        assert!(update(serde_json::json!({ "firstname": "" })).validate().is_err());
+    }
+
+    #[test]
+    fn test_self_editable_fields() {
+        assert!(update(serde_json::json!({ "personal_email": "anna@example.com", "phone_number": null })).is_self_editable());
+        assert!(!update(serde_json::json!({ "phone_number": "0470", "lastname": "Doe" })).is_self_editable());
+        assert!(!update(serde_json::json!({ "gender": null })).is_self_editable());
+        assert!(!update(serde_json::json!({ "professional_email": "anna@plannify.be" })).is_self_editable());
+    }
+
+    #[test]
+    fn test_accreditation_extension() {
+        let start_at = Utc::now() + chrono::Duration::days(1);
+        let end_at = start_at + chrono::Duration::days(7);
+        let employee = LightEmployee { pk_employee_id: Uuid::nil(), firstname: "Anna".to_string(), lastname: "Doe".to_string(), gender: None, professional_email: "anna@plannify.be".to_string() };
+        let accreditation = EmployeeAccreditation {
+            recipient_employee: employee,
+            employee_level: EmployeeLevel { pk_employee_level_id: 1, level_index: 1, level_label: "ADMIN".to_string() },
+            authorizing_employee: None,
+            start_at,
+            end_at: Some(end_at),
+            created_at: Utc::now(),
+            revoked_at: None,
+            revoking_employee: None,
+            revocation_reason: None,
+            is_break_glass: false,
+            justification: None,
+        };
+        let update = |start_at, end_at| EmployeeAccreditationUpdate { start_at, end_at };
+
+        assert!(!accreditation.is_extended_by(&update(None, None)));
+        assert!(!accreditation.is_extended_by(&update(Some(start_at + chrono::Duration::hours(1)), Some(end_at - chrono::Duration::hours(1)))));
+        assert!(accreditation.is_extended_by(&update(None, Some(end_at + chrono::Duration::hours(1)))));
+        assert!(accreditation.is_extended_by(&update(Some(start_at - chrono::Duration::hours(1)), None)));
+
+        let open_ended = EmployeeAccreditation { end_at: None, ..accreditation };
+        assert!(!open_ended.is_extended_by(&update(None, Some(end_at))));
+    }
+}
@@ -1,27 +1,42 @@
 use axum::{
-    middleware::{from_fn, from_fn_with_state}, routing::get, Router
+    middleware::{from_fn, from_fn_with_state}, routing::{delete, get, post, put}, Router
 };
 use crate::{
-    employee::{handlers::{get_all_employees, get_employee_by_id, get_all_levels, get_level_by_id, get_all_authorizations, get_all_accreditations, get_employee_all_accreditations}, services::EmployeeService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+    employee::{handlers::{get_all_employees, get_employee_by_id, get_all_levels, get_level_by_id, get_level_matrix, compare_levels, get_all_authorizations, get_authorization_holders, get_employee_permissions_at, get_all_accreditations, get_all_break_glass_accreditations, create_break_glass, get_employee_all_accreditations, update_employee, deactivate_employee, reactivate_employee, create_employee_accreditation, update_employee_accreditation, revoke_employee_accreditation, create_level, update_level, delete_level, add_level_authorization, remove_level_authorization}, services::EmployeeService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
 };
 use std::sync::Arc;
 
 pub fn protected_employees_routes(
-    jwt_secret: String,
+    middleware_state: MiddlewareState,
     employee_service: Arc<EmployeeService>,
 ) -> Router {
-    let auth_state = MiddlewareState { jwt_secret };
-
     Router::new()
         .route("/employees", get(get_all_employees).route_layer(from_fn(with_required_permissions(vec![20]))))
         .route("/employees/{id}", get(get_employee_by_id).route_layer(from_fn(with_required_permissions(vec![20]))))
+        .route("/employees/{id}", put(update_employee).patch(update_employee))
+        .route("/employees/{id}", delete(deactivate_employee).route_layer(from_fn(with_required_permissions(vec![23]))))
+        .route("/employees/{id}/reactivate", post(reactivate_employee).route_layer(from_fn(with_required_permissions(vec![23]))))
         .route("/employees/levels", get(get_all_levels).route_layer(from_fn(with_required_permissions(vec![33]))))
+        .route("/employees/levels", post(create_level).route_layer(from_fn(with_required_permissions(vec![38]))))
+        .route("/employees/levels/matrix", get(get_level_matrix).route_layer(from_fn(with_required_permissions(vec![32, 33]))))
+        .route("/employees/levels/compare", get(compare_levels).route_layer(from_fn(with_required_permissions(vec![32, 33]))))
         .route("/employees/levels/{id}", get(get_level_by_id).route_layer(from_fn(with_required_permissions(vec![33]))))
+        .route("/employees/levels/{id}", put(update_level).patch(update_level).route_layer(from_fn(with_required_permissions(vec![39]))))
+        .route("/employees/levels/{id}", delete(delete_level).route_layer(from_fn(with_required_permissions(vec![40]))))
+        .route("/employees/levels/{id}/authorizations/{authorization_id}", post(add_level_authorization).route_layer(from_fn(with_required_permissions(vec![39]))))
+        .route("/employees/levels/{id}/authorizations/{authorization_id}", delete(remove_level_authorization).route_layer(from_fn(with_required_permissions(vec![39]))))
         .route("/employees/authorizations", get(get_all_authorizations).route_layer(from_fn(with_required_permissions(vec![32]))))
+        .route("/employees/authorizations/{id}/holders", get(get_authorization_holders).route_layer(from_fn(with_required_permissions(vec![34]))))
+        .route("/employees/{id}/permissions", get(get_employee_permissions_at).route_layer(from_fn(with_required_permissions(vec![34]))))
         .route("/employees/accreditations", get(get_all_accreditations).route_layer(from_fn(with_required_permissions(vec![34]))))
+        .route("/employees/accreditations/break-glass", get(get_all_break_glass_accreditations).route_layer(from_fn(with_required_permissions(vec![34]))))
+        .route("/employees/accreditations/break-glass", post(create_break_glass).route_layer(from_fn(with_required_permissions(vec![41]))))
         .route("/employees/{id}/accreditations", get(get_employee_all_accreditations).route_layer(from_fn(with_required_permissions(vec![34]))))
+        .route("/employees/{id}/accreditations", post(create_employee_accreditation).route_layer(from_fn(with_required_permissions(vec![35]))))
+        .route("/employees/{id}/accreditations/{created_at}", put(update_employee_accreditation).patch(update_employee_accreditation).route_layer(from_fn(with_required_permissions(vec![36]))))
+        .route("/employees/{id}/accreditations/{created_at}/revoke", post(revoke_employee_accreditation).route_layer(from_fn(with_required_permissions(vec![37]))))
         .layer(from_fn_with_state(
-            auth_state,
+            middleware_state,
             auth_middleware,
         ))
         .with_state(employee_service.clone())
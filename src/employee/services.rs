@@ -1,9 +1,120 @@
-use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::{PgConnection, PgExecutor, PgPool};
 use uuid::Uuid;
 
-use crate::{employee::models::{CrudType, Employee, EmployeeAccreditation, EmployeeAuthorization, EmployeeLevel, EmployeeLevelWithAuthorizations, EntityType, GetAllEmployeesQuery, LightEmployee}, errors::app_error::AppError, models::paginate::{PaginateQuery, PaginatedResponse, PaginationInfo}};
+use crate::{auth::services::invalidate_employee_permissions, employee::models::{AuthorizationHolder, AuthorizationHolders, BreakGlassCreate, CrudType, Employee, EmployeeAccreditation, EmployeeAccreditationCreate, EmployeeAccreditationRevoke, EmployeeAccreditationUpdate, EffectiveAuthorization, EmployeeAuthorization, EmployeeEffectivePermissions, EmployeeLevel, EmployeeLevelComparison, EmployeeLevelCreate, EmployeeLevelMatrix, EmployeeLevelMatrixAuthorization, EmployeeLevelMatrixCategory, EmployeeLevelMatrixFeature, EmployeeLevelUpdate, EmployeeLevelWithAuthorizations, EmployeeUpdate, EntityType, GetAllEmployeesQuery, LightEmployee, PermissionGrant}, errors::app_error::AppError, history::{models::{FieldChanges, NewActionHistory, SYSTEM_EMPLOYEE_ID}, services::record_action}, models::{paginate::{PaginateQuery, PaginatedResponse, PaginationInfo}, period::{validate_end_at, validate_start_at}}, pending_operation::{models::{PendingOperation, PendingOperationAction}, services::create_pending_operation}};
 use futures::stream::StreamExt;
+use tracing::warn;
+
+/// Label of the level that must always keep at least one active holder
+pub const ADMIN_LEVEL_LABEL: &str = "ADMIN";
+
+/// Key of the transaction lock taken before checking the active ADMIN holders
+const ADMIN_HOLDERS_LOCK_KEY: i64 = 0x41444d494e;
+
+/// Longest break-glass elevation to the ADMIN level, in minutes
+pub const BREAK_GLASS_ADMIN_MAX_MINUTES: i64 = 60;
+
+/// Open or close the grant period of an authorization type for a level,
+/// must be called alongside every change of `link_employee_authorization`
+pub async fn record_level_authorization_change<'e, E: PgExecutor<'e>>(executor: E, level_id: i32, authorization_type_id: i32, granted: bool) -> Result<(), AppError> {
+    if granted {
+        sqlx::query!(
+            r#"
+            INSERT INTO employee_level_authorization_histories (fk_employee_level_id, fk_employee_authorization_type_id, granted_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT DO NOTHING
+            "#,
+            level_id,
+            authorization_type_id
+        )
+        .execute(executor)
+        .await?;
+    } else {
+        sqlx::query!(
+            r#"
+            UPDATE employee_level_authorization_histories SET revoked_at = NOW()
+            WHERE fk_employee_level_id = $1 AND fk_employee_authorization_type_id = $2 AND revoked_at IS NULL
+            "#,
+            level_id,
+            authorization_type_id
+        )
+        .execute(executor)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Every grant of an authorization type to an employee at the date `$1`, with its source:
+/// a personal accreditation, an accreditation of a team or a delegation. Suspended employees hold none.
+const PERMISSION_GRANTS_AT: &str = r#"
+    SELECT grants.* FROM (
+        SELECT eaa.fk_recipient_employee_id AS fk_employee_id, elah.fk_employee_authorization_type_id, 'ACCREDITATION' AS source,
+            el.pk_employee_level_id, el.level_index, el.level_label,
+            NULL::UUID AS pk_employee_team_id, NULL::VARCHAR AS team_name,
+            NULL::UUID AS pk_employee_permission_delegation_id, NULL::UUID AS fk_delegator_employee_id
+        FROM employee_accreditation_authorizations eaa
+        JOIN employee_level_authorization_histories elah ON elah.fk_employee_level_id = eaa.fk_employee_level_id
+        JOIN employee_levels el ON el.pk_employee_level_id = eaa.fk_employee_level_id
+        WHERE eaa.start_at <= $1
+            AND (eaa.end_at IS NULL OR eaa.end_at > $1)
+            AND elah.granted_at <= $1
+            AND (elah.revoked_at IS NULL OR elah.revoked_at > $1)
+        UNION
+        SELECT etm.fk_employee_id, elah.fk_employee_authorization_type_id, 'TEAM',
+            el.pk_employee_level_id, el.level_index, el.level_label,
+            et.pk_employee_team_id, et.name,
+            NULL, NULL
+        FROM employee_team_memberships etm
+        JOIN employee_teams et ON et.pk_employee_team_id = etm.fk_employee_team_id
+        JOIN employee_team_accreditations eta ON eta.fk_employee_team_id = etm.fk_employee_team_id
+        JOIN employee_level_authorization_histories elah ON elah.fk_employee_level_id = eta.fk_employee_level_id
+        JOIN employee_levels el ON el.pk_employee_level_id = eta.fk_employee_level_id
+        WHERE etm.joined_at <= $1
+            AND (etm.left_at IS NULL OR etm.left_at > $1)
+            AND eta.start_at <= $1
+            AND (eta.end_at IS NULL OR eta.end_at > $1)
+            AND elah.granted_at <= $1
+            AND (elah.revoked_at IS NULL OR elah.revoked_at > $1)
+        UNION
+        SELECT epd.fk_delegate_employee_id, epdt.fk_employee_authorization_type_id, 'DELEGATION',
+            NULL, NULL, NULL,
+            NULL, NULL,
+            epd.pk_employee_permission_delegation_id, epd.fk_delegator_employee_id
+        FROM employee_permission_delegations epd
+        JOIN employee_permission_delegation_types epdt ON epdt.fk_employee_permission_delegation_id = epd.pk_employee_permission_delegation_id
+        JOIN employees delegator ON delegator.pk_employee_id = epd.fk_delegator_employee_id
+        WHERE epd.start_at <= $1
+            AND epd.end_at > $1
+            AND (epd.revoked_at IS NULL OR epd.revoked_at > $1)
+            AND (delegator.deactivated_at IS NULL OR delegator.deactivated_at > $1)
+            AND EXISTS (
+                SELECT 1 FROM employee_accreditation_authorizations deaa
+                JOIN employee_level_authorization_histories delah ON delah.fk_employee_level_id = deaa.fk_employee_level_id
+                WHERE deaa.fk_recipient_employee_id = epd.fk_delegator_employee_id
+                    AND delah.fk_employee_authorization_type_id = epdt.fk_employee_authorization_type_id
+                    AND deaa.start_at <= $1
+                    AND (deaa.end_at IS NULL OR deaa.end_at > $1)
+                    AND delah.granted_at <= $1
+                    AND (delah.revoked_at IS NULL OR delah.revoked_at > $1)
+            )
+            AND NOT EXISTS (
+                SELECT 1 FROM employee_suspensions des
+                WHERE des.fk_employee_id = epd.fk_delegator_employee_id
+                    AND des.start_at <= $1
+                    AND (des.end_at IS NULL OR des.end_at > $1)
+                    AND (des.lifted_at IS NULL OR des.lifted_at > $1)
+            )
+    ) grants
+    WHERE NOT EXISTS (
+        SELECT 1 FROM employee_suspensions es
+        WHERE es.fk_employee_id = grants.fk_employee_id
+            AND es.start_at <= $1
+            AND (es.end_at IS NULL OR es.end_at > $1)
+            AND (es.lifted_at IS NULL OR es.lifted_at > $1)
+    )
+"#;
 
 pub struct EmployeeService {
     pool: PgPool,
@@ -187,7 +298,14 @@ impl EmployeeService {
 
         sqlx::query_as!(
             Employee,
-            r#"SELECT * FROM employees WHERE pk_employee_id = $1"#,
+            r#"
+            SELECT
+                pk_employee_id, firstname, lastname, gender, personal_email,
+                login_password_hash, phone_number, professional_email,
+                professional_email_password, created_at, last_login_at, deactivated_at
+            FROM employees
+            WHERE pk_employee_id = $1
+            "#,
             _employee_uuid_id
         )
         .fetch_one(&self.pool)
@@ -195,6 +313,257 @@ impl EmployeeService {
         .map_err(|_| AppError::NotFound("Employee not found".to_string()))
     }
 
+    /// The authorization type is the one the author used, "Update an employee" or "Update their own profile"
+    pub async fn update_employee(&self, employee_id: &Uuid, update: &EmployeeUpdate, author_id: &Uuid, authorization_type_id: i32) -> Result<Employee, AppError> {
+        let existing = self.get_employee_by_id(&employee_id.to_string()).await?;
+
+        let professional_email_conflict = || AppError::Conflict("An other employee already uses this professional email".to_string(), "EMPLOYEE_PROFESSIONAL_EMAIL_ALREADY_EXISTS".to_string());
+
+        // if the professional email is modified, check if it already exists
+        if let Some(ref professional_email) = update.professional_email {
+            if self.professional_email_exists_except_employee(professional_email, employee_id).await? {
+                return Err(professional_email_conflict());
+            }
+        }
+
+        let mut changes = FieldChanges::new();
+        changes.track("firstname", &existing.firstname, update.firstname.as_ref());
+        changes.track("lastname", &existing.lastname, update.lastname.as_ref());
+        changes.track("gender", &existing.gender, update.gender.as_ref());
+        changes.track("personal_email", &existing.personal_email, update.personal_email.as_ref());
+        changes.track("phone_number", &existing.phone_number, update.phone_number.as_ref());
+        changes.track("professional_email", &existing.professional_email, update.professional_email.as_ref());
+
+        if changes.is_empty() {
+            return Ok(existing);
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let employee = sqlx::query_as!(
+            Employee,
+            r#"
+            UPDATE employees SET
+                firstname = COALESCE($1, firstname),
+                lastname = COALESCE($2, lastname),
+                gender = CASE WHEN $3 THEN $4 ELSE gender END,
+                personal_email = COALESCE($5, personal_email),
+                phone_number = CASE WHEN $6 THEN $7 ELSE phone_number END,
+                professional_email = COALESCE($8, professional_email)
+            WHERE pk_employee_id = $9
+            RETURNING
+                pk_employee_id, firstname, lastname, gender, personal_email,
+                login_password_hash, phone_number, professional_email,
+                professional_email_password, created_at, last_login_at, deactivated_at
+            "#,
+            update.firstname,
+            update.lastname,
+            // the nullable fields are only written when provided, null included
+            update.gender.is_some(),
+            update.gender.clone().flatten(),
+            update.personal_email,
+            update.phone_number.is_some(),
+            update.phone_number.clone().flatten(),
+            update.professional_email,
+            employee_id
+        )
+        .fetch_one(&mut *tx)
+        .await
+        // the unique constraint refuses an address taken concurrently since the check
+        .map_err(|e| match e.as_database_error() {
+            Some(db_error) if db_error.is_unique_violation() => professional_email_conflict(),
+            _ => AppError::from(e),
+        })?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id,
+            entity_id: Some(*employee_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "UPDATE_EMPLOYEE",
+                "changes": changes.into_value(),
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(employee)
+    }
+
+    // Deactivate an employee (soft delete), ending their accreditations and revoking their sessions
+    pub async fn deactivate_employee(&self, employee_id: &Uuid, author_id: &Uuid) -> Result<(), AppError> {
+        if employee_id == author_id {
+            return Err(AppError::Conflict("An employee cannot deactivate themselves".to_string(), "EMPLOYEE_SELF_DEACTIVATION".to_string()));
+        }
+
+        let employee = self.get_employee_by_id(&employee_id.to_string()).await?;
+        if employee.deactivated_at.is_some() {
+            return Err(AppError::Conflict("Employee has already been deactivated".to_string(), "EMPLOYEE_ALREADY_DEACTIVATED".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        if self.is_last_active_admin(&mut tx, employee_id).await? {
+            return Err(AppError::Conflict("The last active administrator cannot be deactivated".to_string(), "LAST_ACTIVE_ADMIN".to_string()));
+        }
+
+        sqlx::query!(
+            "UPDATE employees SET deactivated_at = NOW(), sessions_revoked_at = NOW() WHERE pk_employee_id = $1",
+            employee_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        // end the running accreditations and cancel the ones that have not started yet
+        let ended_accreditations = sqlx::query!(
+            r#"
+            UPDATE employee_accreditation_authorizations
+            SET end_at = GREATEST(start_at, NOW())
+            WHERE fk_recipient_employee_id = $1 AND (end_at IS NULL OR end_at > NOW())
+            "#,
+            employee_id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 23,
+            entity_id: Some(*employee_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "DEACTIVATE_EMPLOYEE",
+                "ended_accreditations": ended_accreditations,
+            }),
+        }).await?;
+
+        // the delegates of the employee lose the delegated authorizations
+        invalidate_employee_permissions(&mut *tx, &[*employee_id]).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    // Reactivate a deactivated employee, their accreditations have to be granted again
+    pub async fn reactivate_employee(&self, employee_id: &Uuid, author_id: &Uuid) -> Result<Employee, AppError> {
+        if *employee_id == SYSTEM_EMPLOYEE_ID {
+            return Err(AppError::Conflict("The system employee cannot be reactivated".to_string(), "SYSTEM_EMPLOYEE".to_string()));
+        }
+
+        let previous = self.get_employee_by_id(&employee_id.to_string()).await?;
+        if previous.deactivated_at.is_none() {
+            return Err(AppError::Conflict("Employee is not deactivated".to_string(), "EMPLOYEE_NOT_DEACTIVATED".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let employee = sqlx::query_as!(
+            Employee,
+            r#"
+            UPDATE employees SET deactivated_at = NULL
+            WHERE pk_employee_id = $1
+            RETURNING
+                pk_employee_id, firstname, lastname, gender, personal_email,
+                login_password_hash, phone_number, professional_email,
+                professional_email_password, created_at, last_login_at, deactivated_at
+            "#,
+            employee_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 23,
+            entity_id: Some(*employee_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "REACTIVATE_EMPLOYEE",
+                "deactivated_at": previous.deactivated_at,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(employee)
+    }
+
+    // Check if the employee is the only active and not suspended employee currently holding the ADMIN level
+    // A transaction lock makes concurrent deactivations or revocations wait for each other,
+    // it has to be called inside the transaction ending the accreditation
+    pub async fn is_last_active_admin(&self, conn: &mut PgConnection, employee_id: &Uuid) -> Result<bool, AppError> {
+        sqlx::query!("SELECT pg_advisory_xact_lock($1)", ADMIN_HOLDERS_LOCK_KEY)
+            .execute(&mut *conn)
+            .await?;
+
+        // the lock may have been waited for, the transaction start is not the current time anymore
+        let mut holders = sqlx::query_scalar!(
+            r#"
+            SELECT eaa.fk_recipient_employee_id
+            FROM employee_accreditation_authorizations eaa
+            JOIN employee_levels el ON eaa.fk_employee_level_id = el.pk_employee_level_id
+            JOIN employees e ON eaa.fk_recipient_employee_id = e.pk_employee_id
+            WHERE el.level_label = $1
+                AND e.deactivated_at IS NULL
+                AND NOT eaa.is_break_glass
+                AND eaa.start_at <= clock_timestamp()
+                AND (eaa.end_at IS NULL OR eaa.end_at > clock_timestamp())
+                AND NOT EXISTS (
+                    SELECT 1 FROM employee_suspensions es
+                    WHERE es.fk_employee_id = e.pk_employee_id
+                        AND es.lifted_at IS NULL
+                        AND es.start_at <= clock_timestamp()
+                        AND (es.end_at IS NULL OR es.end_at > clock_timestamp())
+                )
+            "#,
+            ADMIN_LEVEL_LABEL
+        )
+        .fetch_all(&mut *conn)
+        .await?;
+        holders.sort();
+        holders.dedup();
+
+        Ok(holders.len() == 1 && holders[0] == *employee_id)
+    }
+
+    // Lowest level index (highest rank) among the levels an employee currently holds,
+    // break-glass elevations are not taken into account
+    pub async fn get_employee_highest_level_index(&self, employee_id: &Uuid) -> Result<Option<i32>, AppError> {
+        let level_index = sqlx::query_scalar!(
+            r#"
+            SELECT MIN(el.level_index)
+            FROM employee_accreditation_authorizations eaa
+            JOIN employee_levels el ON eaa.fk_employee_level_id = el.pk_employee_level_id
+            WHERE eaa.fk_recipient_employee_id = $1
+                AND NOT eaa.is_break_glass
+                AND el.deleted_at IS NULL
+                AND eaa.start_at <= NOW()
+                AND (eaa.end_at IS NULL OR eaa.end_at > NOW())
+            "#,
+            employee_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(level_index)
+    }
+
+    // Check if a professional email is used by another employee
+    pub async fn professional_email_exists_except_employee(&self, professional_email: &str, employee_id: &Uuid) -> Result<bool, AppError> {
+        let count = sqlx::query!(
+            "SELECT COUNT(*) as count FROM employees WHERE professional_email = $1 AND pk_employee_id != $2",
+            professional_email,
+            employee_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count.count.unwrap_or(0) > 0)
+    }
+
     pub async fn get_light_employee_by_id(&self, employee_id: &str) -> Result<LightEmployee, AppError> {
         let _employee_uuid_id: Uuid = Uuid::parse_str(employee_id).expect("Employee ID is not a valid UUID");
     
@@ -301,6 +670,8 @@ impl EmployeeService {
             r#"
             SELECT pk_employee_level_id, level_index, level_label
             FROM employee_levels
+            WHERE deleted_at IS NULL
+            ORDER BY level_index
             "#
         )
         .fetch_all(&self.pool).await?;
@@ -327,6 +698,290 @@ impl EmployeeService {
         Ok(level.unwrap())
     }
 
+    pub async fn get_admin_level(&self) -> Result<EmployeeLevel, AppError> {
+        sqlx::query_as!(
+            EmployeeLevel,
+            r#"
+            SELECT pk_employee_level_id, level_index, level_label
+            FROM employee_levels
+            WHERE level_label = $1 AND deleted_at IS NULL
+            "#,
+            ADMIN_LEVEL_LABEL
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Level not found".to_string()))
+    }
+
+    pub async fn employee_level_is_deleted(&self, level_id: i32) -> Result<bool, AppError> {
+        let deleted_at = sqlx::query_scalar!(
+            "SELECT deleted_at FROM employee_levels WHERE pk_employee_level_id = $1",
+            level_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Level not found".to_string()))?;
+
+        Ok(deleted_at.is_some())
+    }
+
+    // Check if a label is used by another level that has not been deleted
+    pub async fn level_label_exists_except_level(&self, level_label: &str, level_id: Option<i32>) -> Result<bool, AppError> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!" FROM employee_levels
+            WHERE level_label = $1 AND deleted_at IS NULL AND ($2::INTEGER IS NULL OR pk_employee_level_id != $2)
+            "#,
+            level_label,
+            level_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count > 0)
+    }
+
+    pub async fn create_employee_level(&self, create_req: &EmployeeLevelCreate, author_id: &Uuid) -> Result<EmployeeLevel, AppError> {
+        if self.level_label_exists_except_level(&create_req.level_label, None).await? {
+            return Err(AppError::Conflict("A level with this label already exists".to_string(), "LEVEL_LABEL_ALREADY_EXISTS".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let level = sqlx::query_as!(
+            EmployeeLevel,
+            r#"
+            INSERT INTO employee_levels (level_index, level_label)
+            VALUES ($1, $2)
+            RETURNING pk_employee_level_id, level_index, level_label
+            "#,
+            create_req.level_index,
+            create_req.level_label
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 38,
+            entity_id: None,
+            entity_type: EntityType::EMPLOYEE_LEVEL,
+            description: serde_json::json!({
+                "action": "CREATE_LEVEL",
+                "employee_level_id": level.pk_employee_level_id,
+                "level_index": level.level_index,
+                "level_label": level.level_label,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(level)
+    }
+
+    pub async fn update_employee_level(&self, level_id: i32, update_req: &EmployeeLevelUpdate, author_id: &Uuid) -> Result<EmployeeLevel, AppError> {
+        let existing = self.get_employee_level_by_id(level_id).await?;
+        if self.employee_level_is_deleted(level_id).await? {
+            return Err(AppError::Conflict("A deleted level cannot be modified".to_string(), "LEVEL_DELETED".to_string()));
+        }
+
+        if let Some(ref level_label) = update_req.level_label {
+            if existing.level_label == ADMIN_LEVEL_LABEL && level_label != ADMIN_LEVEL_LABEL {
+                return Err(AppError::Conflict(format!("The {} level cannot be renamed", ADMIN_LEVEL_LABEL), "ADMIN_LEVEL_PROTECTED".to_string()));
+            }
+            if self.level_label_exists_except_level(level_label, Some(level_id)).await? {
+                return Err(AppError::Conflict("A level with this label already exists".to_string(), "LEVEL_LABEL_ALREADY_EXISTS".to_string()));
+            }
+        }
+
+        let mut changes = FieldChanges::new();
+        changes.track("level_index", &existing.level_index, update_req.level_index.as_ref());
+        changes.track("level_label", &existing.level_label, update_req.level_label.as_ref());
+
+        if changes.is_empty() {
+            return Ok(existing);
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let level = sqlx::query_as!(
+            EmployeeLevel,
+            r#"
+            UPDATE employee_levels SET
+                level_index = COALESCE($1, level_index),
+                level_label = COALESCE($2, level_label)
+            WHERE pk_employee_level_id = $3
+            RETURNING pk_employee_level_id, level_index, level_label
+            "#,
+            update_req.level_index,
+            update_req.level_label,
+            level_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 39,
+            entity_id: None,
+            entity_type: EntityType::EMPLOYEE_LEVEL,
+            description: serde_json::json!({
+                "action": "UPDATE_LEVEL",
+                "employee_level_id": level_id,
+                "changes": changes.into_value(),
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(level)
+    }
+
+    // Delete a level (soft delete), it is kept so that past accreditations still reference it
+    pub async fn delete_employee_level(&self, level_id: i32, author_id: &Uuid) -> Result<(), AppError> {
+        let existing = self.get_employee_level_by_id(level_id).await?;
+        if self.employee_level_is_deleted(level_id).await? {
+            return Err(AppError::NotFound("Level has already been deleted".to_string()));
+        }
+        if existing.level_label == ADMIN_LEVEL_LABEL {
+            return Err(AppError::Conflict(format!("The {} level cannot be deleted", ADMIN_LEVEL_LABEL), "ADMIN_LEVEL_PROTECTED".to_string()));
+        }
+
+        // running and upcoming accreditations still rely on the level
+        let accreditations_in_use = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!" FROM employee_accreditation_authorizations
+            WHERE fk_employee_level_id = $1 AND (end_at IS NULL OR end_at > NOW())
+            "#,
+            level_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        if accreditations_in_use > 0 {
+            return Err(AppError::Conflict(format!("The level is still used by {} active accreditation(s)", accreditations_in_use), "LEVEL_IN_USE".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            "UPDATE employee_levels SET deleted_at = NOW() WHERE pk_employee_level_id = $1",
+            level_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 40,
+            entity_id: None,
+            entity_type: EntityType::EMPLOYEE_LEVEL,
+            description: serde_json::json!({
+                "action": "DELETE_LEVEL",
+                "employee_level_id": level_id,
+                "level_label": existing.level_label,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    // Grant an authorization type to every holder of the level
+    pub async fn add_employee_level_authorization(&self, level_id: i32, authorization_type_id: i32, author_id: &Uuid) -> Result<EmployeeLevelWithAuthorizations, AppError> {
+        self.change_employee_level_authorization(level_id, authorization_type_id, true, author_id).await
+    }
+
+    // Withdraw an authorization type from every holder of the level
+    pub async fn remove_employee_level_authorization(&self, level_id: i32, authorization_type_id: i32, author_id: &Uuid) -> Result<EmployeeLevelWithAuthorizations, AppError> {
+        self.change_employee_level_authorization(level_id, authorization_type_id, false, author_id).await
+    }
+
+    async fn change_employee_level_authorization(&self, level_id: i32, authorization_type_id: i32, linked: bool, author_id: &Uuid) -> Result<EmployeeLevelWithAuthorizations, AppError> {
+        self.get_employee_level_by_id(level_id).await?;
+        if self.employee_level_is_deleted(level_id).await? {
+            return Err(AppError::Conflict("A deleted level cannot be modified".to_string(), "LEVEL_DELETED".to_string()));
+        }
+
+        let authorization_type_exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS(SELECT 1 FROM employee_authorization_types WHERE pk_employee_authorization_type_id = $1) as "exists!""#,
+            authorization_type_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        if !authorization_type_exists {
+            return Err(AppError::NotFound("Authorization type not found".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let rows_affected = if linked {
+            sqlx::query!(
+                r#"
+                INSERT INTO link_employee_authorization (fk_employee_authorization_type_id, fk_employee_level_id)
+                VALUES ($1, $2)
+                ON CONFLICT DO NOTHING
+                "#,
+                authorization_type_id,
+                level_id
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
+        } else {
+            sqlx::query!(
+                "DELETE FROM link_employee_authorization WHERE fk_employee_authorization_type_id = $1 AND fk_employee_level_id = $2",
+                authorization_type_id,
+                level_id
+            )
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
+        };
+
+        if rows_affected == 0 {
+            return Err(if linked {
+                AppError::Conflict("The level already has this authorization".to_string(), "LEVEL_AUTHORIZATION_ALREADY_EXISTS".to_string())
+            } else {
+                AppError::NotFound("The level does not have this authorization".to_string())
+            });
+        }
+
+        record_level_authorization_change(&mut *tx, level_id, authorization_type_id, linked).await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 39,
+            entity_id: None,
+            entity_type: EntityType::EMPLOYEE_LEVEL,
+            description: serde_json::json!({
+                "action": if linked { "ADD_LEVEL_AUTHORIZATION" } else { "REMOVE_LEVEL_AUTHORIZATION" },
+                "employee_level_id": level_id,
+                "employee_authorization_type_id": authorization_type_id,
+            }),
+        }).await?;
+
+        // the current holders of the level have to refresh their permissions
+        let holders = sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT fk_recipient_employee_id
+            FROM employee_accreditation_authorizations
+            WHERE fk_employee_level_id = $1 AND (end_at IS NULL OR end_at > NOW())
+            "#,
+            level_id
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        invalidate_employee_permissions(&mut *tx, &holders).await?;
+
+        tx.commit().await?;
+
+        self.get_employee_level_with_authorizations_by_id(level_id).await
+    }
+
     pub async fn get_employee_level_with_authorizations_by_id(&self, level_id: i32) -> Result<EmployeeLevelWithAuthorizations, AppError> {
         let level = self.get_employee_level_by_id(level_id).await?;
         let authorizations = self.get_all_employee_authorizations_by_level_id(level_id).await?;
@@ -338,6 +993,222 @@ impl EmployeeService {
         })
     }
 
+    // Every authorization type with the active levels it is granted to
+    pub async fn get_employee_level_matrix(&self) -> Result<EmployeeLevelMatrix, AppError> {
+        let levels = self.get_all_employee_levels().await?;
+        let mut authorizations = self.get_all_employee_authorizations().await?;
+        authorizations.sort_by_key(|authorization| (authorization.category_index, authorization.authorization_index, authorization.pk_employee_authorization_id));
+
+        let grants = sqlx::query!(
+            r#"
+            SELECT lea.fk_employee_authorization_type_id, lea.fk_employee_level_id
+            FROM link_employee_authorization lea
+            JOIN employee_levels el ON el.pk_employee_level_id = lea.fk_employee_level_id
+            WHERE el.deleted_at IS NULL
+            ORDER BY el.level_index
+            "#
+        )
+        .fetch_all(&self.pool).await?;
+
+        let mut categories: Vec<EmployeeLevelMatrixCategory> = Vec::new();
+        for authorization in authorizations {
+            let granted_level_ids = grants.iter()
+                .filter(|grant| grant.fk_employee_authorization_type_id == authorization.pk_employee_authorization_id)
+                .map(|grant| grant.fk_employee_level_id)
+                .collect();
+
+            if categories.last().is_none_or(|category| category.category_name_code != authorization.category_name_code) {
+                categories.push(EmployeeLevelMatrixCategory {
+                    category_name_code: authorization.category_name_code.clone(),
+                    category_entity_type: authorization.category_entity_type.clone(),
+                    category_index: authorization.category_index,
+                    features: Vec::new(),
+                });
+            }
+            let features = &mut categories.last_mut().unwrap().features;
+            if features.last().is_none_or(|feature| feature.authorization_feature_code != authorization.authorization_feature_code) {
+                features.push(EmployeeLevelMatrixFeature {
+                    authorization_feature_code: authorization.authorization_feature_code.clone(),
+                    authorization_index: authorization.authorization_index,
+                    authorizations: Vec::new(),
+                });
+            }
+            features.last_mut().unwrap().authorizations.push(EmployeeLevelMatrixAuthorization {
+                pk_employee_authorization_id: authorization.pk_employee_authorization_id,
+                crud_type: authorization.crud_type,
+                description: authorization.description,
+                granted_level_ids,
+            });
+        }
+
+        Ok(EmployeeLevelMatrix { levels, categories })
+    }
+
+    // Authorizations granted to one level and not to the other
+    pub async fn compare_employee_levels(&self, level_a_id: i32, level_b_id: i32) -> Result<EmployeeLevelComparison, AppError> {
+        let level_a = self.get_employee_level_with_authorizations_by_id(level_a_id).await?;
+        let level_b = self.get_employee_level_with_authorizations_by_id(level_b_id).await?;
+
+        let ids_a: Vec<i32> = level_a.authorizations.iter().map(|authorization| authorization.pk_employee_authorization_id).collect();
+        let ids_b: Vec<i32> = level_b.authorizations.iter().map(|authorization| authorization.pk_employee_authorization_id).collect();
+
+        let mut only_in_a: Vec<EmployeeAuthorization> = level_a.authorizations.into_iter()
+            .filter(|authorization| !ids_b.contains(&authorization.pk_employee_authorization_id))
+            .collect();
+        let mut only_in_b: Vec<EmployeeAuthorization> = level_b.authorizations.into_iter()
+            .filter(|authorization| !ids_a.contains(&authorization.pk_employee_authorization_id))
+            .collect();
+        only_in_a.sort_by_key(|authorization| authorization.pk_employee_authorization_id);
+        only_in_b.sort_by_key(|authorization| authorization.pk_employee_authorization_id);
+
+        Ok(EmployeeLevelComparison {
+            level_a: EmployeeLevel {
+                pk_employee_level_id: level_a.pk_employee_level_id,
+                level_index: level_a.level_index,
+                level_label: level_a.level_label,
+            },
+            level_b: EmployeeLevel {
+                pk_employee_level_id: level_b.pk_employee_level_id,
+                level_index: level_b.level_index,
+                level_label: level_b.level_label,
+            },
+            only_in_a,
+            only_in_b,
+        })
+    }
+
+    // Permissions of an employee at a given date, rebuilt from the personal and team accreditations,
+    // the delegations, the level grant periods and the suspensions in effect at that date
+    pub async fn get_employee_permissions_at(&self, employee_id: &Uuid, at: &DateTime<Utc>) -> Result<EmployeeEffectivePermissions, AppError> {
+        self.get_employee_by_id(&employee_id.to_string()).await?;
+
+        let suspended = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM employee_suspensions
+                WHERE fk_employee_id = $1
+                AND start_at <= $2
+                AND (end_at IS NULL OR end_at > $2)
+                AND (lifted_at IS NULL OR lifted_at > $2)
+            ) as "suspended!"
+            "#,
+            employee_id,
+            at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        let employee_levels = sqlx::query_as!(
+            EmployeeLevel,
+            r#"
+            SELECT DISTINCT el.pk_employee_level_id, el.level_index, el.level_label
+            FROM employee_accreditation_authorizations eaa
+            JOIN employee_levels el ON el.pk_employee_level_id = eaa.fk_employee_level_id
+            WHERE eaa.fk_recipient_employee_id = $1
+            AND eaa.start_at <= $2
+            AND (eaa.end_at IS NULL OR eaa.end_at > $2)
+            ORDER BY el.level_index
+            "#,
+            employee_id,
+            at
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let authorizations = if suspended {
+            Vec::new()
+        } else {
+            let grants = sqlx::query_as::<_, PermissionGrant>(&format!(
+                "{} AND grants.fk_employee_id = $2 ORDER BY grants.fk_employee_authorization_type_id, grants.source, grants.level_index",
+                PERMISSION_GRANTS_AT
+            ))
+            .bind(at)
+            .bind(employee_id)
+            .fetch_all(&self.pool)
+            .await?;
+
+            let all_authorizations = self.get_all_employee_authorizations().await?;
+            let mut authorizations: Vec<EffectiveAuthorization> = Vec::new();
+            for grant in grants {
+                let authorization_type_id = grant.fk_employee_authorization_type_id;
+                let Some(source) = grant.to_source() else { continue };
+
+                match authorizations.last_mut() {
+                    Some(last) if last.authorization.pk_employee_authorization_id == authorization_type_id => last.sources.push(source),
+                    _ => {
+                        let Some(authorization) = all_authorizations.iter().find(|a| a.pk_employee_authorization_id == authorization_type_id) else { continue };
+                        authorizations.push(EffectiveAuthorization {
+                            authorization: authorization.clone(),
+                            sources: vec![source],
+                        });
+                    }
+                }
+            }
+            authorizations
+        };
+
+        Ok(EmployeeEffectivePermissions {
+            pk_employee_id: *employee_id,
+            at: *at,
+            suspended,
+            employee_levels,
+            authorizations,
+        })
+    }
+
+    // Employees who held an authorization type at a given date, with where they held it from
+    pub async fn get_authorization_holders_at(&self, authorization_type_id: i32, at: &DateTime<Utc>) -> Result<AuthorizationHolders, AppError> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM employee_authorization_types WHERE pk_employee_authorization_type_id = $1) as "exists!""#,
+            authorization_type_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        if !exists {
+            return Err(AppError::NotFound("Authorization type not found".to_string()));
+        }
+
+        let grants = sqlx::query_as::<_, PermissionGrant>(&format!(
+            "{} AND grants.fk_employee_authorization_type_id = $2 ORDER BY grants.source, grants.level_index",
+            PERMISSION_GRANTS_AT
+        ))
+        .bind(at)
+        .bind(authorization_type_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let employee_ids: Vec<Uuid> = grants.iter().map(|grant| grant.fk_employee_id).collect();
+        let employees = sqlx::query_as!(
+            LightEmployee,
+            r#"
+            SELECT pk_employee_id, firstname, lastname, gender, professional_email
+            FROM employees
+            WHERE pk_employee_id = ANY($1)
+            ORDER BY lastname, firstname
+            "#,
+            &employee_ids
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        // grouped by employee, in the order of their names
+        let holders = employees.into_iter()
+            .flat_map(|employee| {
+                grants.iter()
+                    .filter(|grant| grant.fk_employee_id == employee.pk_employee_id)
+                    .filter_map(|grant| grant.to_source())
+                    .map(|source| AuthorizationHolder { employee: employee.clone(), source })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        Ok(AuthorizationHolders {
+            pk_employee_authorization_id: authorization_type_id,
+            at: *at,
+            holders,
+        })
+    }
+
     pub async fn get_all_employee_accreditations(&self, filters: &PaginateQuery) -> Result<(Vec<EmployeeAccreditation>, u64), AppError> {
         let total_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) as count FROM employee_accreditation_authorizations")
                 .fetch_one(&self.pool)
@@ -350,6 +1221,11 @@ impl EmployeeService {
             start_at: DateTime<Utc>,
             end_at: Option<DateTime<Utc>>,
             created_at: DateTime<Utc>,
+            revoked_at: Option<DateTime<Utc>>,
+            fk_revoking_employee_id: Option<Uuid>,
+            revocation_reason: Option<String>,
+            is_break_glass: bool,
+            justification: Option<String>,
         }
 
         let accreditations_row = sqlx::query_as!(
@@ -361,7 +1237,12 @@ impl EmployeeService {
                 eaa.fk_authorizing_employee_id,
                 eaa.start_at,
                 eaa.end_at,
-                eaa.created_at
+                eaa.created_at,
+                eaa.revoked_at,
+                eaa.fk_revoking_employee_id,
+                eaa.revocation_reason,
+                eaa.is_break_glass,
+                eaa.justification
             FROM employee_accreditation_authorizations eaa
             ORDER BY created_at ASC
             LIMIT $1
@@ -380,6 +1261,11 @@ impl EmployeeService {
                 let start_at = row.start_at;
                 let end_at = row.end_at;
                 let created_at = row.created_at;
+                let revoked_at = row.revoked_at;
+                let fk_revoking_employee_id = row.fk_revoking_employee_id;
+                let revocation_reason = row.revocation_reason;
+                let is_break_glass = row.is_break_glass;
+                let justification = row.justification;
 
                 async move {
                     let recipient_employee = match self.get_light_employee_by_id(&fk_recipient_employee_id.to_string()).await.ok() {
@@ -416,6 +1302,22 @@ impl EmployeeService {
                         },
                     };
 
+                    let revoking_employee: Option<LightEmployee> = match fk_revoking_employee_id {
+                        Some(revoking_id) => {
+                            match self.get_light_employee_by_id(&revoking_id.to_string()).await.ok() {
+                                Some(emp) => {
+                                    Some(emp)
+                                },
+                                None => {
+                                    return None;
+                                },
+                            }
+                        },
+                        None => {
+                            None
+                        },
+                    };
+
                     Some(EmployeeAccreditation {
                         recipient_employee,
                         employee_level,
@@ -423,6 +1325,11 @@ impl EmployeeService {
                         start_at,
                         end_at,
                         created_at,
+                        revoked_at,
+                        revoking_employee,
+                        revocation_reason,
+                        is_break_glass,
+                        justification,
                     })
                 }
             })
@@ -447,6 +1354,11 @@ impl EmployeeService {
             start_at: DateTime<Utc>,
             end_at: Option<DateTime<Utc>>,
             created_at: DateTime<Utc>,
+            revoked_at: Option<DateTime<Utc>>,
+            fk_revoking_employee_id: Option<Uuid>,
+            revocation_reason: Option<String>,
+            is_break_glass: bool,
+            justification: Option<String>,
         }
 
         let accreditations_row = sqlx::query_as!(
@@ -458,7 +1370,12 @@ impl EmployeeService {
                 eaa.fk_authorizing_employee_id,
                 eaa.start_at,
                 eaa.end_at,
-                eaa.created_at
+                eaa.created_at,
+                eaa.revoked_at,
+                eaa.fk_revoking_employee_id,
+                eaa.revocation_reason,
+                eaa.is_break_glass,
+                eaa.justification
             FROM employee_accreditation_authorizations eaa
             WHERE eaa.fk_recipient_employee_id = $1
             ORDER BY created_at ASC
@@ -479,6 +1396,11 @@ impl EmployeeService {
                 let start_at = row.start_at;
                 let end_at = row.end_at;
                 let created_at = row.created_at;
+                let revoked_at = row.revoked_at;
+                let fk_revoking_employee_id = row.fk_revoking_employee_id;
+                let revocation_reason = row.revocation_reason;
+                let is_break_glass = row.is_break_glass;
+                let justification = row.justification;
 
                 async move {
                     let recipient_employee = match self.get_light_employee_by_id(&fk_recipient_employee_id.to_string()).await.ok() {
@@ -515,6 +1437,22 @@ impl EmployeeService {
                         },
                     };
 
+                    let revoking_employee: Option<LightEmployee> = match fk_revoking_employee_id {
+                        Some(revoking_id) => {
+                            match self.get_light_employee_by_id(&revoking_id.to_string()).await.ok() {
+                                Some(emp) => {
+                                    Some(emp)
+                                },
+                                None => {
+                                    return None;
+                                },
+                            }
+                        },
+                        None => {
+                            None
+                        },
+                    };
+
                     Some(EmployeeAccreditation {
                         recipient_employee,
                         employee_level,
@@ -522,6 +1460,11 @@ impl EmployeeService {
                         start_at,
                         end_at,
                         created_at,
+                        revoked_at,
+                        revoking_employee,
+                        revocation_reason,
+                        is_break_glass,
+                        justification,
                     })
                 }
             })
@@ -530,4 +1473,424 @@ impl EmployeeService {
 
         Ok((accreditations, total_count))
     }
-}
\ No newline at end of file
+
+    // Get a single accreditation, identified by its recipient and creation date
+    pub async fn get_employee_accreditation(&self, recipient_id: &Uuid, created_at: &DateTime<Utc>) -> Result<EmployeeAccreditation, AppError> {
+        let row = sqlx::query!(
+            r#"
+            SELECT fk_employee_level_id, fk_authorizing_employee_id, start_at, end_at, created_at, revoked_at, fk_revoking_employee_id, revocation_reason, is_break_glass, justification
+            FROM employee_accreditation_authorizations
+            WHERE fk_recipient_employee_id = $1 AND created_at = $2
+            "#,
+            recipient_id,
+            created_at
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Accreditation not found".to_string()))?;
+
+        let recipient_employee = self.get_light_employee_by_id(&recipient_id.to_string()).await?;
+        let employee_level = self.get_employee_level_by_id(row.fk_employee_level_id).await?;
+        let authorizing_employee = match row.fk_authorizing_employee_id {
+            Some(authorizing_id) => Some(self.get_light_employee_by_id(&authorizing_id.to_string()).await?),
+            None => None,
+        };
+        let revoking_employee = match row.fk_revoking_employee_id {
+            Some(revoking_id) => Some(self.get_light_employee_by_id(&revoking_id.to_string()).await?),
+            None => None,
+        };
+
+        Ok(EmployeeAccreditation {
+            recipient_employee,
+            employee_level,
+            authorizing_employee,
+            start_at: row.start_at,
+            end_at: row.end_at,
+            created_at: row.created_at,
+            revoked_at: row.revoked_at,
+            revoking_employee,
+            revocation_reason: row.revocation_reason,
+            is_break_glass: row.is_break_glass,
+            justification: row.justification,
+        })
+    }
+
+    // Grant a level to an employee for a period
+    // The ADMIN level cannot be granted by a single employee, see `request_admin_accreditation`
+    pub async fn create_employee_accreditation(&self, recipient_id: &Uuid, create_req: &EmployeeAccreditationCreate, author_id: &Uuid) -> Result<EmployeeAccreditation, AppError> {
+        let level = self.get_employee_level_by_id(create_req.fk_employee_level_id).await?;
+        if level.level_label == ADMIN_LEVEL_LABEL {
+            return Err(AppError::Forbidden(format!("Granting the {} level requires the approval of a second employee", ADMIN_LEVEL_LABEL), "FOUR_EYES_REQUIRED".to_string()));
+        }
+
+        validate_start_at(&create_req.start_at, "accreditation")?;
+        self.validate_accreditation_grant(recipient_id, &level, &create_req.start_at, create_req.end_at.as_ref()).await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let created_at = sqlx::query_scalar!(
+            r#"
+            INSERT INTO employee_accreditation_authorizations (fk_recipient_employee_id, fk_employee_level_id, fk_authorizing_employee_id, start_at, end_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING created_at
+            "#,
+            recipient_id,
+            level.pk_employee_level_id,
+            author_id,
+            create_req.start_at,
+            create_req.end_at
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 35,
+            entity_id: Some(*recipient_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "GRANT_ACCREDITATION",
+                "accreditation_created_at": created_at,
+                "employee_level_id": level.pk_employee_level_id,
+                "employee_level_label": level.level_label,
+                "start_at": create_req.start_at,
+                "end_at": create_req.end_at,
+            }),
+        }).await?;
+
+        invalidate_employee_permissions(&mut *tx, &[*recipient_id]).await?;
+
+        tx.commit().await?;
+
+        self.get_employee_accreditation(recipient_id, &created_at).await
+    }
+
+    // Store an ADMIN accreditation as a pending operation, it is granted once another employee approves it
+    pub async fn request_admin_accreditation(&self, recipient_id: &Uuid, create_req: &EmployeeAccreditationCreate, author_id: &Uuid) -> Result<PendingOperation, AppError> {
+        let level = self.get_admin_level().await?;
+
+        validate_start_at(&create_req.start_at, "accreditation")?;
+        self.validate_accreditation_grant(recipient_id, &level, &create_req.start_at, create_req.end_at.as_ref()).await?;
+
+        let action = PendingOperationAction::GRANT_ADMIN_ACCREDITATION {
+            fk_recipient_employee_id: *recipient_id,
+            start_at: create_req.start_at,
+            end_at: create_req.end_at,
+        };
+        create_pending_operation(&self.pool, &action, author_id, None).await
+    }
+
+    // Checks shared by every way of granting a level
+    pub async fn validate_accreditation_grant(&self, recipient_id: &Uuid, level: &EmployeeLevel, start_at: &DateTime<Utc>, end_at: Option<&DateTime<Utc>>) -> Result<(), AppError> {
+        let recipient = self.get_employee_by_id(&recipient_id.to_string()).await?;
+        if recipient.deactivated_at.is_some() {
+            return Err(AppError::Conflict("A deactivated employee cannot receive an accreditation".to_string(), "EMPLOYEE_DEACTIVATED".to_string()));
+        }
+
+        if self.employee_level_is_deleted(level.pk_employee_level_id).await? {
+            return Err(AppError::Conflict("A deleted level cannot be granted".to_string(), "LEVEL_DELETED".to_string()));
+        }
+
+        if end_at.is_some_and(|end_at| end_at <= start_at) {
+            return Err(AppError::Validation("The accreditation end must be after its start".to_string()));
+        }
+
+        if self.accreditation_overlaps(recipient_id, level.pk_employee_level_id, start_at, end_at, None).await? {
+            return Err(AppError::Conflict("The employee already holds this level during the requested period".to_string(), "ACCREDITATION_OVERLAP".to_string()));
+        }
+
+        Ok(())
+    }
+
+    // Grant a level to the requester for a bounded duration, the elevation ends on its own
+    pub async fn create_break_glass(&self, requester_id: &Uuid, create_req: &BreakGlassCreate) -> Result<EmployeeAccreditation, AppError> {
+        let level = self.get_employee_level_by_id(create_req.fk_employee_level_id).await?;
+        // an ADMIN elevation is kept shorter, the audit entry lets the other admins review it
+        let is_admin_elevation = level.level_label == ADMIN_LEVEL_LABEL;
+        if is_admin_elevation && create_req.duration_minutes > BREAK_GLASS_ADMIN_MAX_MINUTES {
+            return Err(AppError::Validation(format!("A break-glass elevation to the {} level cannot last more than {} minutes", ADMIN_LEVEL_LABEL, BREAK_GLASS_ADMIN_MAX_MINUTES)));
+        }
+        if self.employee_level_is_deleted(level.pk_employee_level_id).await? {
+            return Err(AppError::Conflict("A deleted level cannot be granted".to_string(), "LEVEL_DELETED".to_string()));
+        }
+
+        let start_at = Utc::now();
+        let end_at = start_at + Duration::minutes(create_req.duration_minutes);
+
+        if self.accreditation_overlaps(requester_id, level.pk_employee_level_id, &start_at, Some(&end_at), None).await? {
+            return Err(AppError::Conflict("The employee already holds this level during the requested period".to_string(), "ACCREDITATION_OVERLAP".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let created_at = sqlx::query_scalar!(
+            r#"
+            INSERT INTO employee_accreditation_authorizations (fk_recipient_employee_id, fk_employee_level_id, fk_authorizing_employee_id, start_at, end_at, is_break_glass, justification)
+            VALUES ($1, $2, $1, $3, $4, TRUE, $5)
+            RETURNING created_at
+            "#,
+            requester_id,
+            level.pk_employee_level_id,
+            start_at,
+            end_at,
+            create_req.justification
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *requester_id,
+            authorization_type_id: 41,
+            entity_id: Some(*requester_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "BREAK_GLASS",
+                "accreditation_created_at": created_at,
+                "employee_level_id": level.pk_employee_level_id,
+                "employee_level_label": level.level_label,
+                "is_admin_elevation": is_admin_elevation,
+                "start_at": start_at,
+                "end_at": end_at,
+                "justification": create_req.justification,
+            }),
+        }).await?;
+
+        // the requester must refresh their token to use the elevation
+        invalidate_employee_permissions(&mut *tx, &[*requester_id]).await?;
+
+        tx.commit().await?;
+
+        warn!("Break-glass elevation of employee {} to level {} until {}: {}", requester_id, level.level_label, end_at, create_req.justification);
+
+        self.get_employee_accreditation(requester_id, &created_at).await
+    }
+
+    pub async fn get_all_break_glass_accreditations(&self, filters: &PaginateQuery) -> Result<(Vec<EmployeeAccreditation>, u64), AppError> {
+        let total_count = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) as count FROM employee_accreditation_authorizations WHERE is_break_glass")
+                .fetch_one(&self.pool)
+                .await? as u64;
+
+        let keys = sqlx::query!(
+            r#"
+            SELECT fk_recipient_employee_id, created_at
+            FROM employee_accreditation_authorizations
+            WHERE is_break_glass
+            ORDER BY created_at DESC
+            LIMIT $1
+            OFFSET $2
+            "#,
+            filters.limit as i64,
+            ((filters.page - 1) * filters.limit) as i64
+        )
+        .fetch_all(&self.pool).await?;
+
+        let mut accreditations = Vec::with_capacity(keys.len());
+        for key in keys {
+            accreditations.push(self.get_employee_accreditation(&key.fk_recipient_employee_id, &key.created_at).await?);
+        }
+
+        Ok((accreditations, total_count))
+    }
+
+    // Shorten, extend or end early an accreditation
+    pub async fn update_employee_accreditation(&self, recipient_id: &Uuid, created_at: &DateTime<Utc>, update_req: &EmployeeAccreditationUpdate, author_id: &Uuid) -> Result<EmployeeAccreditation, AppError> {
+        let existing = self.get_employee_accreditation(recipient_id, created_at).await?;
+        let now = Utc::now();
+        let (start_at, end_at) = self.validate_accreditation_update(&existing, update_req).await?;
+        if existing.employee_level.level_label == ADMIN_LEVEL_LABEL && existing.is_extended_by(update_req) {
+            return Err(AppError::Forbidden(format!("Extending an {} accreditation requires the approval of a second employee", ADMIN_LEVEL_LABEL), "FOUR_EYES_REQUIRED".to_string()));
+        }
+        let level_id = existing.employee_level.pk_employee_level_id;
+
+        let mut changes = FieldChanges::new();
+        changes.track("start_at", &existing.start_at, update_req.start_at.as_ref());
+        changes.track("end_at", &existing.end_at, update_req.end_at.as_ref().map(|_| &update_req.end_at));
+
+        if changes.is_empty() {
+            return Ok(existing);
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // ending the accreditation right away must not leave the platform without administrator
+        if end_at.is_some_and(|end_at| end_at <= now) && existing.start_at <= now {
+            self.ensure_not_last_active_admin(&mut tx, recipient_id, &existing.employee_level).await?;
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE employee_accreditation_authorizations
+            SET start_at = $1, end_at = $2
+            WHERE fk_recipient_employee_id = $3 AND created_at = $4
+            "#,
+            start_at,
+            end_at,
+            recipient_id,
+            created_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 36,
+            entity_id: Some(*recipient_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "UPDATE_ACCREDITATION",
+                "accreditation_created_at": created_at,
+                "employee_level_id": level_id,
+                "changes": changes.into_value(),
+            }),
+        }).await?;
+
+        invalidate_employee_permissions(&mut *tx, &[*recipient_id]).await?;
+
+        tx.commit().await?;
+
+        self.get_employee_accreditation(recipient_id, created_at).await
+    }
+
+    // Store the extension of an ADMIN accreditation as a pending operation, it is applied once another employee approves it
+    pub async fn request_admin_accreditation_update(&self, recipient_id: &Uuid, created_at: &DateTime<Utc>, update_req: &EmployeeAccreditationUpdate, author_id: &Uuid) -> Result<PendingOperation, AppError> {
+        let existing = self.get_employee_accreditation(recipient_id, created_at).await?;
+        if existing.employee_level.level_label != ADMIN_LEVEL_LABEL {
+            return Err(AppError::Validation(format!("Only the updates of {} accreditations need an approval", ADMIN_LEVEL_LABEL)));
+        }
+        self.validate_accreditation_update(&existing, update_req).await?;
+
+        let action = PendingOperationAction::UPDATE_ADMIN_ACCREDITATION {
+            fk_recipient_employee_id: *recipient_id,
+            accreditation_created_at: *created_at,
+            start_at: update_req.start_at,
+            end_at: update_req.end_at,
+        };
+        create_pending_operation(&self.pool, &action, author_id, None).await
+    }
+
+    // Checks shared by every way of updating an accreditation, returns the resulting period
+    pub async fn validate_accreditation_update(&self, existing: &EmployeeAccreditation, update_req: &EmployeeAccreditationUpdate) -> Result<(DateTime<Utc>, Option<DateTime<Utc>>), AppError> {
+        let now = Utc::now();
+
+        if existing.revoked_at.is_some() {
+            return Err(AppError::Conflict("A revoked accreditation cannot be modified".to_string(), "ACCREDITATION_REVOKED".to_string()));
+        }
+        if existing.end_at.is_some_and(|end_at| end_at <= now) {
+            return Err(AppError::Conflict("An ended accreditation cannot be modified".to_string(), "ACCREDITATION_ENDED".to_string()));
+        }
+
+        if let Some(start_at) = update_req.start_at {
+            if existing.start_at <= now {
+                return Err(AppError::Validation("The start of an accreditation that has already started cannot be modified".to_string()));
+            }
+            validate_start_at(&start_at, "accreditation")?;
+        }
+        if let Some(end_at) = update_req.end_at {
+            validate_end_at(&end_at, "accreditation")?;
+        }
+        // a break-glass elevation can only be shortened, its duration is bounded on creation
+        if existing.is_break_glass && existing.is_extended_by(update_req) {
+            return Err(AppError::Conflict("A break-glass accreditation cannot be extended".to_string(), "BREAK_GLASS_EXTENSION".to_string()));
+        }
+
+        let start_at = update_req.start_at.unwrap_or(existing.start_at);
+        let end_at = update_req.end_at.or(existing.end_at);
+        if end_at.is_some_and(|end_at| end_at <= start_at) {
+            return Err(AppError::Validation("The accreditation end must be after its start".to_string()));
+        }
+
+        let recipient_id = &existing.recipient_employee.pk_employee_id;
+        if self.accreditation_overlaps(recipient_id, existing.employee_level.pk_employee_level_id, &start_at, end_at.as_ref(), Some(&existing.created_at)).await? {
+            return Err(AppError::Conflict("The employee already holds this level during the requested period".to_string(), "ACCREDITATION_OVERLAP".to_string()));
+        }
+
+        Ok((start_at, end_at))
+    }
+
+    // Revoke an accreditation, it is ended and kept with who revoked it and why
+    pub async fn revoke_employee_accreditation(&self, recipient_id: &Uuid, created_at: &DateTime<Utc>, revoke_req: &EmployeeAccreditationRevoke, author_id: &Uuid) -> Result<EmployeeAccreditation, AppError> {
+        let existing = self.get_employee_accreditation(recipient_id, created_at).await?;
+        let now = Utc::now();
+
+        if existing.revoked_at.is_some() {
+            return Err(AppError::Conflict("Accreditation has already been revoked".to_string(), "ACCREDITATION_REVOKED".to_string()));
+        }
+        if existing.end_at.is_some_and(|end_at| end_at <= now) {
+            return Err(AppError::Conflict("An ended accreditation cannot be revoked".to_string(), "ACCREDITATION_ENDED".to_string()));
+        }
+        let mut tx = self.pool.begin().await?;
+
+        if existing.start_at <= now {
+            self.ensure_not_last_active_admin(&mut tx, recipient_id, &existing.employee_level).await?;
+        }
+
+        sqlx::query!(
+            r#"
+            UPDATE employee_accreditation_authorizations
+            SET end_at = GREATEST(start_at, NOW()),
+                revoked_at = NOW(),
+                fk_revoking_employee_id = $1,
+                revocation_reason = $2
+            WHERE fk_recipient_employee_id = $3 AND created_at = $4
+            "#,
+            author_id,
+            revoke_req.revocation_reason,
+            recipient_id,
+            created_at
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 37,
+            entity_id: Some(*recipient_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "REVOKE_ACCREDITATION",
+                "accreditation_created_at": created_at,
+                "employee_level_id": existing.employee_level.pk_employee_level_id,
+                "previous_end_at": existing.end_at,
+                "revocation_reason": revoke_req.revocation_reason,
+            }),
+        }).await?;
+
+        invalidate_employee_permissions(&mut *tx, &[*recipient_id]).await?;
+
+        tx.commit().await?;
+
+        self.get_employee_accreditation(recipient_id, created_at).await
+    }
+
+    pub async fn ensure_not_last_active_admin(&self, conn: &mut PgConnection, recipient_id: &Uuid, level: &EmployeeLevel) -> Result<(), AppError> {
+        if level.level_label == ADMIN_LEVEL_LABEL && self.is_last_active_admin(conn, recipient_id).await? {
+            return Err(AppError::Conflict("The last active administrator accreditation cannot be ended".to_string(), "LAST_ACTIVE_ADMIN".to_string()));
+        }
+
+        Ok(())
+    }
+
+    // Check if the employee holds the level during any part of the period,
+    // `excluded_created_at` allows ignoring the accreditation being modified
+    pub async fn accreditation_overlaps(&self, recipient_id: &Uuid, level_id: i32, start_at: &DateTime<Utc>, end_at: Option<&DateTime<Utc>>, excluded_created_at: Option<&DateTime<Utc>>) -> Result<bool, AppError> {
+        let count = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) as "count!"
+            FROM employee_accreditation_authorizations
+            WHERE fk_recipient_employee_id = $1
+                AND fk_employee_level_id = $2
+                AND tstzrange(start_at, end_at) && tstzrange($3, $4)
+                AND ($5::TIMESTAMPTZ IS NULL OR created_at != $5)
+            "#,
+            recipient_id,
+            level_id,
+            start_at,
+            end_at,
+            excluded_created_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count > 0)
+    }
+}
@@ -0,0 +1,128 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    employee_accreditation_request::{models::{AccreditationRequestStatus, ApproveAccreditationRequestRequest, CreateAccreditationRequestRequest, EmployeeAccreditationRequest, GetAllAccreditationRequestsQuery, RejectAccreditationRequestRequest}, services::AccreditationRequestService}, errors::app_error::AppError, middleware::AuthState, models::{paginate::{PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}, path::parse_uuid}
+};
+
+/// "Read employee accreditations" authorization type, needed to see the requests of others
+const READ_ACCREDITATIONS_PERMISSION: i32 = 34;
+
+fn validate_request<T: Validate>(req: &T) -> Result<(), AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(format!("The request content is not valid: {}", e)))
+}
+
+fn ensure_self_or_reader(auth_state: &AuthState, employee_id: &Uuid) -> Result<(), AppError> {
+    if auth_state.employee_id != *employee_id && !auth_state.authorizations.contains(&READ_ACCREDITATIONS_PERMISSION) {
+        return Err(AppError::InsufficientPermissions(vec![READ_ACCREDITATIONS_PERMISSION]));
+    }
+    Ok(())
+}
+
+async fn paginated_requests(
+    requester_id: Option<&Uuid>,
+    filters: &GetAllAccreditationRequestsQuery,
+    request_service: &AccreditationRequestService,
+) -> Result<Json<PaginatedResponse<EmployeeAccreditationRequest>>, AppError> {
+    if filters.page == 0 {
+        return Err(AppError::Validation("Page must be greater than 0".to_string()));
+    }
+    if filters.limit == 0 || filters.limit > PAGINATE_MAX_LIMIT {
+        return Err(AppError::Validation(format!("Limit must be between 1 and {}.", PAGINATE_MAX_LIMIT)));
+    }
+    if filters.status.as_deref().is_some_and(|status| status.parse::<AccreditationRequestStatus>().is_err()) {
+        return Err(AppError::Validation("Status must be PENDING, APPROVED, REJECTED or CANCELLED".to_string()));
+    }
+
+    let (requests, total) = request_service.get_all_requests(requester_id, filters).await?;
+
+    Ok(Json(PaginatedResponse {
+        data: requests,
+        pagination: PaginationInfo {
+            page: filters.page,
+            limit: filters.limit,
+            total,
+        },
+    }))
+}
+
+pub async fn get_all_requests(
+    Query(filters): Query<GetAllAccreditationRequestsQuery>,
+    State(request_service): State<Arc<AccreditationRequestService>>,
+) -> Result<Json<PaginatedResponse<EmployeeAccreditationRequest>>, AppError> {
+    paginated_requests(None, &filters, &request_service).await
+}
+
+pub async fn get_employee_requests(
+    Path(employee_id): Path<String>,
+    Query(filters): Query<GetAllAccreditationRequestsQuery>,
+    State(request_service): State<Arc<AccreditationRequestService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<PaginatedResponse<EmployeeAccreditationRequest>>, AppError> {
+    let employee_uuid = parse_uuid(&employee_id, "Employee")?;
+    ensure_self_or_reader(&auth_state, &employee_uuid)?;
+    paginated_requests(Some(&employee_uuid), &filters, &request_service).await
+}
+
+pub async fn get_request_by_id(
+    Path(request_id): Path<String>,
+    State(request_service): State<Arc<AccreditationRequestService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<EmployeeAccreditationRequest>, AppError> {
+    let request_uuid = parse_uuid(&request_id, "Accreditation request")?;
+    let request = request_service.get_request_by_id(&request_uuid).await?;
+    ensure_self_or_reader(&auth_state, &request.fk_requester_employee_id)?;
+    Ok(Json(request))
+}
+
+pub async fn create_request(
+    State(request_service): State<Arc<AccreditationRequestService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<CreateAccreditationRequestRequest>,
+) -> Result<(StatusCode, Json<EmployeeAccreditationRequest>), AppError> {
+    validate_request(&create_req)?;
+    let request = request_service.create_request(&auth_state.employee_id, &create_req).await?;
+    Ok((StatusCode::CREATED, Json(request)))
+}
+
+pub async fn approve_request(
+    Path(request_id): Path<String>,
+    State(request_service): State<Arc<AccreditationRequestService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(approve_req): Json<ApproveAccreditationRequestRequest>,
+) -> Result<Json<EmployeeAccreditationRequest>, AppError> {
+    let request_uuid = parse_uuid(&request_id, "Accreditation request")?;
+    validate_request(&approve_req)?;
+    let request = request_service.approve_request(&request_uuid, &approve_req, &auth_state.employee_id).await?;
+    Ok(Json(request))
+}
+
+pub async fn reject_request(
+    Path(request_id): Path<String>,
+    State(request_service): State<Arc<AccreditationRequestService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(reject_req): Json<RejectAccreditationRequestRequest>,
+) -> Result<Json<EmployeeAccreditationRequest>, AppError> {
+    let request_uuid = parse_uuid(&request_id, "Accreditation request")?;
+    validate_request(&reject_req)?;
+    let request = request_service.reject_request(&request_uuid, &reject_req, &auth_state.employee_id).await?;
+    Ok(Json(request))
+}
+
+pub async fn cancel_request(
+    Path(request_id): Path<String>,
+    State(request_service): State<Arc<AccreditationRequestService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<EmployeeAccreditationRequest>, AppError> {
+    let request_uuid = parse_uuid(&request_id, "Accreditation request")?;
+    let request = request_service.cancel_request(&request_uuid, &auth_state.employee_id).await?;
+    Ok(Json(request))
+}
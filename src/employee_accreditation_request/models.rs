@@ -0,0 +1,105 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::paginate::{default_limit, default_page, default_sort_order};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum AccreditationRequestStatus {
+    PENDING,
+    APPROVED,
+    REJECTED,
+    CANCELLED,
+}
+
+impl FromStr for AccreditationRequestStatus {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PENDING" => Ok(AccreditationRequestStatus::PENDING),
+            "APPROVED" => Ok(AccreditationRequestStatus::APPROVED),
+            "REJECTED" => Ok(AccreditationRequestStatus::REJECTED),
+            "CANCELLED" => Ok(AccreditationRequestStatus::CANCELLED),
+            _ => Err(()),
+        }
+    }
+}
+
+impl AccreditationRequestStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccreditationRequestStatus::PENDING => "PENDING",
+            AccreditationRequestStatus::APPROVED => "APPROVED",
+            AccreditationRequestStatus::REJECTED => "REJECTED",
+            AccreditationRequestStatus::CANCELLED => "CANCELLED",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmployeeAccreditationRequest {
+    pub pk_employee_accreditation_request_id: Uuid,
+    pub fk_requester_employee_id: Uuid,
+    pub fk_employee_level_id: i32,
+    pub start_at: DateTime<Utc>,
+    pub end_at: Option<DateTime<Utc>>,
+    pub justification: String,
+    /// PENDING, APPROVED, REJECTED or CANCELLED
+    pub status: String,
+    pub fk_reviewing_employee_id: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_comment: Option<String>,
+    /// Creation date of the accreditation granted on approval, it identifies it with the requester
+    pub accreditation_created_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAccreditationRequestRequest {
+    #[validate(range(min = 1, message = "Employee level ID is not valid"))]
+    pub fk_employee_level_id: i32,
+    pub start_at: DateTime<Utc>,
+    /// No end means a permanent accreditation
+    pub end_at: Option<DateTime<Utc>>,
+    #[validate(length(min = 10, max = 1000, message = "Justification must contain between 10 and 1000 characters"))]
+    pub justification: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ApproveAccreditationRequestRequest {
+    #[validate(length(min = 1, max = 1000, message = "Review comment cannot be empty and cannot be longer than 1000 characters"))]
+    pub review_comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RejectAccreditationRequestRequest {
+    #[validate(length(min = 1, max = 1000, message = "Review comment is required and cannot be longer than 1000 characters"))]
+    pub review_comment: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetAllAccreditationRequestsQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+}
+
+impl Default for GetAllAccreditationRequestsQuery {
+    fn default() -> Self {
+        Self {
+            page: default_page(),
+            limit: default_limit(),
+            status: None,
+            sort_order: default_sort_order(),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use axum::{
+    middleware::{from_fn, from_fn_with_state}, routing::{get, post}, Router
+};
+use crate::{
+    employee_accreditation_request::{handlers::{approve_request, cancel_request, create_request, get_all_requests, get_employee_requests, get_request_by_id, reject_request}, services::AccreditationRequestService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+};
+use std::sync::Arc;
+
+pub fn protected_accreditation_request_routes(
+    middleware_state: MiddlewareState,
+    request_service: Arc<AccreditationRequestService>,
+) -> Router {
+    Router::new()
+        .route("/employees/accreditation-requests", get(get_all_requests).route_layer(from_fn(with_required_permissions(vec![34]))))
+        .route("/employees/accreditation-requests", post(create_request))
+        .route("/employees/accreditation-requests/{id}", get(get_request_by_id))
+        .route("/employees/accreditation-requests/{id}/approve", post(approve_request).route_layer(from_fn(with_required_permissions(vec![35]))))
+        .route("/employees/accreditation-requests/{id}/reject", post(reject_request).route_layer(from_fn(with_required_permissions(vec![35]))))
+        .route("/employees/accreditation-requests/{id}/cancel", post(cancel_request))
+        .route("/employees/{id}/accreditation-requests", get(get_employee_requests))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(request_service.clone())
+}
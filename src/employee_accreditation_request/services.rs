@@ -0,0 +1,340 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    auth::services::invalidate_employee_permissions, employee::{models::{EmployeeLevel, EntityType}, services::{EmployeeService, ADMIN_LEVEL_LABEL}}, employee_accreditation_request::models::{AccreditationRequestStatus, ApproveAccreditationRequestRequest, CreateAccreditationRequestRequest, EmployeeAccreditationRequest, GetAllAccreditationRequestsQuery, RejectAccreditationRequestRequest}, errors::app_error::AppError, history::{models::NewActionHistory, services::record_action}, models::period::validate_start_at
+};
+
+// Requesting an accreditation and cancelling the request are allowed to every employee,
+// this authorization type is only referenced by the action history
+const OWN_ACCREDITATION_REQUEST_PERMISSION: i32 = 56;
+
+pub struct AccreditationRequestService {
+    pool: PgPool,
+    employee_service: EmployeeService,
+}
+
+impl AccreditationRequestService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            employee_service: EmployeeService::new(pool.clone()),
+            pool,
+        }
+    }
+
+    // Get all accreditation requests, optionally restricted to one requester
+    pub async fn get_all_requests(&self, requester_id: Option<&Uuid>, filters: &GetAllAccreditationRequestsQuery) -> Result<(Vec<EmployeeAccreditationRequest>, u64), AppError> {
+        let offset = (filters.page - 1) * filters.limit;
+
+        let where_clause = "WHERE ($1::UUID IS NULL OR fk_requester_employee_id = $1) AND ($2::VARCHAR IS NULL OR status = $2)";
+
+        let total_count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) as count FROM employee_accreditation_requests {}", where_clause))
+            .bind(requester_id)
+            .bind(&filters.status)
+            .fetch_one(&self.pool)
+            .await?;
+
+        // Determine sort order (default to ASC for chronological order)
+        let order_direction = match filters.sort_order.to_lowercase().as_str() {
+            "desc" => "DESC",
+            _ => "ASC",
+        };
+
+        let select_query = format!(
+            "SELECT pk_employee_accreditation_request_id, fk_requester_employee_id, fk_employee_level_id, start_at, end_at, justification, status, fk_reviewing_employee_id, reviewed_at, review_comment, accreditation_created_at, created_at FROM employee_accreditation_requests {} ORDER BY created_at {} LIMIT $3 OFFSET $4",
+            where_clause,
+            order_direction
+        );
+
+        let requests = sqlx::query_as::<_, EmployeeAccreditationRequest>(&select_query)
+            .bind(requester_id)
+            .bind(&filters.status)
+            .bind(filters.limit as i64)
+            .bind(offset as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok((requests, total_count as u64))
+    }
+
+    pub async fn get_request_by_id(&self, request_id: &Uuid) -> Result<EmployeeAccreditationRequest, AppError> {
+        sqlx::query_as!(
+            EmployeeAccreditationRequest,
+            r#"
+            SELECT pk_employee_accreditation_request_id, fk_requester_employee_id, fk_employee_level_id, start_at, end_at, justification, status, fk_reviewing_employee_id, reviewed_at, review_comment, accreditation_created_at, created_at
+            FROM employee_accreditation_requests
+            WHERE pk_employee_accreditation_request_id = $1
+            "#,
+            request_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Accreditation request not found".to_string()))
+    }
+
+    // An employee asks for a level for a period
+    pub async fn create_request(&self, requester_id: &Uuid, create_req: &CreateAccreditationRequestRequest) -> Result<EmployeeAccreditationRequest, AppError> {
+        let level = self.employee_service.get_employee_level_by_id(create_req.fk_employee_level_id).await?;
+        if self.employee_service.employee_level_is_deleted(level.pk_employee_level_id).await? {
+            return Err(AppError::Conflict("A deleted level cannot be requested".to_string(), "LEVEL_DELETED".to_string()));
+        }
+
+        validate_start_at(&create_req.start_at, "accreditation")?;
+        if create_req.end_at.is_some_and(|end_at| end_at <= create_req.start_at) {
+            return Err(AppError::Validation("The accreditation end must be after its start".to_string()));
+        }
+
+        if self.employee_service.accreditation_overlaps(requester_id, level.pk_employee_level_id, &create_req.start_at, create_req.end_at.as_ref(), None).await? {
+            return Err(AppError::Conflict("The employee already holds this level during the requested period".to_string(), "ACCREDITATION_OVERLAP".to_string()));
+        }
+
+        let pending_exists = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM employee_accreditation_requests
+                WHERE fk_requester_employee_id = $1 AND fk_employee_level_id = $2 AND status = 'PENDING'
+            ) as "exists!"
+            "#,
+            requester_id,
+            level.pk_employee_level_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        if pending_exists {
+            return Err(AppError::Conflict("A request for this level is already pending".to_string(), "ACCREDITATION_REQUEST_ALREADY_PENDING".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let request = sqlx::query_as!(
+            EmployeeAccreditationRequest,
+            r#"
+            INSERT INTO employee_accreditation_requests (fk_requester_employee_id, fk_employee_level_id, start_at, end_at, justification)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING pk_employee_accreditation_request_id, fk_requester_employee_id, fk_employee_level_id, start_at, end_at, justification, status, fk_reviewing_employee_id, reviewed_at, review_comment, accreditation_created_at, created_at
+            "#,
+            requester_id,
+            level.pk_employee_level_id,
+            create_req.start_at,
+            create_req.end_at,
+            create_req.justification
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *requester_id,
+            authorization_type_id: OWN_ACCREDITATION_REQUEST_PERMISSION,
+            entity_id: Some(*requester_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "CREATE_ACCREDITATION_REQUEST",
+                "accreditation_request_id": request.pk_employee_accreditation_request_id,
+                "employee_level_id": level.pk_employee_level_id,
+                "employee_level_label": level.level_label,
+                "start_at": request.start_at,
+                "end_at": request.end_at,
+                "justification": request.justification,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(request)
+    }
+
+    // Approve a pending request, the accreditation is granted with the approver as authorizing employee
+    pub async fn approve_request(&self, request_id: &Uuid, approve_req: &ApproveAccreditationRequestRequest, approver_id: &Uuid) -> Result<EmployeeAccreditationRequest, AppError> {
+        let existing = self.get_pending_request(request_id).await?;
+        let level = self.ensure_can_review(&existing, approver_id).await?;
+
+        let requester = self.employee_service.get_employee_by_id(&existing.fk_requester_employee_id.to_string()).await?;
+        if requester.deactivated_at.is_some() {
+            return Err(AppError::Conflict("A deactivated employee cannot receive an accreditation".to_string(), "EMPLOYEE_DEACTIVATED".to_string()));
+        }
+        if self.employee_service.employee_level_is_deleted(level.pk_employee_level_id).await? {
+            return Err(AppError::Conflict("A deleted level cannot be granted".to_string(), "LEVEL_DELETED".to_string()));
+        }
+
+        // a request reviewed after its requested start begins at the approval
+        let now = Utc::now();
+        let start_at = existing.start_at.max(now);
+        if existing.end_at.is_some_and(|end_at| end_at <= start_at) {
+            return Err(AppError::Conflict("The requested period is over".to_string(), "ACCREDITATION_REQUEST_EXPIRED".to_string()));
+        }
+
+        if self.employee_service.accreditation_overlaps(&existing.fk_requester_employee_id, level.pk_employee_level_id, &start_at, existing.end_at.as_ref(), None).await? {
+            return Err(AppError::Conflict("The employee already holds this level during the requested period".to_string(), "ACCREDITATION_OVERLAP".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let accreditation_created_at = sqlx::query_scalar!(
+            r#"
+            INSERT INTO employee_accreditation_authorizations (fk_recipient_employee_id, fk_employee_level_id, fk_authorizing_employee_id, start_at, end_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING created_at
+            "#,
+            existing.fk_requester_employee_id,
+            level.pk_employee_level_id,
+            approver_id,
+            start_at,
+            existing.end_at
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let request = sqlx::query_as!(
+            EmployeeAccreditationRequest,
+            r#"
+            UPDATE employee_accreditation_requests SET
+                status = 'APPROVED',
+                fk_reviewing_employee_id = $1,
+                reviewed_at = NOW(),
+                review_comment = $2,
+                accreditation_created_at = $3
+            WHERE pk_employee_accreditation_request_id = $4 AND status = 'PENDING'
+            RETURNING pk_employee_accreditation_request_id, fk_requester_employee_id, fk_employee_level_id, start_at, end_at, justification, status, fk_reviewing_employee_id, reviewed_at, review_comment, accreditation_created_at, created_at
+            "#,
+            approver_id,
+            approve_req.review_comment,
+            accreditation_created_at,
+            request_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("The accreditation request is not pending anymore".to_string(), "ACCREDITATION_REQUEST_NOT_PENDING".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *approver_id,
+            authorization_type_id: 35,
+            entity_id: Some(request.fk_requester_employee_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "APPROVE_ACCREDITATION_REQUEST",
+                "accreditation_request_id": request.pk_employee_accreditation_request_id,
+                "accreditation_created_at": accreditation_created_at,
+                "employee_level_id": level.pk_employee_level_id,
+                "employee_level_label": level.level_label,
+                "start_at": start_at,
+                "end_at": request.end_at,
+            }),
+        }).await?;
+
+        invalidate_employee_permissions(&mut *tx, &[request.fk_requester_employee_id]).await?;
+
+        tx.commit().await?;
+
+        Ok(request)
+    }
+
+    pub async fn reject_request(&self, request_id: &Uuid, reject_req: &RejectAccreditationRequestRequest, reviewer_id: &Uuid) -> Result<EmployeeAccreditationRequest, AppError> {
+        let existing = self.get_pending_request(request_id).await?;
+        let level = self.ensure_can_review(&existing, reviewer_id).await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let request = sqlx::query_as!(
+            EmployeeAccreditationRequest,
+            r#"
+            UPDATE employee_accreditation_requests SET
+                status = 'REJECTED',
+                fk_reviewing_employee_id = $1,
+                reviewed_at = NOW(),
+                review_comment = $2
+            WHERE pk_employee_accreditation_request_id = $3 AND status = 'PENDING'
+            RETURNING pk_employee_accreditation_request_id, fk_requester_employee_id, fk_employee_level_id, start_at, end_at, justification, status, fk_reviewing_employee_id, reviewed_at, review_comment, accreditation_created_at, created_at
+            "#,
+            reviewer_id,
+            reject_req.review_comment,
+            request_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("The accreditation request is not pending anymore".to_string(), "ACCREDITATION_REQUEST_NOT_PENDING".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *reviewer_id,
+            authorization_type_id: 35,
+            entity_id: Some(request.fk_requester_employee_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "REJECT_ACCREDITATION_REQUEST",
+                "accreditation_request_id": request.pk_employee_accreditation_request_id,
+                "employee_level_id": level.pk_employee_level_id,
+                "employee_level_label": level.level_label,
+                "review_comment": request.review_comment,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(request)
+    }
+
+    // Only the requester can cancel their own pending request
+    pub async fn cancel_request(&self, request_id: &Uuid, requester_id: &Uuid) -> Result<EmployeeAccreditationRequest, AppError> {
+        let existing = self.get_pending_request(request_id).await?;
+        if existing.fk_requester_employee_id != *requester_id {
+            return Err(AppError::Forbidden("Only the requester can cancel an accreditation request".to_string(), "ACCREDITATION_REQUEST_NOT_REQUESTER".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let request = sqlx::query_as!(
+            EmployeeAccreditationRequest,
+            r#"
+            UPDATE employee_accreditation_requests SET status = 'CANCELLED'
+            WHERE pk_employee_accreditation_request_id = $1 AND status = 'PENDING'
+            RETURNING pk_employee_accreditation_request_id, fk_requester_employee_id, fk_employee_level_id, start_at, end_at, justification, status, fk_reviewing_employee_id, reviewed_at, review_comment, accreditation_created_at, created_at
+            "#,
+            request_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("The accreditation request is not pending anymore".to_string(), "ACCREDITATION_REQUEST_NOT_PENDING".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *requester_id,
+            authorization_type_id: OWN_ACCREDITATION_REQUEST_PERMISSION,
+            entity_id: Some(*requester_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "CANCEL_ACCREDITATION_REQUEST",
+                "accreditation_request_id": request.pk_employee_accreditation_request_id,
+                "employee_level_id": request.fk_employee_level_id,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(request)
+    }
+
+    async fn get_pending_request(&self, request_id: &Uuid) -> Result<EmployeeAccreditationRequest, AppError> {
+        let request = self.get_request_by_id(request_id).await?;
+        if request.status != AccreditationRequestStatus::PENDING.as_str() {
+            return Err(AppError::Conflict("The accreditation request is not pending anymore".to_string(), "ACCREDITATION_REQUEST_NOT_PENDING".to_string()));
+        }
+        Ok(request)
+    }
+
+    // A reviewer must hold a level ranked strictly higher (lower level index) than the requested one,
+    // except for ADMIN which has no higher level: another administrator reviews it, the requester
+    // and the reviewer being two different employees satisfies the four-eyes rule
+    async fn ensure_can_review(&self, request: &EmployeeAccreditationRequest, reviewer_id: &Uuid) -> Result<EmployeeLevel, AppError> {
+        if request.fk_requester_employee_id == *reviewer_id {
+            return Err(AppError::Forbidden("An employee cannot review their own accreditation request".to_string(), "ACCREDITATION_REQUEST_SELF_REVIEW".to_string()));
+        }
+
+        let level = self.employee_service.get_employee_level_by_id(request.fk_employee_level_id).await?;
+        let reviewer_level_index = self.employee_service.get_employee_highest_level_index(reviewer_id).await?;
+        let ranked_high_enough = |index: i32| index < level.level_index || (level.level_label == ADMIN_LEVEL_LABEL && index == level.level_index);
+        if !reviewer_level_index.is_some_and(ranked_high_enough) {
+            return Err(AppError::Forbidden("Reviewing this request requires a level ranked higher than the requested one".to_string(), "ACCREDITATION_REQUEST_INSUFFICIENT_LEVEL".to_string()));
+        }
+
+        Ok(level)
+    }
+}
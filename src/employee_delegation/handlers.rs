@@ -0,0 +1,100 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    employee_delegation::{models::{CreateEmployeePermissionDelegationRequest, EmployeePermissionDelegation, GetAllEmployeePermissionDelegationsQuery}, services::EmployeeDelegationService}, errors::app_error::AppError, middleware::AuthState, models::{paginate::{PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}, path::parse_uuid}
+};
+
+/// "Read all permission delegations" authorization type, needed to see the delegations of others
+const READ_DELEGATIONS_PERMISSION: i32 = 46;
+
+fn validate_request<T: Validate>(req: &T) -> Result<(), AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(format!("The request content is not valid: {}", e)))
+}
+
+async fn paginated_delegations(
+    employee_id: Option<&Uuid>,
+    filters: &GetAllEmployeePermissionDelegationsQuery,
+    delegation_service: &EmployeeDelegationService,
+) -> Result<Json<PaginatedResponse<EmployeePermissionDelegation>>, AppError> {
+    if filters.page == 0 {
+        return Err(AppError::Validation("Page must be greater than 0".to_string()));
+    }
+    if filters.limit == 0 || filters.limit > PAGINATE_MAX_LIMIT {
+        return Err(AppError::Validation(format!("Limit must be between 1 and {}.", PAGINATE_MAX_LIMIT)));
+    }
+
+    let (delegations, total) = delegation_service.get_all_delegations(employee_id, filters).await?;
+
+    Ok(Json(PaginatedResponse {
+        data: delegations,
+        pagination: PaginationInfo {
+            page: filters.page,
+            limit: filters.limit,
+            total,
+        },
+    }))
+}
+
+pub async fn get_all_delegations(
+    Query(filters): Query<GetAllEmployeePermissionDelegationsQuery>,
+    State(delegation_service): State<Arc<EmployeeDelegationService>>,
+) -> Result<Json<PaginatedResponse<EmployeePermissionDelegation>>, AppError> {
+    paginated_delegations(None, &filters, &delegation_service).await
+}
+
+// Delegations given or received by an employee, visible to themselves
+pub async fn get_employee_delegations(
+    Path(employee_id): Path<String>,
+    Query(filters): Query<GetAllEmployeePermissionDelegationsQuery>,
+    State(delegation_service): State<Arc<EmployeeDelegationService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<PaginatedResponse<EmployeePermissionDelegation>>, AppError> {
+    let employee_uuid = parse_uuid(&employee_id, "Employee")?;
+    if auth_state.employee_id != employee_uuid && !auth_state.authorizations.contains(&READ_DELEGATIONS_PERMISSION) {
+        return Err(AppError::InsufficientPermissions(vec![READ_DELEGATIONS_PERMISSION]));
+    }
+    paginated_delegations(Some(&employee_uuid), &filters, &delegation_service).await
+}
+
+pub async fn get_delegation_by_id(
+    Path(delegation_id): Path<String>,
+    State(delegation_service): State<Arc<EmployeeDelegationService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<EmployeePermissionDelegation>, AppError> {
+    let delegation_uuid = parse_uuid(&delegation_id, "Permission delegation")?;
+    let delegation = delegation_service.get_delegation_by_id(&delegation_uuid).await?;
+    let is_party = auth_state.employee_id == delegation.fk_delegator_employee_id || auth_state.employee_id == delegation.fk_delegate_employee_id;
+    if !is_party && !auth_state.authorizations.contains(&READ_DELEGATIONS_PERMISSION) {
+        return Err(AppError::InsufficientPermissions(vec![READ_DELEGATIONS_PERMISSION]));
+    }
+    Ok(Json(delegation))
+}
+
+pub async fn create_delegation(
+    State(delegation_service): State<Arc<EmployeeDelegationService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<CreateEmployeePermissionDelegationRequest>,
+) -> Result<(StatusCode, Json<EmployeePermissionDelegation>), AppError> {
+    validate_request(&create_req)?;
+    let delegation = delegation_service.create_delegation(&create_req, &auth_state.employee_id).await?;
+    Ok((StatusCode::CREATED, Json(delegation)))
+}
+
+pub async fn revoke_delegation(
+    Path(delegation_id): Path<String>,
+    State(delegation_service): State<Arc<EmployeeDelegationService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<EmployeePermissionDelegation>, AppError> {
+    let delegation_uuid = parse_uuid(&delegation_id, "Permission delegation")?;
+    let delegation = delegation_service.revoke_delegation(&delegation_uuid, &auth_state.employee_id, &auth_state.authorizations).await?;
+    Ok(Json(delegation))
+}
@@ -0,0 +1,46 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::paginate::{default_limit, default_page, default_sort_order};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmployeePermissionDelegation {
+    pub pk_employee_permission_delegation_id: Uuid,
+    pub fk_delegator_employee_id: Uuid,
+    pub fk_delegate_employee_id: Uuid,
+    /// Delegated authorization types, only effective while the delegator still holds them
+    pub authorization_type_ids: Vec<i32>,
+    pub start_at: DateTime<Utc>,
+    pub end_at: DateTime<Utc>,
+    pub reason: Option<String>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub fk_revoking_employee_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateEmployeePermissionDelegationRequest {
+    pub fk_delegate_employee_id: Uuid,
+    #[validate(length(min = 1, message = "At least one authorization type must be delegated"))]
+    pub authorization_type_ids: Vec<i32>,
+    /// Defaults to now
+    pub start_at: Option<DateTime<Utc>>,
+    pub end_at: DateTime<Utc>,
+    #[validate(length(min = 1, max = 1000, message = "Reason cannot be empty and cannot be longer than 1000 characters"))]
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetAllEmployeePermissionDelegationsQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    #[serde(default)]
+    pub active: Option<bool>,
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+}
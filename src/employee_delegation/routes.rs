@@ -0,0 +1,24 @@
+use axum::{
+    middleware::{from_fn, from_fn_with_state}, routing::{get, post}, Router
+};
+use crate::{
+    employee_delegation::{handlers::{create_delegation, get_all_delegations, get_delegation_by_id, get_employee_delegations, revoke_delegation}, services::EmployeeDelegationService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+};
+use std::sync::Arc;
+
+pub fn protected_employee_delegation_routes(
+    middleware_state: MiddlewareState,
+    delegation_service: Arc<EmployeeDelegationService>,
+) -> Router {
+    Router::new()
+        .route("/employees/delegations", get(get_all_delegations).route_layer(from_fn(with_required_permissions(vec![46]))))
+        .route("/employees/delegations", post(create_delegation).route_layer(from_fn(with_required_permissions(vec![47]))))
+        .route("/employees/delegations/{id}", get(get_delegation_by_id))
+        .route("/employees/delegations/{id}/revoke", post(revoke_delegation))
+        .route("/employees/{id}/delegations", get(get_employee_delegations))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(delegation_service.clone())
+}
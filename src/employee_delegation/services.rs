@@ -0,0 +1,237 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    auth::services::invalidate_employee_permissions, employee::models::EntityType, employee_delegation::models::{CreateEmployeePermissionDelegationRequest, EmployeePermissionDelegation, GetAllEmployeePermissionDelegationsQuery}, errors::app_error::AppError, history::{models::NewActionHistory, services::record_action}, models::period::validate_start_at
+};
+
+/// SQL condition matching the delegations currently in effect
+const ACTIVE_DELEGATION_CONDITION: &str = "(revoked_at IS NULL AND start_at <= NOW() AND end_at > NOW())";
+
+const DELEGATION_COLUMNS: &str = r#"
+    pk_employee_permission_delegation_id, fk_delegator_employee_id, fk_delegate_employee_id,
+    ARRAY(
+        SELECT fk_employee_authorization_type_id FROM employee_permission_delegation_types
+        WHERE fk_employee_permission_delegation_id = pk_employee_permission_delegation_id
+        ORDER BY fk_employee_authorization_type_id
+    ) as authorization_type_ids,
+    start_at, end_at, reason, revoked_at, fk_revoking_employee_id, created_at
+"#;
+
+/// "Delegate some of one's own authorizations to a colleague" authorization type
+const CREATE_DELEGATION_PERMISSION: i32 = 47;
+/// "Revoke any permission delegation" authorization type
+const REVOKE_DELEGATION_PERMISSION: i32 = 48;
+
+pub struct EmployeeDelegationService {
+    pool: PgPool,
+}
+
+impl EmployeeDelegationService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // Get all delegations, optionally restricted to the ones given or received by an employee
+    pub async fn get_all_delegations(&self, employee_id: Option<&Uuid>, filters: &GetAllEmployeePermissionDelegationsQuery) -> Result<(Vec<EmployeePermissionDelegation>, u64), AppError> {
+        let offset = (filters.page - 1) * filters.limit;
+
+        let where_clause = format!(
+            "WHERE ($1::UUID IS NULL OR fk_delegator_employee_id = $1 OR fk_delegate_employee_id = $1) AND ($2::BOOL IS NULL OR $2 = {})",
+            ACTIVE_DELEGATION_CONDITION
+        );
+
+        let total_count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) as count FROM employee_permission_delegations {}", where_clause))
+            .bind(employee_id)
+            .bind(filters.active)
+            .fetch_one(&self.pool)
+            .await?;
+
+        // Determine sort order (default to ASC for chronological order)
+        let order_direction = match filters.sort_order.to_lowercase().as_str() {
+            "desc" => "DESC",
+            _ => "ASC",
+        };
+
+        let delegations = sqlx::query_as::<_, EmployeePermissionDelegation>(&format!(
+            "SELECT {} FROM employee_permission_delegations {} ORDER BY start_at {} LIMIT $3 OFFSET $4",
+            DELEGATION_COLUMNS,
+            where_clause,
+            order_direction
+        ))
+        .bind(employee_id)
+        .bind(filters.active)
+        .bind(filters.limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((delegations, total_count as u64))
+    }
+
+    pub async fn get_delegation_by_id(&self, delegation_id: &Uuid) -> Result<EmployeePermissionDelegation, AppError> {
+        sqlx::query_as::<_, EmployeePermissionDelegation>(&format!(
+            "SELECT {} FROM employee_permission_delegations WHERE pk_employee_permission_delegation_id = $1",
+            DELEGATION_COLUMNS
+        ))
+        .bind(delegation_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Permission delegation not found".to_string()))
+    }
+
+    // Authorizations the employee currently holds through their own accreditations, delegated ones excluded
+    async fn get_own_permissions(&self, employee_id: &Uuid) -> Result<Vec<i32>, AppError> {
+        let permissions = sqlx::query_scalar!(
+            r#"
+            SELECT DISTINCT lea.fk_employee_authorization_type_id
+            FROM employee_accreditation_authorizations eaa
+            JOIN link_employee_authorization lea ON lea.fk_employee_level_id = eaa.fk_employee_level_id
+            WHERE eaa.fk_recipient_employee_id = $1
+                AND eaa.start_at <= NOW()
+                AND (eaa.end_at IS NULL OR eaa.end_at > NOW())
+            "#,
+            employee_id
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(permissions)
+    }
+
+    // Hand over some of the delegator's own authorizations for a time window
+    pub async fn create_delegation(&self, create_req: &CreateEmployeePermissionDelegationRequest, delegator_id: &Uuid) -> Result<EmployeePermissionDelegation, AppError> {
+        if create_req.fk_delegate_employee_id == *delegator_id {
+            return Err(AppError::Conflict("An employee cannot delegate authorizations to themselves".to_string(), "DELEGATION_SELF".to_string()));
+        }
+
+        let delegate = sqlx::query!(
+            "SELECT deactivated_at FROM employees WHERE pk_employee_id = $1",
+            create_req.fk_delegate_employee_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Employee not found".to_string()))?;
+        if delegate.deactivated_at.is_some() {
+            return Err(AppError::Conflict("Authorizations cannot be delegated to a deactivated employee".to_string(), "EMPLOYEE_DEACTIVATED".to_string()));
+        }
+
+        let start_at = create_req.start_at.unwrap_or_else(Utc::now);
+        validate_start_at(&start_at, "delegation")?;
+        if create_req.end_at <= start_at {
+            return Err(AppError::Validation("The delegation end must be after its start".to_string()));
+        }
+
+        let mut authorization_type_ids = create_req.authorization_type_ids.clone();
+        authorization_type_ids.sort_unstable();
+        authorization_type_ids.dedup();
+
+        let own_permissions = self.get_own_permissions(delegator_id).await?;
+        let exceeding: Vec<i32> = authorization_type_ids.iter()
+            .filter(|id| !own_permissions.contains(id))
+            .copied()
+            .collect();
+        if !exceeding.is_empty() {
+            return Err(AppError::Forbidden(
+                format!("Only authorizations held through one's own accreditations can be delegated, not held: {:?}", exceeding),
+                "DELEGATION_EXCEEDS_PERMISSIONS".to_string(),
+            ));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let delegation_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO employee_permission_delegations (fk_delegator_employee_id, fk_delegate_employee_id, start_at, end_at, reason)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING pk_employee_permission_delegation_id
+            "#,
+            delegator_id,
+            create_req.fk_delegate_employee_id,
+            start_at,
+            create_req.end_at,
+            create_req.reason
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            INSERT INTO employee_permission_delegation_types (fk_employee_permission_delegation_id, fk_employee_authorization_type_id)
+            SELECT $1, UNNEST($2::INTEGER[])
+            "#,
+            delegation_id,
+            &authorization_type_ids
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *delegator_id,
+            authorization_type_id: CREATE_DELEGATION_PERMISSION,
+            entity_id: Some(create_req.fk_delegate_employee_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "DELEGATE_PERMISSIONS",
+                "delegation_id": delegation_id,
+                "authorization_type_ids": authorization_type_ids,
+                "start_at": start_at,
+                "end_at": create_req.end_at,
+                "reason": create_req.reason,
+            }),
+        }).await?;
+
+        invalidate_employee_permissions(&mut *tx, &[create_req.fk_delegate_employee_id]).await?;
+
+        tx.commit().await?;
+
+        self.get_delegation_by_id(&delegation_id).await
+    }
+
+    // The delegator, or an employee allowed to revoke any delegation, ends it early
+    pub async fn revoke_delegation(&self, delegation_id: &Uuid, author_id: &Uuid, author_authorizations: &[i32]) -> Result<EmployeePermissionDelegation, AppError> {
+        let existing = self.get_delegation_by_id(delegation_id).await?;
+
+        let is_delegator = existing.fk_delegator_employee_id == *author_id;
+        if !is_delegator && !author_authorizations.contains(&REVOKE_DELEGATION_PERMISSION) {
+            return Err(AppError::InsufficientPermissions(vec![REVOKE_DELEGATION_PERMISSION]));
+        }
+        if existing.revoked_at.is_some() || existing.end_at <= Utc::now() {
+            return Err(AppError::Conflict("The delegation has already ended".to_string(), "DELEGATION_ENDED".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE employee_permission_delegations SET
+                revoked_at = NOW(),
+                fk_revoking_employee_id = $1
+            WHERE pk_employee_permission_delegation_id = $2
+            "#,
+            author_id,
+            delegation_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: if is_delegator { CREATE_DELEGATION_PERMISSION } else { REVOKE_DELEGATION_PERMISSION },
+            entity_id: Some(existing.fk_delegate_employee_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "REVOKE_PERMISSION_DELEGATION",
+                "delegation_id": delegation_id,
+                "delegator_employee_id": existing.fk_delegator_employee_id,
+            }),
+        }).await?;
+
+        invalidate_employee_permissions(&mut *tx, &[existing.fk_delegate_employee_id]).await?;
+
+        tx.commit().await?;
+
+        self.get_delegation_by_id(delegation_id).await
+    }
+}
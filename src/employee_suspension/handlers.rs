@@ -0,0 +1,106 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    employee_suspension::{models::{CreateEmployeeSuspensionRequest, EmployeeSuspension, GetAllEmployeeSuspensionsQuery, LiftEmployeeSuspensionRequest, UpdateEmployeeSuspensionRequest}, services::EmployeeSuspensionService}, errors::app_error::AppError, middleware::AuthState, models::{paginate::{PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}, path::parse_uuid}
+};
+
+fn validate_request<T: Validate>(req: &T) -> Result<(), AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(format!("The request content is not valid: {}", e)))
+}
+
+async fn paginated_suspensions(
+    employee_id: Option<&Uuid>,
+    filters: &GetAllEmployeeSuspensionsQuery,
+    suspension_service: &EmployeeSuspensionService,
+) -> Result<Json<PaginatedResponse<EmployeeSuspension>>, AppError> {
+    if filters.page == 0 {
+        return Err(AppError::Validation("Page must be greater than 0".to_string()));
+    }
+    if filters.limit == 0 || filters.limit > PAGINATE_MAX_LIMIT {
+        return Err(AppError::Validation(format!("Limit must be between 1 and {}.", PAGINATE_MAX_LIMIT)));
+    }
+
+    let (suspensions, total) = suspension_service.get_all_suspensions(employee_id, filters).await?;
+
+    Ok(Json(PaginatedResponse {
+        data: suspensions,
+        pagination: PaginationInfo {
+            page: filters.page,
+            limit: filters.limit,
+            total,
+        },
+    }))
+}
+
+pub async fn get_all_suspensions(
+    Query(filters): Query<GetAllEmployeeSuspensionsQuery>,
+    State(suspension_service): State<Arc<EmployeeSuspensionService>>,
+) -> Result<Json<PaginatedResponse<EmployeeSuspension>>, AppError> {
+    paginated_suspensions(None, &filters, &suspension_service).await
+}
+
+pub async fn get_employee_suspensions(
+    Path(employee_id): Path<String>,
+    Query(filters): Query<GetAllEmployeeSuspensionsQuery>,
+    State(suspension_service): State<Arc<EmployeeSuspensionService>>,
+) -> Result<Json<PaginatedResponse<EmployeeSuspension>>, AppError> {
+    let employee_uuid = parse_uuid(&employee_id, "Employee")?;
+    paginated_suspensions(Some(&employee_uuid), &filters, &suspension_service).await
+}
+
+pub async fn get_suspension_by_id(
+    Path(suspension_id): Path<String>,
+    State(suspension_service): State<Arc<EmployeeSuspensionService>>,
+) -> Result<Json<EmployeeSuspension>, AppError> {
+    let suspension_uuid = parse_uuid(&suspension_id, "Suspension")?;
+    let suspension = suspension_service.get_suspension_by_id(&suspension_uuid).await?;
+    Ok(Json(suspension))
+}
+
+pub async fn create_suspension(
+    Path(employee_id): Path<String>,
+    State(suspension_service): State<Arc<EmployeeSuspensionService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<CreateEmployeeSuspensionRequest>,
+) -> Result<(StatusCode, Json<EmployeeSuspension>), AppError> {
+    let employee_uuid = parse_uuid(&employee_id, "Employee")?;
+    validate_request(&create_req)?;
+
+    let suspension = suspension_service.create_suspension(&employee_uuid, &create_req, &auth_state.employee_id).await?;
+    Ok((StatusCode::CREATED, Json(suspension)))
+}
+
+pub async fn update_suspension(
+    Path(suspension_id): Path<String>,
+    State(suspension_service): State<Arc<EmployeeSuspensionService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(update_req): Json<UpdateEmployeeSuspensionRequest>,
+) -> Result<Json<EmployeeSuspension>, AppError> {
+    let suspension_uuid = parse_uuid(&suspension_id, "Suspension")?;
+    validate_request(&update_req)?;
+
+    let suspension = suspension_service.update_suspension(&suspension_uuid, &update_req, &auth_state.employee_id).await?;
+    Ok(Json(suspension))
+}
+
+pub async fn lift_suspension(
+    Path(suspension_id): Path<String>,
+    State(suspension_service): State<Arc<EmployeeSuspensionService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(lift_req): Json<LiftEmployeeSuspensionRequest>,
+) -> Result<Json<EmployeeSuspension>, AppError> {
+    let suspension_uuid = parse_uuid(&suspension_id, "Suspension")?;
+    validate_request(&lift_req)?;
+
+    let suspension = suspension_service.lift_suspension(&suspension_uuid, &lift_req, &auth_state.employee_id).await?;
+    Ok(Json(suspension))
+}
@@ -0,0 +1,71 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::{nullable::nullable, paginate::{default_limit, default_page, default_sort_order}};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmployeeSuspension {
+    pub pk_employee_suspension_id: Uuid,
+    pub fk_employee_id: Uuid,
+    pub fk_suspending_employee_id: Uuid,
+    pub reason: String,
+    pub start_at: DateTime<Utc>,
+    pub end_at: Option<DateTime<Utc>>,
+    pub lifted_at: Option<DateTime<Utc>>,
+    pub fk_lifting_employee_id: Option<Uuid>,
+    pub lift_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateEmployeeSuspensionRequest {
+    #[validate(length(min = 1, max = 1000, message = "Reason is required and cannot be longer than 1000 characters"))]
+    pub reason: String,
+
+    /// Defaults to now
+    pub start_at: Option<DateTime<Utc>>,
+    /// No end means the suspension lasts until it is lifted
+    pub end_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateEmployeeSuspensionRequest {
+    #[validate(length(min = 1, max = 1000, message = "Reason cannot be empty and cannot be longer than 1000 characters"))]
+    pub reason: Option<String>,
+
+    /// `null` makes the suspension open-ended again
+    #[serde(default, deserialize_with = "nullable")]
+    pub end_at: Option<Option<DateTime<Utc>>>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct LiftEmployeeSuspensionRequest {
+    #[validate(length(min = 1, max = 1000, message = "Lift reason is required and cannot be longer than 1000 characters"))]
+    pub lift_reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetAllEmployeeSuspensionsQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    #[serde(default)]
+    pub active: Option<bool>,
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+}
+
+impl Default for GetAllEmployeeSuspensionsQuery {
+    fn default() -> Self {
+        Self {
+            page: default_page(),
+            limit: default_limit(),
+            active: None,
+            sort_order: default_sort_order(),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use axum::{
+    middleware::{from_fn, from_fn_with_state}, routing::{get, post, put}, Router
+};
+use crate::{
+    employee_suspension::{handlers::{create_suspension, get_all_suspensions, get_employee_suspensions, get_suspension_by_id, lift_suspension, update_suspension}, services::EmployeeSuspensionService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+};
+use std::sync::Arc;
+
+pub fn protected_employee_suspension_routes(
+    middleware_state: MiddlewareState,
+    suspension_service: Arc<EmployeeSuspensionService>,
+) -> Router {
+    Router::new()
+        .route("/employees/suspensions", get(get_all_suspensions).route_layer(from_fn(with_required_permissions(vec![28]))))
+        .route("/employees/suspensions/{id}", get(get_suspension_by_id).route_layer(from_fn(with_required_permissions(vec![28]))))
+        .route("/employees/suspensions/{id}", put(update_suspension).route_layer(from_fn(with_required_permissions(vec![30]))))
+        .route("/employees/suspensions/{id}/lift", post(lift_suspension).route_layer(from_fn(with_required_permissions(vec![31]))))
+        .route("/employees/{id}/suspensions", get(get_employee_suspensions).route_layer(from_fn(with_required_permissions(vec![28]))))
+        .route("/employees/{id}/suspensions", post(create_suspension).route_layer(from_fn(with_required_permissions(vec![29]))))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(suspension_service.clone())
+}
@@ -0,0 +1,235 @@
+use chrono::Utc;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    auth::services::invalidate_employee_permissions, employee::models::EntityType, employee_suspension::models::{CreateEmployeeSuspensionRequest, EmployeeSuspension, GetAllEmployeeSuspensionsQuery, LiftEmployeeSuspensionRequest, UpdateEmployeeSuspensionRequest}, errors::app_error::AppError, history::{models::{FieldChanges, NewActionHistory}, services::record_action}
+};
+
+/// SQL condition matching the suspensions currently in effect
+const ACTIVE_SUSPENSION_CONDITION: &str = "(lifted_at IS NULL AND start_at <= NOW() AND (end_at IS NULL OR end_at > NOW()))";
+
+pub struct EmployeeSuspensionService {
+    pool: PgPool,
+}
+
+impl EmployeeSuspensionService {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // Get all suspensions, optionally restricted to one employee
+    pub async fn get_all_suspensions(&self, employee_id: Option<&Uuid>, filters: &GetAllEmployeeSuspensionsQuery) -> Result<(Vec<EmployeeSuspension>, u64), AppError> {
+        let offset = (filters.page - 1) * filters.limit;
+
+        let where_clause = format!(
+            "WHERE ($1::UUID IS NULL OR fk_employee_id = $1) AND ($2::BOOL IS NULL OR $2 = {})",
+            ACTIVE_SUSPENSION_CONDITION
+        );
+
+        let total_count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) as count FROM employee_suspensions {}", where_clause))
+            .bind(employee_id)
+            .bind(filters.active)
+            .fetch_one(&self.pool)
+            .await?;
+
+        // Determine sort order (default to ASC for chronological order)
+        let order_direction = match filters.sort_order.to_lowercase().as_str() {
+            "desc" => "DESC",
+            _ => "ASC",
+        };
+
+        let select_query = format!(
+            "SELECT pk_employee_suspension_id, fk_employee_id, fk_suspending_employee_id, reason, start_at, end_at, lifted_at, fk_lifting_employee_id, lift_reason, created_at FROM employee_suspensions {} ORDER BY start_at {} LIMIT $3 OFFSET $4",
+            where_clause,
+            order_direction
+        );
+
+        let suspensions = sqlx::query_as::<_, EmployeeSuspension>(&select_query)
+            .bind(employee_id)
+            .bind(filters.active)
+            .bind(filters.limit as i64)
+            .bind(offset as i64)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok((suspensions, total_count as u64))
+    }
+
+    pub async fn get_suspension_by_id(&self, suspension_id: &Uuid) -> Result<EmployeeSuspension, AppError> {
+        sqlx::query_as!(
+            EmployeeSuspension,
+            r#"
+            SELECT pk_employee_suspension_id, fk_employee_id, fk_suspending_employee_id, reason, start_at, end_at, lifted_at, fk_lifting_employee_id, lift_reason, created_at
+            FROM employee_suspensions
+            WHERE pk_employee_suspension_id = $1
+            "#,
+            suspension_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Employee suspension not found".to_string()))
+    }
+
+    pub async fn create_suspension(&self, employee_id: &Uuid, create_req: &CreateEmployeeSuspensionRequest, author_id: &Uuid) -> Result<EmployeeSuspension, AppError> {
+        if employee_id == author_id {
+            return Err(AppError::Conflict("An employee cannot suspend themselves".to_string(), "EMPLOYEE_SELF_SUSPENSION".to_string()));
+        }
+
+        let employee = sqlx::query!(
+            "SELECT deactivated_at FROM employees WHERE pk_employee_id = $1",
+            employee_id
+        )
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Employee not found".to_string()))?;
+
+        if employee.deactivated_at.is_some() {
+            return Err(AppError::Conflict("A deactivated employee cannot be suspended".to_string(), "EMPLOYEE_DEACTIVATED".to_string()));
+        }
+
+        let start_at = create_req.start_at.unwrap_or_else(Utc::now);
+        if create_req.end_at.is_some_and(|end_at| end_at <= start_at) {
+            return Err(AppError::Validation("The suspension end must be after its start".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let suspension = sqlx::query_as!(
+            EmployeeSuspension,
+            r#"
+            INSERT INTO employee_suspensions (fk_employee_id, fk_suspending_employee_id, reason, start_at, end_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING pk_employee_suspension_id, fk_employee_id, fk_suspending_employee_id, reason, start_at, end_at, lifted_at, fk_lifting_employee_id, lift_reason, created_at
+            "#,
+            employee_id,
+            author_id,
+            create_req.reason,
+            start_at,
+            create_req.end_at
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 29,
+            entity_id: Some(*employee_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "SUSPEND_EMPLOYEE",
+                "suspension_id": suspension.pk_employee_suspension_id,
+                "reason": suspension.reason,
+                "start_at": suspension.start_at,
+                "end_at": suspension.end_at,
+            }),
+        }).await?;
+
+        // the authorizations delegated by the suspended employee are lost with theirs
+        invalidate_employee_permissions(&mut *tx, &[*employee_id]).await?;
+
+        tx.commit().await?;
+
+        Ok(suspension)
+    }
+
+    pub async fn update_suspension(&self, suspension_id: &Uuid, update_req: &UpdateEmployeeSuspensionRequest, author_id: &Uuid) -> Result<EmployeeSuspension, AppError> {
+        let existing = self.get_suspension_by_id(suspension_id).await?;
+        if existing.lifted_at.is_some() {
+            return Err(AppError::Conflict("A lifted suspension cannot be modified".to_string(), "EMPLOYEE_SUSPENSION_LIFTED".to_string()));
+        }
+
+        if update_req.end_at.flatten().is_some_and(|end_at| end_at <= existing.start_at) {
+            return Err(AppError::Validation("The suspension end must be after its start".to_string()));
+        }
+
+        let mut changes = FieldChanges::new();
+        changes.track("reason", &existing.reason, update_req.reason.as_ref());
+        changes.track("end_at", &existing.end_at, update_req.end_at.as_ref());
+
+        if changes.is_empty() {
+            return Ok(existing);
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        // it may have been lifted meanwhile
+        let suspension = sqlx::query_as!(
+            EmployeeSuspension,
+            r#"
+            UPDATE employee_suspensions SET
+                reason = COALESCE($1, reason),
+                end_at = CASE WHEN $2 THEN $3 ELSE end_at END
+            WHERE pk_employee_suspension_id = $4 AND lifted_at IS NULL
+            RETURNING pk_employee_suspension_id, fk_employee_id, fk_suspending_employee_id, reason, start_at, end_at, lifted_at, fk_lifting_employee_id, lift_reason, created_at
+            "#,
+            update_req.reason,
+            update_req.end_at.is_some(),
+            update_req.end_at.flatten(),
+            suspension_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("A lifted suspension cannot be modified".to_string(), "EMPLOYEE_SUSPENSION_LIFTED".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 30,
+            entity_id: Some(suspension.fk_employee_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "UPDATE_EMPLOYEE_SUSPENSION",
+                "suspension_id": suspension.pk_employee_suspension_id,
+                "changes": changes.into_value(),
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(suspension)
+    }
+
+    // Lift a suspension, the row is kept to preserve the history
+    pub async fn lift_suspension(&self, suspension_id: &Uuid, lift_req: &LiftEmployeeSuspensionRequest, author_id: &Uuid) -> Result<EmployeeSuspension, AppError> {
+        let existing = self.get_suspension_by_id(suspension_id).await?;
+        if existing.lifted_at.is_some() {
+            return Err(AppError::Conflict("Employee suspension has already been lifted".to_string(), "EMPLOYEE_SUSPENSION_LIFTED".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let suspension = sqlx::query_as!(
+            EmployeeSuspension,
+            r#"
+            UPDATE employee_suspensions SET
+                lifted_at = NOW(),
+                fk_lifting_employee_id = $1,
+                lift_reason = $2
+            WHERE pk_employee_suspension_id = $3 AND lifted_at IS NULL
+            RETURNING pk_employee_suspension_id, fk_employee_id, fk_suspending_employee_id, reason, start_at, end_at, lifted_at, fk_lifting_employee_id, lift_reason, created_at
+            "#,
+            author_id,
+            lift_req.lift_reason,
+            suspension_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("Employee suspension has already been lifted".to_string(), "EMPLOYEE_SUSPENSION_LIFTED".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 31,
+            entity_id: Some(suspension.fk_employee_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "LIFT_EMPLOYEE_SUSPENSION",
+                "suspension_id": suspension.pk_employee_suspension_id,
+                "lift_reason": suspension.lift_reason,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(suspension)
+    }
+}
@@ -0,0 +1,129 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::{
+    employee::models::{EmployeeAccreditationCreate, EmployeeAccreditationRevoke}, employee_team::{models::{AddEmployeeTeamMemberRequest, CreateEmployeeTeamRequest, EmployeeTeam, EmployeeTeamAccreditation, EmployeeTeamDetails, EmployeeTeamMembership, GetAllEmployeeTeamsQuery, UpdateEmployeeTeamRequest}, services::EmployeeTeamService}, errors::app_error::AppError, middleware::AuthState, models::{paginate::{PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}, path::parse_uuid}
+};
+
+fn validate_request<T: Validate>(req: &T) -> Result<(), AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(format!("The request content is not valid: {}", e)))
+}
+
+pub async fn get_all_teams(
+    Query(filters): Query<GetAllEmployeeTeamsQuery>,
+    State(team_service): State<Arc<EmployeeTeamService>>,
+) -> Result<Json<PaginatedResponse<EmployeeTeam>>, AppError> {
+    if filters.page == 0 {
+        return Err(AppError::Validation("Page must be greater than 0".to_string()));
+    }
+    if filters.limit == 0 || filters.limit > PAGINATE_MAX_LIMIT {
+        return Err(AppError::Validation(format!("Limit must be between 1 and {}.", PAGINATE_MAX_LIMIT)));
+    }
+
+    let (teams, total) = team_service.get_all_teams(&filters).await?;
+
+    Ok(Json(PaginatedResponse {
+        data: teams,
+        pagination: PaginationInfo {
+            page: filters.page,
+            limit: filters.limit,
+            total,
+        },
+    }))
+}
+
+pub async fn get_team_by_id(
+    Path(team_id): Path<String>,
+    State(team_service): State<Arc<EmployeeTeamService>>,
+) -> Result<Json<EmployeeTeamDetails>, AppError> {
+    let team_uuid = parse_uuid(&team_id, "Team")?;
+    let team = team_service.get_team_details(&team_uuid).await?;
+    Ok(Json(team))
+}
+
+pub async fn create_team(
+    State(team_service): State<Arc<EmployeeTeamService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<CreateEmployeeTeamRequest>,
+) -> Result<(StatusCode, Json<EmployeeTeam>), AppError> {
+    validate_request(&create_req)?;
+    let team = team_service.create_team(&create_req, &auth_state.employee_id).await?;
+    Ok((StatusCode::CREATED, Json(team)))
+}
+
+pub async fn update_team(
+    Path(team_id): Path<String>,
+    State(team_service): State<Arc<EmployeeTeamService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(update_req): Json<UpdateEmployeeTeamRequest>,
+) -> Result<Json<EmployeeTeam>, AppError> {
+    validate_request(&update_req)?;
+    let team_uuid = parse_uuid(&team_id, "Team")?;
+    let team = team_service.update_team(&team_uuid, &update_req, &auth_state.employee_id).await?;
+    Ok(Json(team))
+}
+
+pub async fn delete_team(
+    Path(team_id): Path<String>,
+    State(team_service): State<Arc<EmployeeTeamService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<StatusCode, AppError> {
+    let team_uuid = parse_uuid(&team_id, "Team")?;
+    team_service.delete_team(&team_uuid, &auth_state.employee_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn add_team_member(
+    Path(team_id): Path<String>,
+    State(team_service): State<Arc<EmployeeTeamService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(add_req): Json<AddEmployeeTeamMemberRequest>,
+) -> Result<(StatusCode, Json<EmployeeTeamMembership>), AppError> {
+    validate_request(&add_req)?;
+    let team_uuid = parse_uuid(&team_id, "Team")?;
+    let membership = team_service.add_member(&team_uuid, &add_req, &auth_state.employee_id).await?;
+    Ok((StatusCode::CREATED, Json(membership)))
+}
+
+pub async fn remove_team_member(
+    Path((team_id, membership_id)): Path<(String, String)>,
+    State(team_service): State<Arc<EmployeeTeamService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<EmployeeTeamMembership>, AppError> {
+    let team_uuid = parse_uuid(&team_id, "Team")?;
+    let membership_uuid = parse_uuid(&membership_id, "Team membership")?;
+    let membership = team_service.remove_member(&team_uuid, &membership_uuid, &auth_state.employee_id).await?;
+    Ok(Json(membership))
+}
+
+pub async fn create_team_accreditation(
+    Path(team_id): Path<String>,
+    State(team_service): State<Arc<EmployeeTeamService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<EmployeeAccreditationCreate>,
+) -> Result<(StatusCode, Json<EmployeeTeamAccreditation>), AppError> {
+    validate_request(&create_req)?;
+    let team_uuid = parse_uuid(&team_id, "Team")?;
+    let accreditation = team_service.create_team_accreditation(&team_uuid, &create_req, &auth_state.employee_id).await?;
+    Ok((StatusCode::CREATED, Json(accreditation)))
+}
+
+pub async fn revoke_team_accreditation(
+    Path((team_id, accreditation_id)): Path<(String, String)>,
+    State(team_service): State<Arc<EmployeeTeamService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(revoke_req): Json<EmployeeAccreditationRevoke>,
+) -> Result<Json<EmployeeTeamAccreditation>, AppError> {
+    validate_request(&revoke_req)?;
+    let team_uuid = parse_uuid(&team_id, "Team")?;
+    let accreditation_uuid = parse_uuid(&accreditation_id, "Team accreditation")?;
+    let accreditation = team_service.revoke_team_accreditation(&team_uuid, &accreditation_uuid, &revoke_req, &auth_state.employee_id).await?;
+    Ok(Json(accreditation))
+}
@@ -0,0 +1,91 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{employee::models::EmployeeLevel, models::paginate::{default_limit, default_page, default_sort_order}};
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmployeeTeam {
+    pub pk_employee_team_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub fk_creating_employee_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct EmployeeTeamMembership {
+    pub pk_employee_team_membership_id: Uuid,
+    pub fk_employee_team_id: Uuid,
+    pub fk_employee_id: Uuid,
+    pub joined_at: DateTime<Utc>,
+    /// No end means the employee stays a member until removed
+    pub left_at: Option<DateTime<Utc>>,
+    pub fk_adding_employee_id: Uuid,
+    pub fk_removing_employee_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A level held by a team, inherited by the employees while they are members of it
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct EmployeeTeamAccreditation {
+    pub pk_employee_team_accreditation_id: Uuid,
+    pub fk_employee_team_id: Uuid,
+    #[sqlx(flatten)]
+    pub employee_level: EmployeeLevel,
+    pub fk_authorizing_employee_id: Uuid,
+    pub start_at: DateTime<Utc>,
+    pub end_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub fk_revoking_employee_id: Option<Uuid>,
+    pub revocation_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A team with the history of its members and accreditations
+#[derive(Debug, Serialize)]
+pub struct EmployeeTeamDetails {
+    #[serde(flatten)]
+    pub team: EmployeeTeam,
+    pub memberships: Vec<EmployeeTeamMembership>,
+    pub accreditations: Vec<EmployeeTeamAccreditation>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateEmployeeTeamRequest {
+    #[validate(length(min = 1, max = 255, message = "Name is required and cannot be longer than 255 characters"))]
+    pub name: String,
+    #[validate(length(min = 1, max = 1000, message = "Description cannot be empty and cannot be longer than 1000 characters"))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct UpdateEmployeeTeamRequest {
+    #[validate(length(min = 1, max = 255, message = "Name cannot be empty and cannot be longer than 255 characters"))]
+    pub name: Option<String>,
+    #[validate(length(min = 1, max = 1000, message = "Description cannot be empty and cannot be longer than 1000 characters"))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct AddEmployeeTeamMemberRequest {
+    pub fk_employee_id: Uuid,
+    /// Defaults to now
+    pub joined_at: Option<DateTime<Utc>>,
+    pub left_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetAllEmployeeTeamsQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    #[serde(default)]
+    pub deleted: Option<bool>,
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+}
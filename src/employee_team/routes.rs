@@ -0,0 +1,29 @@
+use axum::{
+    middleware::{from_fn, from_fn_with_state}, routing::{delete, get, post, put}, Router
+};
+use crate::{
+    employee_team::{handlers::{add_team_member, create_team, create_team_accreditation, delete_team, get_all_teams, get_team_by_id, remove_team_member, revoke_team_accreditation, update_team}, services::EmployeeTeamService}, middleware::{auth_middleware, with_required_permissions, MiddlewareState}
+};
+use std::sync::Arc;
+
+pub fn protected_employee_team_routes(
+    middleware_state: MiddlewareState,
+    team_service: Arc<EmployeeTeamService>,
+) -> Router {
+    Router::new()
+        .route("/employees/teams", get(get_all_teams).route_layer(from_fn(with_required_permissions(vec![49]))))
+        .route("/employees/teams", post(create_team).route_layer(from_fn(with_required_permissions(vec![50]))))
+        .route("/employees/teams/{id}", get(get_team_by_id).route_layer(from_fn(with_required_permissions(vec![49]))))
+        .route("/employees/teams/{id}", put(update_team).patch(update_team).route_layer(from_fn(with_required_permissions(vec![51]))))
+        .route("/employees/teams/{id}", delete(delete_team).route_layer(from_fn(with_required_permissions(vec![52]))))
+        // the members inherit the accreditations of the team, adding one is also granting them
+        .route("/employees/teams/{id}/members", post(add_team_member).route_layer(from_fn(with_required_permissions(vec![51, 35]))))
+        .route("/employees/teams/{id}/members/{membership_id}/remove", post(remove_team_member).route_layer(from_fn(with_required_permissions(vec![51]))))
+        .route("/employees/teams/{id}/accreditations", post(create_team_accreditation).route_layer(from_fn(with_required_permissions(vec![35]))))
+        .route("/employees/teams/{id}/accreditations/{accreditation_id}/revoke", post(revoke_team_accreditation).route_layer(from_fn(with_required_permissions(vec![37]))))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(team_service.clone())
+}
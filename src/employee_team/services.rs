@@ -0,0 +1,517 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    auth::services::{invalidate_employee_permissions, invalidate_team_permissions}, employee::{models::{EmployeeAccreditationCreate, EmployeeAccreditationRevoke, EntityType}, services::{EmployeeService, ADMIN_LEVEL_LABEL}}, employee_team::models::{AddEmployeeTeamMemberRequest, CreateEmployeeTeamRequest, EmployeeTeam, EmployeeTeamAccreditation, EmployeeTeamDetails, EmployeeTeamMembership, GetAllEmployeeTeamsQuery, UpdateEmployeeTeamRequest}, errors::app_error::AppError, history::{models::{FieldChanges, NewActionHistory}, services::record_action}, models::period::validate_start_at
+};
+
+const TEAM_COLUMNS: &str = "pk_employee_team_id, name, description, fk_creating_employee_id, created_at, deleted_at";
+
+const MEMBERSHIP_COLUMNS: &str = "pk_employee_team_membership_id, fk_employee_team_id, fk_employee_id, joined_at, left_at, fk_adding_employee_id, fk_removing_employee_id, created_at";
+
+const TEAM_ACCREDITATION_COLUMNS: &str = r#"
+    eta.pk_employee_team_accreditation_id, eta.fk_employee_team_id,
+    el.pk_employee_level_id, el.level_index, el.level_label,
+    eta.fk_authorizing_employee_id, eta.start_at, eta.end_at, eta.revoked_at, eta.fk_revoking_employee_id, eta.revocation_reason, eta.created_at
+"#;
+
+pub struct EmployeeTeamService {
+    pool: PgPool,
+    employee_service: EmployeeService,
+}
+
+impl EmployeeTeamService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            employee_service: EmployeeService::new(pool.clone()),
+            pool,
+        }
+    }
+
+    pub async fn get_all_teams(&self, filters: &GetAllEmployeeTeamsQuery) -> Result<(Vec<EmployeeTeam>, u64), AppError> {
+        let offset = (filters.page - 1) * filters.limit;
+
+        let where_clause = "WHERE ($1::BOOL IS NULL OR $1 = (deleted_at IS NOT NULL))";
+
+        let total_count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) as count FROM employee_teams {}", where_clause))
+            .bind(filters.deleted)
+            .fetch_one(&self.pool)
+            .await?;
+
+        // Determine sort order (default to ASC for alphabetical order)
+        let order_direction = match filters.sort_order.to_lowercase().as_str() {
+            "desc" => "DESC",
+            _ => "ASC",
+        };
+
+        let teams = sqlx::query_as::<_, EmployeeTeam>(&format!(
+            "SELECT {} FROM employee_teams {} ORDER BY name {} LIMIT $2 OFFSET $3",
+            TEAM_COLUMNS,
+            where_clause,
+            order_direction
+        ))
+        .bind(filters.deleted)
+        .bind(filters.limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((teams, total_count as u64))
+    }
+
+    pub async fn get_team_by_id(&self, team_id: &Uuid) -> Result<EmployeeTeam, AppError> {
+        sqlx::query_as::<_, EmployeeTeam>(&format!("SELECT {} FROM employee_teams WHERE pk_employee_team_id = $1", TEAM_COLUMNS))
+            .bind(team_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Team not found".to_string()))
+    }
+
+    pub async fn get_team_details(&self, team_id: &Uuid) -> Result<EmployeeTeamDetails, AppError> {
+        let team = self.get_team_by_id(team_id).await?;
+
+        let memberships = sqlx::query_as::<_, EmployeeTeamMembership>(&format!(
+            "SELECT {} FROM employee_team_memberships WHERE fk_employee_team_id = $1 ORDER BY joined_at",
+            MEMBERSHIP_COLUMNS
+        ))
+        .bind(team_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let accreditations = sqlx::query_as::<_, EmployeeTeamAccreditation>(&format!(
+            "SELECT {} FROM employee_team_accreditations eta JOIN employee_levels el ON el.pk_employee_level_id = eta.fk_employee_level_id WHERE eta.fk_employee_team_id = $1 ORDER BY eta.start_at",
+            TEAM_ACCREDITATION_COLUMNS
+        ))
+        .bind(team_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(EmployeeTeamDetails { team, memberships, accreditations })
+    }
+
+    async fn get_membership_by_id(&self, membership_id: &Uuid) -> Result<EmployeeTeamMembership, AppError> {
+        sqlx::query_as::<_, EmployeeTeamMembership>(&format!("SELECT {} FROM employee_team_memberships WHERE pk_employee_team_membership_id = $1", MEMBERSHIP_COLUMNS))
+            .bind(membership_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Team membership not found".to_string()))
+    }
+
+    async fn get_team_accreditation_by_id(&self, accreditation_id: &Uuid) -> Result<EmployeeTeamAccreditation, AppError> {
+        sqlx::query_as::<_, EmployeeTeamAccreditation>(&format!(
+            "SELECT {} FROM employee_team_accreditations eta JOIN employee_levels el ON el.pk_employee_level_id = eta.fk_employee_level_id WHERE eta.pk_employee_team_accreditation_id = $1",
+            TEAM_ACCREDITATION_COLUMNS
+        ))
+        .bind(accreditation_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Team accreditation not found".to_string()))
+    }
+
+    // Return the team if it can still be changed
+    async fn get_active_team(&self, team_id: &Uuid) -> Result<EmployeeTeam, AppError> {
+        let team = self.get_team_by_id(team_id).await?;
+        if team.deleted_at.is_some() {
+            return Err(AppError::Conflict("A deleted team cannot be changed".to_string(), "TEAM_DELETED".to_string()));
+        }
+
+        Ok(team)
+    }
+
+    async fn team_name_exists_except_team(&self, name: &str, team_id: Option<&Uuid>) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM employee_teams
+                WHERE LOWER(name) = LOWER($1) AND deleted_at IS NULL AND ($2::UUID IS NULL OR pk_employee_team_id != $2)
+            ) as "exists!"
+            "#,
+            name,
+            team_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    pub async fn create_team(&self, create_req: &CreateEmployeeTeamRequest, author_id: &Uuid) -> Result<EmployeeTeam, AppError> {
+        if self.team_name_exists_except_team(&create_req.name, None).await? {
+            return Err(AppError::Conflict("A team with this name already exists".to_string(), "TEAM_NAME_EXISTS".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let team = sqlx::query_as::<_, EmployeeTeam>(&format!(
+            "INSERT INTO employee_teams (name, description, fk_creating_employee_id) VALUES ($1, $2, $3) RETURNING {}",
+            TEAM_COLUMNS
+        ))
+        .bind(&create_req.name)
+        .bind(&create_req.description)
+        .bind(author_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 50,
+            entity_id: Some(team.pk_employee_team_id),
+            entity_type: EntityType::EMPLOYEE_TEAM,
+            description: serde_json::json!({
+                "action": "CREATE_TEAM",
+                "name": team.name,
+                "description": team.description,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(team)
+    }
+
+    pub async fn update_team(&self, team_id: &Uuid, update_req: &UpdateEmployeeTeamRequest, author_id: &Uuid) -> Result<EmployeeTeam, AppError> {
+        let existing = self.get_active_team(team_id).await?;
+
+        if let Some(name) = &update_req.name {
+            if self.team_name_exists_except_team(name, Some(team_id)).await? {
+                return Err(AppError::Conflict("A team with this name already exists".to_string(), "TEAM_NAME_EXISTS".to_string()));
+            }
+        }
+
+        let mut changes = FieldChanges::new();
+        changes.track("name", &existing.name, update_req.name.as_ref());
+        changes.track("description", &existing.description, update_req.description.as_ref().map(|_| &update_req.description));
+
+        if changes.is_empty() {
+            return Ok(existing);
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let team = sqlx::query_as::<_, EmployeeTeam>(&format!(
+            "UPDATE employee_teams SET name = COALESCE($1, name), description = COALESCE($2, description) WHERE pk_employee_team_id = $3 RETURNING {}",
+            TEAM_COLUMNS
+        ))
+        .bind(&update_req.name)
+        .bind(&update_req.description)
+        .bind(team_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 51,
+            entity_id: Some(*team_id),
+            entity_type: EntityType::EMPLOYEE_TEAM,
+            description: serde_json::json!({
+                "action": "UPDATE_TEAM",
+                "changes": changes.into_value(),
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(team)
+    }
+
+    // Soft delete a team, its memberships and accreditations end with it
+    pub async fn delete_team(&self, team_id: &Uuid, author_id: &Uuid) -> Result<(), AppError> {
+        self.get_active_team(team_id).await?;
+
+        let mut tx = self.pool.begin().await?;
+
+        // before the memberships end, so that the members are still found
+        invalidate_team_permissions(&mut *tx, team_id).await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE employee_team_memberships SET
+                left_at = GREATEST(joined_at, NOW()),
+                fk_removing_employee_id = $1
+            WHERE fk_employee_team_id = $2 AND (left_at IS NULL OR left_at > NOW())
+            "#,
+            author_id,
+            team_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE employee_team_accreditations SET
+                end_at = GREATEST(start_at, NOW()),
+                revoked_at = NOW(),
+                fk_revoking_employee_id = $1,
+                revocation_reason = 'Team deleted'
+            WHERE fk_employee_team_id = $2 AND revoked_at IS NULL AND (end_at IS NULL OR end_at > NOW())
+            "#,
+            author_id,
+            team_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query!("UPDATE employee_teams SET deleted_at = NOW() WHERE pk_employee_team_id = $1", team_id)
+            .execute(&mut *tx)
+            .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 52,
+            entity_id: Some(*team_id),
+            entity_type: EntityType::EMPLOYEE_TEAM,
+            description: serde_json::json!({
+                "action": "DELETE_TEAM",
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    // Check if the employee is a member of the team during any part of the period
+    async fn membership_overlaps(&self, team_id: &Uuid, employee_id: &Uuid, joined_at: &DateTime<Utc>, left_at: Option<&DateTime<Utc>>) -> Result<bool, AppError> {
+        let exists = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM employee_team_memberships
+                WHERE fk_employee_team_id = $1
+                    AND fk_employee_id = $2
+                    AND tstzrange(joined_at, left_at) && tstzrange($3, $4)
+            ) as "exists!"
+            "#,
+            team_id,
+            employee_id,
+            joined_at,
+            left_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(exists)
+    }
+
+    pub async fn add_member(&self, team_id: &Uuid, add_req: &AddEmployeeTeamMemberRequest, author_id: &Uuid) -> Result<EmployeeTeamMembership, AppError> {
+        self.get_active_team(team_id).await?;
+
+        let employee = self.employee_service.get_employee_by_id(&add_req.fk_employee_id.to_string()).await?;
+        if employee.deactivated_at.is_some() {
+            return Err(AppError::Conflict("A deactivated employee cannot join a team".to_string(), "EMPLOYEE_DEACTIVATED".to_string()));
+        }
+
+        let joined_at = add_req.joined_at.unwrap_or_else(Utc::now);
+        validate_start_at(&joined_at, "membership")?;
+        if add_req.left_at.is_some_and(|left_at| left_at <= joined_at) {
+            return Err(AppError::Validation("The membership end must be after its start".to_string()));
+        }
+        if self.membership_overlaps(team_id, &add_req.fk_employee_id, &joined_at, add_req.left_at.as_ref()).await? {
+            return Err(AppError::Conflict("The employee is already a member of the team during the requested period".to_string(), "TEAM_MEMBERSHIP_OVERLAP".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let membership = sqlx::query_as::<_, EmployeeTeamMembership>(&format!(
+            "INSERT INTO employee_team_memberships (fk_employee_team_id, fk_employee_id, joined_at, left_at, fk_adding_employee_id) VALUES ($1, $2, $3, $4, $5) RETURNING {}",
+            MEMBERSHIP_COLUMNS
+        ))
+        .bind(team_id)
+        .bind(add_req.fk_employee_id)
+        .bind(joined_at)
+        .bind(add_req.left_at)
+        .bind(author_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 51,
+            entity_id: Some(*team_id),
+            entity_type: EntityType::EMPLOYEE_TEAM,
+            description: serde_json::json!({
+                "action": "ADD_TEAM_MEMBER",
+                "membership_id": membership.pk_employee_team_membership_id,
+                "employee_id": membership.fk_employee_id,
+                "joined_at": membership.joined_at,
+                "left_at": membership.left_at,
+            }),
+        }).await?;
+
+        invalidate_employee_permissions(&mut *tx, &[membership.fk_employee_id]).await?;
+
+        tx.commit().await?;
+
+        Ok(membership)
+    }
+
+    // End a membership now, or cancel it if it has not started yet
+    pub async fn remove_member(&self, team_id: &Uuid, membership_id: &Uuid, author_id: &Uuid) -> Result<EmployeeTeamMembership, AppError> {
+        let existing = self.get_membership_by_id(membership_id).await?;
+        if existing.fk_employee_team_id != *team_id {
+            return Err(AppError::NotFound("Team membership not found".to_string()));
+        }
+        if existing.left_at.is_some_and(|left_at| left_at <= Utc::now()) {
+            return Err(AppError::Conflict("The membership has already ended".to_string(), "TEAM_MEMBERSHIP_ENDED".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let membership = sqlx::query_as::<_, EmployeeTeamMembership>(&format!(
+            "UPDATE employee_team_memberships SET left_at = GREATEST(joined_at, NOW()), fk_removing_employee_id = $1 WHERE pk_employee_team_membership_id = $2 RETURNING {}",
+            MEMBERSHIP_COLUMNS
+        ))
+        .bind(author_id)
+        .bind(membership_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 51,
+            entity_id: Some(*team_id),
+            entity_type: EntityType::EMPLOYEE_TEAM,
+            description: serde_json::json!({
+                "action": "REMOVE_TEAM_MEMBER",
+                "membership_id": membership_id,
+                "employee_id": membership.fk_employee_id,
+                "previous_left_at": existing.left_at,
+            }),
+        }).await?;
+
+        invalidate_employee_permissions(&mut *tx, &[membership.fk_employee_id]).await?;
+
+        tx.commit().await?;
+
+        Ok(membership)
+    }
+
+    // Grant a level to a team for a period, its members inherit it
+    // The ADMIN level is only granted individually, with the approval of a second employee
+    pub async fn create_team_accreditation(&self, team_id: &Uuid, create_req: &EmployeeAccreditationCreate, author_id: &Uuid) -> Result<EmployeeTeamAccreditation, AppError> {
+        self.get_active_team(team_id).await?;
+
+        let level = self.employee_service.get_employee_level_by_id(create_req.fk_employee_level_id).await?;
+        if level.level_label == ADMIN_LEVEL_LABEL {
+            return Err(AppError::Forbidden(format!("The {} level cannot be granted to a team", ADMIN_LEVEL_LABEL), "FOUR_EYES_REQUIRED".to_string()));
+        }
+        if self.employee_service.employee_level_is_deleted(level.pk_employee_level_id).await? {
+            return Err(AppError::Conflict("A deleted level cannot be granted".to_string(), "LEVEL_DELETED".to_string()));
+        }
+
+        validate_start_at(&create_req.start_at, "accreditation")?;
+        if create_req.end_at.is_some_and(|end_at| end_at <= create_req.start_at) {
+            return Err(AppError::Validation("The accreditation end must be after its start".to_string()));
+        }
+
+        let overlaps = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM employee_team_accreditations
+                WHERE fk_employee_team_id = $1
+                    AND fk_employee_level_id = $2
+                    AND tstzrange(start_at, end_at) && tstzrange($3, $4)
+            ) as "exists!"
+            "#,
+            team_id,
+            level.pk_employee_level_id,
+            create_req.start_at,
+            create_req.end_at
+        )
+        .fetch_one(&self.pool)
+        .await?;
+        if overlaps {
+            return Err(AppError::Conflict("The team already holds this level during the requested period".to_string(), "ACCREDITATION_OVERLAP".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let accreditation_id = sqlx::query_scalar!(
+            r#"
+            INSERT INTO employee_team_accreditations (fk_employee_team_id, fk_employee_level_id, fk_authorizing_employee_id, start_at, end_at)
+            VALUES ($1, $2, $3, $4, $5)
+            RETURNING pk_employee_team_accreditation_id
+            "#,
+            team_id,
+            level.pk_employee_level_id,
+            author_id,
+            create_req.start_at,
+            create_req.end_at
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 35,
+            entity_id: Some(*team_id),
+            entity_type: EntityType::EMPLOYEE_TEAM,
+            description: serde_json::json!({
+                "action": "GRANT_TEAM_ACCREDITATION",
+                "team_accreditation_id": accreditation_id,
+                "employee_level_id": level.pk_employee_level_id,
+                "employee_level_label": level.level_label,
+                "start_at": create_req.start_at,
+                "end_at": create_req.end_at,
+            }),
+        }).await?;
+
+        invalidate_team_permissions(&mut *tx, team_id).await?;
+
+        tx.commit().await?;
+
+        self.get_team_accreditation_by_id(&accreditation_id).await
+    }
+
+    // Revoke a team accreditation, it is ended and kept with who revoked it and why
+    pub async fn revoke_team_accreditation(&self, team_id: &Uuid, accreditation_id: &Uuid, revoke_req: &EmployeeAccreditationRevoke, author_id: &Uuid) -> Result<EmployeeTeamAccreditation, AppError> {
+        let existing = self.get_team_accreditation_by_id(accreditation_id).await?;
+        if existing.fk_employee_team_id != *team_id {
+            return Err(AppError::NotFound("Team accreditation not found".to_string()));
+        }
+        if existing.revoked_at.is_some() {
+            return Err(AppError::Conflict("Accreditation has already been revoked".to_string(), "ACCREDITATION_REVOKED".to_string()));
+        }
+        if existing.end_at.is_some_and(|end_at| end_at <= Utc::now()) {
+            return Err(AppError::Conflict("An ended accreditation cannot be revoked".to_string(), "ACCREDITATION_ENDED".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"
+            UPDATE employee_team_accreditations
+            SET end_at = GREATEST(start_at, NOW()),
+                revoked_at = NOW(),
+                fk_revoking_employee_id = $1,
+                revocation_reason = $2
+            WHERE pk_employee_team_accreditation_id = $3
+            "#,
+            author_id,
+            revoke_req.revocation_reason,
+            accreditation_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 37,
+            entity_id: Some(*team_id),
+            entity_type: EntityType::EMPLOYEE_TEAM,
+            description: serde_json::json!({
+                "action": "REVOKE_TEAM_ACCREDITATION",
+                "team_accreditation_id": accreditation_id,
+                "employee_level_id": existing.employee_level.pk_employee_level_id,
+                "previous_end_at": existing.end_at,
+                "revocation_reason": revoke_req.revocation_reason,
+            }),
+        }).await?;
+
+        invalidate_team_permissions(&mut *tx, team_id).await?;
+
+        tx.commit().await?;
+
+        self.get_team_accreditation_by_id(accreditation_id).await
+    }
+}
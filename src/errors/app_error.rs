@@ -23,6 +23,9 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Forbidden: {0} (Code: {1})")]
+    Forbidden(String, String),
+
     #[error("Insufficient permissions: {0:?}")]
     InsufficientPermissions(Vec<i32>),
     
@@ -38,12 +41,13 @@ impl IntoResponse for AppError {
             AppError::Conflict(ref message, ref _error_code) => (StatusCode::CONFLICT, message.as_str()),
             AppError::NotFound(ref message) => (StatusCode::NOT_FOUND, message.as_str()),
             AppError::Validation(ref message) => (StatusCode::BAD_REQUEST, message.as_str()),
+            AppError::Forbidden(ref message, ref _error_code) => (StatusCode::FORBIDDEN, message.as_str()),
             AppError::InsufficientPermissions(ref _permissions) => (StatusCode::FORBIDDEN, "Insufficient permissions"),
             AppError::Internal(ref message) => (StatusCode::INTERNAL_SERVER_ERROR, message.as_str()),
         };
 
         let body = match self {
-            AppError::Conflict(ref message, ref error_code) => {
+            AppError::Conflict(ref message, ref error_code) | AppError::Forbidden(ref message, ref error_code) => {
                 Json(json!({
                     "error": message,
                     "error_code": error_code,
@@ -0,0 +1,45 @@
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use uuid::Uuid;
+
+use crate::employee::models::EntityType;
+
+/// Deactivated employee created by the migrations, the actions of the background tasks are recorded under it
+pub const SYSTEM_EMPLOYEE_ID: Uuid = Uuid::from_u128(1);
+
+/// A row to append to `employee_action_histories`
+#[derive(Debug, Clone)]
+pub struct NewActionHistory {
+    pub employee_id: Uuid,
+    pub authorization_type_id: i32,
+    pub entity_id: Option<Uuid>,
+    pub entity_type: EntityType,
+    pub description: Value,
+}
+
+/// Collects the `{ "old": .., "new": .. }` diff of the fields modified by an update
+#[derive(Debug, Default)]
+pub struct FieldChanges(Map<String, Value>);
+
+impl FieldChanges {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the field only when a new value is provided and differs from the current one
+    pub fn track<T: Serialize + PartialEq + ?Sized>(&mut self, field: &str, old: &T, new: Option<&T>) {
+        if let Some(new) = new {
+            if new != old {
+                self.0.insert(field.to_string(), json!({ "old": old, "new": new }));
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn into_value(self) -> Value {
+        Value::Object(self.0)
+    }
+}
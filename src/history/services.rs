@@ -0,0 +1,26 @@
+use sqlx::PgExecutor;
+
+use crate::{errors::app_error::AppError, history::models::NewActionHistory};
+
+/// Append an entry to the employee action history.
+///
+/// Takes any executor so the entry can be written in the same transaction as the audited change.
+/// `clock_timestamp()` is used instead of the column default so that several entries written in
+/// one transaction do not collide on the primary key.
+pub async fn record_action<'e, E: PgExecutor<'e>>(executor: E, action: &NewActionHistory) -> Result<(), AppError> {
+    sqlx::query!(
+        r#"
+        INSERT INTO employee_action_histories (fk_employee_id, fk_employee_authorization_type_id, fk_entity_id, fk_entity_type, description, created_at)
+        VALUES ($1, $2, $3, ($4::TEXT)::"EntityType", $5, clock_timestamp())
+        "#,
+        action.employee_id,
+        action.authorization_type_id,
+        action.entity_id,
+        action.entity_type.as_str(),
+        action.description
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
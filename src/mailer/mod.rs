@@ -0,0 +1,68 @@
+use lettre::{
+    message::{header::ContentType, Mailbox}, transport::smtp::authentication::Credentials, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum MailError {
+    #[error("Invalid address: {0}")]
+    Address(#[from] lettre::address::AddressError),
+
+    #[error("Invalid message: {0}")]
+    Message(#[from] lettre::error::Error),
+
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+}
+
+/// Sends the plain text mails through an SMTP server
+pub struct Mailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl Mailer {
+    /// SMTP server from `SMTP_HOST`, `SMTP_PORT`, `SMTP_TLS` (none, starttls or tls)
+    /// and the optional `SMTP_USERNAME` and `SMTP_PASSWORD`, sender from `MAIL_FROM`
+    pub fn from_env() -> Self {
+        let host = std::env::var("SMTP_HOST").unwrap_or_else(|_| "localhost".to_string());
+        let tls = std::env::var("SMTP_TLS").unwrap_or_else(|_| "starttls".to_string());
+
+        let mut builder = match tls.as_str() {
+            // only for a local catch-all server
+            "none" => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&host),
+            "starttls" => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host).expect("SMTP_HOST is not valid"),
+            "tls" => AsyncSmtpTransport::<Tokio1Executor>::relay(&host).expect("SMTP_HOST is not valid"),
+            _ => panic!("SMTP_TLS must be none, starttls or tls"),
+        };
+        if let Ok(port) = std::env::var("SMTP_PORT") {
+            builder = builder.port(port.parse().expect("SMTP_PORT must be a port number"));
+        }
+        let username = std::env::var("SMTP_USERNAME").ok().filter(|username| !username.is_empty());
+        if let (Some(username), Ok(password)) = (username, std::env::var("SMTP_PASSWORD")) {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        let from = std::env::var("MAIL_FROM")
+            .unwrap_or_else(|_| "Plannify <no-reply@plannify.be>".to_string())
+            .parse()
+            .expect("MAIL_FROM must be a valid mailbox");
+
+        Self {
+            transport: builder.build(),
+            from,
+        }
+    }
+
+    pub async fn send(&self, to: &str, subject: &str, body: &str) -> Result<(), MailError> {
+        let message = Message::builder()
+            .from(self.from.clone())
+            .to(to.parse()?)
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body.to_string())?;
+
+        self.transport.send(message).await?;
+
+        Ok(())
+    }
+}
@@ -1,17 +1,36 @@
 use axum::Router;
 use sqlx::PgPool;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tower_http::{cors::CorsLayer, trace::{DefaultMakeSpan, DefaultOnResponse, TraceLayer}};
-use tracing::info;
+use tracing::{error, info, warn};
 
-use crate::{auth::{routes::public_auth_routes, services::AuthService}, driver::{routes::protected_driver_routes, services::DriverService}, employee::{routes::protected_employees_routes, services::EmployeeService}};
+use crate::{catalogue::{models::PermissionCatalogue, services::CatalogueService}, auth::{routes::public_auth_routes, services::AuthService}, driver::{routes::protected_driver_routes, services::DriverService}, driver_data_export::{routes::protected_driver_data_export_routes, services::DriverDataExportService}, driver_email_verification::{routes::{protected_driver_email_verification_routes, public_driver_email_verification_routes}, services::DriverEmailVerificationService}, driver_erasure::{routes::protected_driver_erasure_routes, services::DriverErasureService}, driver_mail::{routes::protected_driver_mail_routes, services::DriverMailService}, driver_password_reset::{routes::{protected_driver_password_reset_routes, public_driver_password_reset_routes}, services::DriverPasswordResetService}, driver_rest_settings::{routes::protected_driver_rest_settings_routes, services::DriverRestSettingsService}, driver_suspension::{routes::protected_driver_suspension_routes, services::DriverSuspensionService}, driver_workday::{routes::protected_driver_workday_routes, services::DriverWorkdayService}, driver_workday_document::{routes::protected_driver_workday_document_routes, services::DriverWorkdayDocumentService}, employee::{routes::protected_employees_routes, services::EmployeeService}, employee_suspension::{routes::protected_employee_suspension_routes, services::EmployeeSuspensionService}, employee_accreditation_request::{routes::protected_accreditation_request_routes, services::AccreditationRequestService}, employee_delegation::{routes::protected_employee_delegation_routes, services::EmployeeDelegationService}, employee_team::{routes::protected_employee_team_routes, services::EmployeeTeamService}, mailer::Mailer, middleware::MiddlewareState, pending_operation::{routes::protected_pending_operation_routes, services::PendingOperationService}, recertification::{routes::protected_recertification_routes, services::RecertificationService}, storage::storage_from_env};
 
 mod models;
 mod errors;
 mod middleware;
 mod driver;
+mod driver_data_export;
+mod driver_email_verification;
+mod driver_erasure;
+mod driver_mail;
+mod driver_password_reset;
+mod driver_rest_settings;
+mod driver_suspension;
+mod driver_workday;
+mod driver_workday_document;
 mod auth;
 mod employee;
+mod employee_suspension;
+mod employee_accreditation_request;
+mod employee_delegation;
+mod employee_team;
+mod history;
+mod catalogue;
+mod recertification;
+mod pending_operation;
+mod storage;
+mod mailer;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -26,27 +45,205 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .expect("DATABASE_URL must be defined");
     
     let pool = PgPool::connect(&database_url).await?;
+
+    // Compare the permission catalogue with the database
+    let catalogue = PermissionCatalogue::embedded()?;
+    let catalogue_service = CatalogueService::new(pool.clone());
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("sync-permissions") {
+        let dry_run = args.iter().any(|arg| arg == "--dry-run");
+        let report = catalogue_service.sync(&catalogue, dry_run).await?;
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    // apply (default), report or off
+    let catalogue_sync_mode = std::env::var("PERMISSION_CATALOGUE_SYNC")
+        .unwrap_or_else(|_| "apply".to_string());
+    if catalogue_sync_mode != "off" {
+        let report = catalogue_service.sync(&catalogue, catalogue_sync_mode == "report").await?;
+        for change in &report.applied {
+            info!("Permission catalogue v{}: {}", report.version, change);
+        }
+        for difference in &report.reported {
+            warn!("Permission catalogue v{}: {}", report.version, difference);
+        }
+    }
     let jwt_secret = std::env::var("JWT_SECRET")
         .expect("JWT_SECRET must be defined");
     
+    let document_storage = storage_from_env();
     let driver_service = Arc::new(DriverService::new(pool.clone()));
+    let driver_rest_settings_service = Arc::new(DriverRestSettingsService::new(pool.clone()));
+    let driver_workday_service = Arc::new(DriverWorkdayService::new(pool.clone()));
+    let driver_suspension_service = Arc::new(DriverSuspensionService::new(pool.clone()));
+    let driver_email_verification_service = Arc::new(DriverEmailVerificationService::new(pool.clone()));
+    let driver_password_reset_service = Arc::new(DriverPasswordResetService::new(pool.clone()));
+    let driver_mail_service = Arc::new(DriverMailService::new(pool.clone(), Arc::new(Mailer::from_env())));
+    let driver_workday_document_service = Arc::new(DriverWorkdayDocumentService::new(pool.clone(), document_storage.clone()));
+    let driver_erasure_service = Arc::new(DriverErasureService::new(pool.clone(), document_storage.clone()));
+    let driver_data_export_service = Arc::new(DriverDataExportService::new(pool.clone(), document_storage.clone()));
     let auth_service = Arc::new(AuthService::new(pool.clone()));
     let employee_service = Arc::new(EmployeeService::new(pool.clone()));
+    let employee_suspension_service = Arc::new(EmployeeSuspensionService::new(pool.clone()));
+    let accreditation_request_service = Arc::new(AccreditationRequestService::new(pool.clone()));
+    let employee_delegation_service = Arc::new(EmployeeDelegationService::new(pool.clone()));
+    let employee_team_service = Arc::new(EmployeeTeamService::new(pool.clone()));
+    let recertification_service = Arc::new(RecertificationService::new(pool.clone()));
+    let pending_operation_service = Arc::new(PendingOperationService::new(pool.clone()));
+    let middleware_state = MiddlewareState { jwt_secret, pool: pool.clone() };
     
     info!("Database connection established");
+
+    // Close the recertification campaigns once due
+    let due_recertification_service = recertification_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            if let Err(e) = due_recertification_service.close_due_campaigns().await {
+                error!("Failed to close the due recertification campaigns: {}", e);
+            }
+        }
+    });
+
+    // Lift the driver suspensions once past their end
+    let due_driver_suspension_service = driver_suspension_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            if let Err(e) = due_driver_suspension_service.lift_due_suspensions().await {
+                error!("Failed to lift the due driver suspensions: {}", e);
+            }
+        }
+    });
+
+    // Invalidate the driver temporary passwords not changed in time
+    let expired_driver_password_reset_service = driver_password_reset_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            if let Err(e) = expired_driver_password_reset_service.expire_temporary_passwords().await {
+                error!("Failed to expire the driver temporary passwords: {}", e);
+            }
+        }
+    });
+
+    // Erase the personal data of the drivers once their grace period has passed
+    let due_driver_erasure_service = driver_erasure_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            if let Err(e) = due_driver_erasure_service.erase_due_drivers().await {
+                error!("Failed to erase the due drivers: {}", e);
+            }
+        }
+    });
+
+    // Generate the queued driver data exports and delete the expired archives
+    let queued_driver_data_export_service = driver_data_export_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(10));
+        loop {
+            interval.tick().await;
+            if let Err(e) = queued_driver_data_export_service.process_pending_exports().await {
+                error!("Failed to generate the driver data exports: {}", e);
+            }
+            if let Err(e) = queued_driver_data_export_service.expire_exports().await {
+                error!("Failed to expire the driver data exports: {}", e);
+            }
+        }
+    });
+
+    // Deliver the mails queued in the driver mail outbox
+    let outbox_mail_service = driver_mail_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(30));
+        loop {
+            interval.tick().await;
+            if let Err(e) = outbox_mail_service.deliver_pending_mails().await {
+                error!("Failed to deliver the driver mail outbox: {}", e);
+            }
+        }
+    });
     
     // CORS configuration
     let cors = CorsLayer::permissive();
 
     let admin_router = Router::new()
         .merge(public_auth_routes(auth_service.clone()))
+        .merge(public_driver_email_verification_routes(driver_email_verification_service.clone()))
+        .merge(public_driver_password_reset_routes(driver_password_reset_service.clone()))
         .merge(protected_driver_routes(
-            jwt_secret.clone(),
+            middleware_state.clone(),
             driver_service.clone(),
         ))
+        .merge(protected_driver_rest_settings_routes(
+            middleware_state.clone(),
+            driver_rest_settings_service.clone(),
+        ))
+        .merge(protected_driver_workday_routes(
+            middleware_state.clone(),
+            driver_workday_service.clone(),
+        ))
+        .merge(protected_driver_workday_document_routes(
+            middleware_state.clone(),
+            driver_workday_document_service.clone(),
+        ))
+        .merge(protected_driver_mail_routes(
+            middleware_state.clone(),
+            driver_mail_service.clone(),
+        ))
+        .merge(protected_driver_email_verification_routes(
+            middleware_state.clone(),
+            driver_email_verification_service.clone(),
+        ))
+        .merge(protected_driver_password_reset_routes(
+            middleware_state.clone(),
+            driver_password_reset_service.clone(),
+        ))
+        .merge(protected_driver_suspension_routes(
+            middleware_state.clone(),
+            driver_suspension_service.clone(),
+        ))
+        .merge(protected_driver_erasure_routes(
+            middleware_state.clone(),
+            driver_erasure_service.clone(),
+        ))
+        .merge(protected_driver_data_export_routes(
+            middleware_state.clone(),
+            driver_data_export_service.clone(),
+        ))
         .merge(protected_employees_routes(
-            jwt_secret.clone(),
+            middleware_state.clone(),
             employee_service.clone(),
+        ))
+        .merge(protected_employee_suspension_routes(
+            middleware_state.clone(),
+            employee_suspension_service.clone(),
+        ))
+        .merge(protected_accreditation_request_routes(
+            middleware_state.clone(),
+            accreditation_request_service.clone(),
+        ))
+        .merge(protected_employee_delegation_routes(
+            middleware_state.clone(),
+            employee_delegation_service.clone(),
+        ))
+        .merge(protected_employee_team_routes(
+            middleware_state.clone(),
+            employee_team_service.clone(),
+        ))
+        .merge(protected_recertification_routes(
+            middleware_state.clone(),
+            recertification_service.clone(),
+        ))
+        .merge(protected_pending_operation_routes(
+            middleware_state.clone(),
+            pending_operation_service.clone(),
         ));
 
     let app = Router::new()
@@ -5,6 +5,7 @@ use axum::{
 };
 use http::header;
 use jsonwebtoken::{decode, DecodingKey, Validation};
+use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::{
@@ -20,10 +21,11 @@ pub struct AuthState {
 #[derive(Clone)]
 pub struct MiddlewareState {
     pub jwt_secret: String,
+    pub pool: PgPool,
 }
 
 pub async fn auth_middleware(
-    State(MiddlewareState { jwt_secret, .. }): State<MiddlewareState>,
+    State(MiddlewareState { jwt_secret, pool }): State<MiddlewareState>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, AppError> {
@@ -52,6 +54,41 @@ pub async fn auth_middleware(
         return Err(AppError::Validation("JWT token expired".to_string()));
     }
 
+    // check if the employee sessions have been revoked or their permissions changed since
+    // the token was issued, or if a suspension has come into effect
+    let session = sqlx::query!(
+        r#"
+        SELECT
+            e.sessions_revoked_at,
+            e.permissions_updated_at,
+            EXISTS (
+                SELECT 1 FROM employee_suspensions es
+                WHERE es.fk_employee_id = e.pk_employee_id
+                    AND es.lifted_at IS NULL
+                    AND es.start_at <= NOW()
+                    AND (es.end_at IS NULL OR es.end_at > NOW())
+            ) as "suspended!"
+        FROM employees e
+        WHERE e.pk_employee_id = $1
+        "#,
+        claims.sub
+    )
+    .fetch_optional(&pool)
+    .await?
+    .ok_or_else(|| AppError::Validation("Invalid JWT token".to_string()))?;
+
+    if session.sessions_revoked_at.is_some_and(|revoked_at| claims.iat <= revoked_at.timestamp()) {
+        return Err(AppError::Validation("JWT token revoked".to_string()));
+    }
+
+    if session.permissions_updated_at.is_some_and(|updated_at| claims.issued_at_ms() <= updated_at.timestamp_millis()) {
+        return Err(AppError::Validation("JWT token outdated, the permissions have changed".to_string()));
+    }
+
+    if session.suspended {
+        return Err(AppError::Forbidden("Your account is suspended".to_string(), "EMPLOYEE_SUSPENDED".to_string()));
+    }
+
     // create auth state
     let auth_state = AuthState {
         employee_id: claims.sub,
@@ -0,0 +1,34 @@
+/// Quotes a CSV field when it contains a separator, a quote or a line break (RFC 4180)
+pub fn escape_csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Escaped fields joined by commas, terminated by CRLF
+pub fn csv_line<S: AsRef<str>>(fields: impl IntoIterator<Item = S>) -> String {
+    let fields: Vec<String> = fields.into_iter().map(|field| escape_csv_field(field.as_ref())).collect();
+    format!("{}\r\n", fields.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_csv_field() {
+        assert_eq!(escape_csv_field("plain"), "plain");
+        assert_eq!(escape_csv_field(""), "");
+        assert_eq!(escape_csv_field("Doe, Jo"), "\"Doe, Jo\"");
+        assert_eq!(escape_csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(escape_csv_field("two\nlines"), "\"two\nlines\"");
+        assert_eq!(escape_csv_field("carriage\rreturn"), "\"carriage\rreturn\"");
+    }
+
+    #[test]
+    fn test_csv_line() {
+        assert_eq!(csv_line(["a", "b,c", "", "\"d\""]), "a,\"b,c\",,\"\"\"d\"\"\"\r\n");
+    }
+}
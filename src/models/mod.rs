@@ -1 +1,5 @@
-pub mod paginate;
\ No newline at end of file
+pub mod csv;
+pub mod nullable;
+pub mod paginate;
+pub mod path;
+pub mod period;
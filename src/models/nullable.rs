@@ -0,0 +1,7 @@
+use serde::{Deserialize, Deserializer};
+
+/// Tells an explicit `null` apart from a missing field, to use with `#[serde(default, deserialize_with = "nullable")]`:
+/// a missing field is `None` and `null` is `Some(None)`
+pub fn nullable<'de, T: Deserialize<'de>, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Option<T>>, D::Error> {
+    Option::<T>::deserialize(deserializer).map(Some)
+}
@@ -0,0 +1,9 @@
+use uuid::Uuid;
+
+use crate::errors::app_error::AppError;
+
+/// Parses an id taken from the path, `label` names the entity in the error (e.g. "Driver")
+pub fn parse_uuid(id: &str, label: &str) -> Result<Uuid, AppError> {
+    id.parse::<Uuid>()
+        .map_err(|_| AppError::Validation(format!("{} ID is not valid", label)))
+}
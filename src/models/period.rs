@@ -0,0 +1,45 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::errors::app_error::AppError;
+
+// a small tolerance avoids refusing "now" because of clock skew between the client and the server
+fn earliest_allowed() -> DateTime<Utc> {
+    Utc::now() - Duration::minutes(1)
+}
+
+/// Refuses a period starting in the past, `subject` names the period in the error (e.g. "accreditation")
+pub fn validate_start_at(start_at: &DateTime<Utc>, subject: &str) -> Result<(), AppError> {
+    if *start_at < earliest_allowed() {
+        return Err(AppError::Validation(format!("The {} cannot start in the past", subject)));
+    }
+
+    Ok(())
+}
+
+/// Refuses a period ending in the past, with the same tolerance as `validate_start_at`
+pub fn validate_end_at(end_at: &DateTime<Utc>, subject: &str) -> Result<(), AppError> {
+    if *end_at < earliest_allowed() {
+        return Err(AppError::Validation(format!("The {} cannot end in the past", subject)));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_at_tolerates_clock_skew() {
+        assert!(validate_start_at(&(Utc::now() - Duration::seconds(30)), "delegation").is_ok());
+        assert!(validate_start_at(&(Utc::now() + Duration::hours(1)), "delegation").is_ok());
+    }
+
+    #[test]
+    fn start_at_in_the_past_is_refused() {
+        match validate_start_at(&(Utc::now() - Duration::minutes(5)), "delegation") {
+            Err(AppError::Validation(message)) => assert_eq!(message, "The delegation cannot start in the past"),
+            _ => panic!("a start in the past must be refused"),
+        }
+    }
+}
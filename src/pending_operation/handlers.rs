@@ -0,0 +1,117 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::{
+    errors::app_error::AppError, middleware::AuthState, models::{paginate::{PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}, path::parse_uuid}, pending_operation::{models::{ApprovePendingOperationRequest, CreatePendingOperationRequest, GetAllPendingOperationsQuery, PendingOperation, PendingOperationExecution, PendingOperationStatus, RejectPendingOperationRequest}, services::PendingOperationService}
+};
+
+fn validate_request<T: Validate>(req: &T) -> Result<(), AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(format!("The request content is not valid: {}", e)))
+}
+
+// An operation is visible to its requester and to the employees who could review it
+fn ensure_can_view(auth_state: &AuthState, operation: &PendingOperation) -> Result<(), AppError> {
+    if operation.fk_requesting_employee_id != auth_state.employee_id && !auth_state.authorizations.contains(&operation.fk_employee_authorization_type_id) {
+        return Err(AppError::InsufficientPermissions(vec![operation.fk_employee_authorization_type_id]));
+    }
+    Ok(())
+}
+
+pub async fn get_all_operations(
+    Query(filters): Query<GetAllPendingOperationsQuery>,
+    State(operation_service): State<Arc<PendingOperationService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<PaginatedResponse<PendingOperation>>, AppError> {
+    if filters.page == 0 {
+        return Err(AppError::Validation("Page must be greater than 0".to_string()));
+    }
+    if filters.limit == 0 || filters.limit > PAGINATE_MAX_LIMIT {
+        return Err(AppError::Validation(format!("Limit must be between 1 and {}.", PAGINATE_MAX_LIMIT)));
+    }
+    if filters.status.as_deref().is_some_and(|status| status.parse::<PendingOperationStatus>().is_err()) {
+        return Err(AppError::Validation("Status must be PENDING, APPROVED, EXECUTED, REJECTED or CANCELLED".to_string()));
+    }
+
+    let (operations, total) = operation_service.get_all_operations(&auth_state.employee_id, &auth_state.authorizations, &filters).await?;
+
+    Ok(Json(PaginatedResponse {
+        data: operations,
+        pagination: PaginationInfo {
+            page: filters.page,
+            limit: filters.limit,
+            total,
+        },
+    }))
+}
+
+pub async fn get_operation_by_id(
+    Path(operation_id): Path<String>,
+    State(operation_service): State<Arc<PendingOperationService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<PendingOperation>, AppError> {
+    let operation_uuid = parse_uuid(&operation_id, "Pending operation")?;
+    let operation = operation_service.get_operation_by_id(&operation_uuid).await?;
+    ensure_can_view(&auth_state, &operation)?;
+    Ok(Json(operation))
+}
+
+pub async fn create_operation(
+    State(operation_service): State<Arc<PendingOperationService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<CreatePendingOperationRequest>,
+) -> Result<(StatusCode, Json<PendingOperation>), AppError> {
+    validate_request(&create_req)?;
+    let operation = operation_service.create_operation(&create_req.action, create_req.justification.as_deref(), &auth_state.employee_id, &auth_state.authorizations).await?;
+    Ok((StatusCode::CREATED, Json(operation)))
+}
+
+pub async fn approve_operation(
+    Path(operation_id): Path<String>,
+    State(operation_service): State<Arc<PendingOperationService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(approve_req): Json<ApprovePendingOperationRequest>,
+) -> Result<Json<PendingOperationExecution>, AppError> {
+    let operation_uuid = parse_uuid(&operation_id, "Pending operation")?;
+    validate_request(&approve_req)?;
+    let execution = operation_service.approve_operation(&operation_uuid, &approve_req, &auth_state.employee_id, &auth_state.authorizations).await?;
+    Ok(Json(execution))
+}
+
+pub async fn reject_operation(
+    Path(operation_id): Path<String>,
+    State(operation_service): State<Arc<PendingOperationService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(reject_req): Json<RejectPendingOperationRequest>,
+) -> Result<Json<PendingOperation>, AppError> {
+    let operation_uuid = parse_uuid(&operation_id, "Pending operation")?;
+    validate_request(&reject_req)?;
+    let operation = operation_service.reject_operation(&operation_uuid, &reject_req, &auth_state.employee_id, &auth_state.authorizations).await?;
+    Ok(Json(operation))
+}
+
+pub async fn cancel_operation(
+    Path(operation_id): Path<String>,
+    State(operation_service): State<Arc<PendingOperationService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<PendingOperation>, AppError> {
+    let operation_uuid = parse_uuid(&operation_id, "Pending operation")?;
+    let operation = operation_service.cancel_operation(&operation_uuid, &auth_state.employee_id).await?;
+    Ok(Json(operation))
+}
+
+pub async fn execute_operation(
+    Path(operation_id): Path<String>,
+    State(operation_service): State<Arc<PendingOperationService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<PendingOperationExecution>, AppError> {
+    let operation_uuid = parse_uuid(&operation_id, "Pending operation")?;
+    let execution = operation_service.execute_operation(&operation_uuid, &auth_state.employee_id, &auth_state.authorizations).await?;
+    Ok(Json(execution))
+}
@@ -0,0 +1,236 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{employee::models::EntityType, models::paginate::{default_limit, default_page, default_sort_order}};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum PendingOperationStatus {
+    PENDING,
+    /// Approved, waiting for the requester to retrieve the result
+    APPROVED,
+    EXECUTED,
+    REJECTED,
+    CANCELLED,
+}
+
+impl FromStr for PendingOperationStatus {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "PENDING" => Ok(PendingOperationStatus::PENDING),
+            "APPROVED" => Ok(PendingOperationStatus::APPROVED),
+            "EXECUTED" => Ok(PendingOperationStatus::EXECUTED),
+            "REJECTED" => Ok(PendingOperationStatus::REJECTED),
+            "CANCELLED" => Ok(PendingOperationStatus::CANCELLED),
+            _ => Err(()),
+        }
+    }
+}
+
+impl PendingOperationStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PendingOperationStatus::PENDING => "PENDING",
+            PendingOperationStatus::APPROVED => "APPROVED",
+            PendingOperationStatus::EXECUTED => "EXECUTED",
+            PendingOperationStatus::REJECTED => "REJECTED",
+            PendingOperationStatus::CANCELLED => "CANCELLED",
+        }
+    }
+}
+
+/// A sensitive operation that needs the approval of a second employee.
+/// Stored as its `operation_type` and its JSON `payload`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "operation_type", content = "payload")]
+#[allow(non_camel_case_types)]
+pub enum PendingOperationAction {
+    DEACTIVATE_DRIVER {
+        fk_driver_id: Uuid,
+    },
+    GRANT_ADMIN_ACCREDITATION {
+        fk_recipient_employee_id: Uuid,
+        start_at: DateTime<Utc>,
+        end_at: Option<DateTime<Utc>>,
+    },
+    /// Extends the period of an ADMIN accreditation, the fields left empty are not modified
+    UPDATE_ADMIN_ACCREDITATION {
+        fk_recipient_employee_id: Uuid,
+        accreditation_created_at: DateTime<Utc>,
+        start_at: Option<DateTime<Utc>>,
+        end_at: Option<DateTime<Utc>>,
+    },
+    REVEAL_PROFESSIONAL_EMAIL_PASSWORD {
+        fk_employee_id: Uuid,
+    },
+    /// Schedules the erasure, the personal data is anonymized once the grace period has passed
+    ERASE_DRIVER {
+        fk_driver_id: Uuid,
+    },
+}
+
+impl PendingOperationAction {
+    pub fn operation_type(&self) -> &'static str {
+        match self {
+            PendingOperationAction::DEACTIVATE_DRIVER { .. } => "DEACTIVATE_DRIVER",
+            PendingOperationAction::GRANT_ADMIN_ACCREDITATION { .. } => "GRANT_ADMIN_ACCREDITATION",
+            PendingOperationAction::UPDATE_ADMIN_ACCREDITATION { .. } => "UPDATE_ADMIN_ACCREDITATION",
+            PendingOperationAction::REVEAL_PROFESSIONAL_EMAIL_PASSWORD { .. } => "REVEAL_PROFESSIONAL_EMAIL_PASSWORD",
+            PendingOperationAction::ERASE_DRIVER { .. } => "ERASE_DRIVER",
+        }
+    }
+
+    /// Authorization type both the requester and the approver must hold
+    pub fn authorization_type_id(&self) -> i32 {
+        match self {
+            PendingOperationAction::DEACTIVATE_DRIVER { .. } => 4,
+            PendingOperationAction::GRANT_ADMIN_ACCREDITATION { .. } => 35,
+            PendingOperationAction::UPDATE_ADMIN_ACCREDITATION { .. } => 36,
+            PendingOperationAction::REVEAL_PROFESSIONAL_EMAIL_PASSWORD { .. } => 24,
+            PendingOperationAction::ERASE_DRIVER { .. } => 53,
+        }
+    }
+
+    /// The entity the operation applies to
+    pub fn entity(&self) -> (EntityType, Uuid) {
+        match self {
+            PendingOperationAction::DEACTIVATE_DRIVER { fk_driver_id } => (EntityType::DRIVER, *fk_driver_id),
+            PendingOperationAction::GRANT_ADMIN_ACCREDITATION { fk_recipient_employee_id, .. } => (EntityType::EMPLOYEE, *fk_recipient_employee_id),
+            PendingOperationAction::UPDATE_ADMIN_ACCREDITATION { fk_recipient_employee_id, .. } => (EntityType::EMPLOYEE, *fk_recipient_employee_id),
+            PendingOperationAction::REVEAL_PROFESSIONAL_EMAIL_PASSWORD { fk_employee_id } => (EntityType::EMPLOYEE, *fk_employee_id),
+            PendingOperationAction::ERASE_DRIVER { fk_driver_id } => (EntityType::DRIVER, *fk_driver_id),
+        }
+    }
+
+    /// Operations returning data are executed when the requester retrieves the result, the others on approval
+    pub fn is_executed_on_approval(&self) -> bool {
+        !matches!(self, PendingOperationAction::REVEAL_PROFESSIONAL_EMAIL_PASSWORD { .. })
+    }
+
+    pub fn split(&self) -> (&'static str, Value) {
+        let payload = serde_json::to_value(self)
+            .ok()
+            .and_then(|mut value| value.get_mut("payload").map(Value::take))
+            .unwrap_or(Value::Null);
+        (self.operation_type(), payload)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct PendingOperation {
+    pub pk_pending_operation_id: Uuid,
+    /// DEACTIVATE_DRIVER, GRANT_ADMIN_ACCREDITATION, UPDATE_ADMIN_ACCREDITATION, REVEAL_PROFESSIONAL_EMAIL_PASSWORD or ERASE_DRIVER
+    pub operation_type: String,
+    pub payload: Value,
+    /// Authorization type both the requester and the approver must hold
+    pub fk_employee_authorization_type_id: i32,
+    pub fk_requesting_employee_id: Uuid,
+    pub justification: Option<String>,
+    /// PENDING, APPROVED, EXECUTED, REJECTED or CANCELLED
+    pub status: String,
+    pub fk_reviewing_employee_id: Option<Uuid>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+    pub review_comment: Option<String>,
+    pub executed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl PendingOperation {
+    pub fn action(&self) -> Result<PendingOperationAction, serde_json::Error> {
+        serde_json::from_value(serde_json::json!({
+            "operation_type": self.operation_type,
+            "payload": self.payload,
+        }))
+    }
+}
+
+/// An executed operation with the data it returned, if any
+#[derive(Debug, Serialize)]
+pub struct PendingOperationExecution {
+    #[serde(flatten)]
+    pub operation: PendingOperation,
+    pub result: Option<Value>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreatePendingOperationRequest {
+    #[serde(flatten)]
+    pub action: PendingOperationAction,
+    #[validate(length(min = 10, max = 1000, message = "Justification must contain between 10 and 1000 characters"))]
+    pub justification: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct ApprovePendingOperationRequest {
+    #[validate(length(min = 1, max = 1000, message = "Review comment cannot be empty and cannot be longer than 1000 characters"))]
+    pub review_comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RejectPendingOperationRequest {
+    #[validate(length(min = 1, max = 1000, message = "Review comment is required and cannot be longer than 1000 characters"))]
+    pub review_comment: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetAllPendingOperationsQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub operation_type: Option<String>,
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pending_operation_action_round_trip() {
+        let action = PendingOperationAction::DEACTIVATE_DRIVER { fk_driver_id: Uuid::nil() };
+        let (operation_type, payload) = action.split();
+        assert_eq!(operation_type, "DEACTIVATE_DRIVER");
+        assert_eq!(payload, serde_json::json!({ "fk_driver_id": Uuid::nil() }));
+
+        let operation = PendingOperation {
+            pk_pending_operation_id: Uuid::nil(),
+            operation_type: operation_type.to_string(),
+            payload,
+            fk_employee_authorization_type_id: action.authorization_type_id(),
+            fk_requesting_employee_id: Uuid::nil(),
+            justification: None,
+            status: PendingOperationStatus::PENDING.as_str().to_string(),
+            fk_reviewing_employee_id: None,
+            reviewed_at: None,
+            review_comment: None,
+            executed_at: None,
+            created_at: Utc::now(),
+        };
+        assert_eq!(operation.action().unwrap(), action);
+    }
+
+    #[test]
+    fn test_create_pending_operation_request_deserialization() {
+        let request: CreatePendingOperationRequest = serde_json::from_str(r#"{
+            "operation_type": "REVEAL_PROFESSIONAL_EMAIL_PASSWORD",
+            "payload": { "fk_employee_id": "00000000-0000-0000-0000-000000000000" },
+            "justification": "Mailbox migration"
+        }"#).unwrap();
+        assert_eq!(request.action, PendingOperationAction::REVEAL_PROFESSIONAL_EMAIL_PASSWORD { fk_employee_id: Uuid::nil() });
+        assert!(!request.action.is_executed_on_approval());
+
+        let unknown = serde_json::from_str::<CreatePendingOperationRequest>(r#"{ "operation_type": "DELETE_EVERYTHING", "payload": {} }"#);
+        assert!(unknown.is_err());
+    }
+}
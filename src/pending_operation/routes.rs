@@ -0,0 +1,27 @@
+use axum::{
+    middleware::from_fn_with_state, routing::{get, post}, Router
+};
+use crate::{
+    middleware::{auth_middleware, MiddlewareState}, pending_operation::{handlers::{approve_operation, cancel_operation, create_operation, execute_operation, get_all_operations, get_operation_by_id, reject_operation}, services::PendingOperationService}
+};
+use std::sync::Arc;
+
+// The authorization type needed depends on the operation, it is checked by the service
+pub fn protected_pending_operation_routes(
+    middleware_state: MiddlewareState,
+    operation_service: Arc<PendingOperationService>,
+) -> Router {
+    Router::new()
+        .route("/pending-operations", get(get_all_operations))
+        .route("/pending-operations", post(create_operation))
+        .route("/pending-operations/{id}", get(get_operation_by_id))
+        .route("/pending-operations/{id}/approve", post(approve_operation))
+        .route("/pending-operations/{id}/reject", post(reject_operation))
+        .route("/pending-operations/{id}/cancel", post(cancel_operation))
+        .route("/pending-operations/{id}/execute", post(execute_operation))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(operation_service.clone())
+}
@@ -0,0 +1,489 @@
+use chrono::Utc;
+use serde_json::{json, Value};
+use sqlx::{PgConnection, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    auth::services::invalidate_employee_permissions, driver::services::DriverService, driver_erasure::services::{ensure_driver_erasable, schedule_driver_erasure}, employee::{models::EmployeeAccreditationUpdate, services::{EmployeeService, ADMIN_LEVEL_LABEL}}, errors::app_error::AppError, history::{models::NewActionHistory, services::record_action}, pending_operation::models::{ApprovePendingOperationRequest, GetAllPendingOperationsQuery, PendingOperation, PendingOperationAction, PendingOperationExecution, PendingOperationStatus, RejectPendingOperationRequest}
+};
+
+const PENDING_OPERATION_COLUMNS: &str = "pk_pending_operation_id, operation_type, payload, fk_employee_authorization_type_id, fk_requesting_employee_id, justification, status, fk_reviewing_employee_id, reviewed_at, review_comment, executed_at, created_at";
+
+/// Store a sensitive operation until a second employee approves it.
+///
+/// The same operation cannot be waiting twice. The caller is responsible for checking that the
+/// requester holds the authorization type of the operation and that the operation is valid.
+pub async fn create_pending_operation(pool: &PgPool, action: &PendingOperationAction, requester_id: &Uuid, justification: Option<&str>) -> Result<PendingOperation, AppError> {
+    let (operation_type, payload) = action.split();
+    let (entity_type, entity_id) = action.entity();
+
+    let mut tx = pool.begin().await?;
+
+    // the partial unique index refuses a second waiting operation, even when requested concurrently
+    let operation = sqlx::query_as::<_, PendingOperation>(&format!(
+        r#"
+        INSERT INTO pending_operations (operation_type, payload, fk_employee_authorization_type_id, fk_requesting_employee_id, justification)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (operation_type, payload) WHERE status IN ('PENDING', 'APPROVED') DO NOTHING
+        RETURNING {}
+        "#,
+        PENDING_OPERATION_COLUMNS
+    ))
+    .bind(operation_type)
+    .bind(payload)
+    .bind(action.authorization_type_id())
+    .bind(requester_id)
+    .bind(justification)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::Conflict("This operation is already waiting for an approval".to_string(), "PENDING_OPERATION_ALREADY_EXISTS".to_string()))?;
+
+    record_action(&mut *tx, &NewActionHistory {
+        employee_id: *requester_id,
+        authorization_type_id: operation.fk_employee_authorization_type_id,
+        entity_id: Some(entity_id),
+        entity_type,
+        description: json!({
+            "action": "CREATE_PENDING_OPERATION",
+            "pending_operation_id": operation.pk_pending_operation_id,
+            "operation_type": operation.operation_type,
+            "payload": operation.payload,
+            "justification": operation.justification,
+        }),
+    }).await?;
+
+    tx.commit().await?;
+
+    Ok(operation)
+}
+
+pub struct PendingOperationService {
+    pool: PgPool,
+    driver_service: DriverService,
+    employee_service: EmployeeService,
+}
+
+impl PendingOperationService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            driver_service: DriverService::new(pool.clone()),
+            employee_service: EmployeeService::new(pool.clone()),
+            pool,
+        }
+    }
+
+    // Operations visible to an employee: the ones they requested and the ones they could approve
+    pub async fn get_all_operations(&self, viewer_id: &Uuid, viewer_authorizations: &[i32], filters: &GetAllPendingOperationsQuery) -> Result<(Vec<PendingOperation>, u64), AppError> {
+        let offset = (filters.page - 1) * filters.limit;
+
+        let where_clause = r#"
+            WHERE (fk_requesting_employee_id = $1 OR fk_employee_authorization_type_id = ANY($2))
+            AND ($3::VARCHAR IS NULL OR status = $3)
+            AND ($4::VARCHAR IS NULL OR operation_type = $4)
+        "#;
+
+        let total_count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) as count FROM pending_operations {}", where_clause))
+            .bind(viewer_id)
+            .bind(viewer_authorizations)
+            .bind(&filters.status)
+            .bind(&filters.operation_type)
+            .fetch_one(&self.pool)
+            .await?;
+
+        // Determine sort order (default to ASC for chronological order)
+        let order_direction = match filters.sort_order.to_lowercase().as_str() {
+            "desc" => "DESC",
+            _ => "ASC",
+        };
+
+        let operations = sqlx::query_as::<_, PendingOperation>(&format!(
+            "SELECT {} FROM pending_operations {} ORDER BY created_at {} LIMIT $5 OFFSET $6",
+            PENDING_OPERATION_COLUMNS,
+            where_clause,
+            order_direction
+        ))
+        .bind(viewer_id)
+        .bind(viewer_authorizations)
+        .bind(&filters.status)
+        .bind(&filters.operation_type)
+        .bind(filters.limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((operations, total_count as u64))
+    }
+
+    pub async fn get_operation_by_id(&self, operation_id: &Uuid) -> Result<PendingOperation, AppError> {
+        sqlx::query_as::<_, PendingOperation>(&format!("SELECT {} FROM pending_operations WHERE pk_pending_operation_id = $1", PENDING_OPERATION_COLUMNS))
+            .bind(operation_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Pending operation not found".to_string()))
+    }
+
+    pub async fn create_operation(&self, action: &PendingOperationAction, justification: Option<&str>, requester_id: &Uuid, requester_authorizations: &[i32]) -> Result<PendingOperation, AppError> {
+        if !requester_authorizations.contains(&action.authorization_type_id()) {
+            return Err(AppError::InsufficientPermissions(vec![action.authorization_type_id()]));
+        }
+
+        self.validate_action(action).await?;
+
+        create_pending_operation(&self.pool, action, requester_id, justification).await
+    }
+
+    // A second employee holding the same authorization approves the operation.
+    // Operations with side effects are executed right away on behalf of the requester.
+    pub async fn approve_operation(&self, operation_id: &Uuid, approve_req: &ApprovePendingOperationRequest, approver_id: &Uuid, approver_authorizations: &[i32]) -> Result<PendingOperationExecution, AppError> {
+        let existing = self.get_operation_in_status(operation_id, PendingOperationStatus::PENDING).await?;
+        let action = self.ensure_can_review(&existing, approver_id, approver_authorizations)?;
+
+        self.validate_action(&action).await?;
+
+        let status = if action.is_executed_on_approval() { PendingOperationStatus::EXECUTED } else { PendingOperationStatus::APPROVED };
+        let (entity_type, entity_id) = action.entity();
+
+        let mut tx = self.pool.begin().await?;
+
+        let operation = sqlx::query_as::<_, PendingOperation>(&format!(
+            r#"
+            UPDATE pending_operations SET
+                status = $1,
+                fk_reviewing_employee_id = $2,
+                reviewed_at = NOW(),
+                review_comment = $3,
+                executed_at = CASE WHEN $1 = 'EXECUTED' THEN NOW() END
+            WHERE pk_pending_operation_id = $4 AND status = 'PENDING'
+            RETURNING {}
+            "#,
+            PENDING_OPERATION_COLUMNS
+        ))
+        .bind(status.as_str())
+        .bind(approver_id)
+        .bind(&approve_req.review_comment)
+        .bind(operation_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("The operation is not pending anymore".to_string(), "PENDING_OPERATION_NOT_PENDING".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *approver_id,
+            authorization_type_id: operation.fk_employee_authorization_type_id,
+            entity_id: Some(entity_id),
+            entity_type,
+            description: json!({
+                "action": "APPROVE_PENDING_OPERATION",
+                "pending_operation_id": operation.pk_pending_operation_id,
+                "operation_type": operation.operation_type,
+                "requesting_employee_id": operation.fk_requesting_employee_id,
+                "review_comment": operation.review_comment,
+            }),
+        }).await?;
+
+        let result = if action.is_executed_on_approval() {
+            Self::execute_action(&mut tx, &operation, &action).await?
+        } else {
+            None
+        };
+
+        tx.commit().await?;
+
+        Ok(PendingOperationExecution { operation, result })
+    }
+
+    pub async fn reject_operation(&self, operation_id: &Uuid, reject_req: &RejectPendingOperationRequest, reviewer_id: &Uuid, reviewer_authorizations: &[i32]) -> Result<PendingOperation, AppError> {
+        let existing = self.get_operation_in_status(operation_id, PendingOperationStatus::PENDING).await?;
+        let action = self.ensure_can_review(&existing, reviewer_id, reviewer_authorizations)?;
+        let (entity_type, entity_id) = action.entity();
+
+        let mut tx = self.pool.begin().await?;
+
+        let operation = sqlx::query_as::<_, PendingOperation>(&format!(
+            r#"
+            UPDATE pending_operations SET
+                status = 'REJECTED',
+                fk_reviewing_employee_id = $1,
+                reviewed_at = NOW(),
+                review_comment = $2
+            WHERE pk_pending_operation_id = $3 AND status = 'PENDING'
+            RETURNING {}
+            "#,
+            PENDING_OPERATION_COLUMNS
+        ))
+        .bind(reviewer_id)
+        .bind(&reject_req.review_comment)
+        .bind(operation_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("The operation is not pending anymore".to_string(), "PENDING_OPERATION_NOT_PENDING".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *reviewer_id,
+            authorization_type_id: operation.fk_employee_authorization_type_id,
+            entity_id: Some(entity_id),
+            entity_type,
+            description: json!({
+                "action": "REJECT_PENDING_OPERATION",
+                "pending_operation_id": operation.pk_pending_operation_id,
+                "operation_type": operation.operation_type,
+                "requesting_employee_id": operation.fk_requesting_employee_id,
+                "review_comment": operation.review_comment,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(operation)
+    }
+
+    // Only the requester can cancel their operation, as long as it has not been executed
+    pub async fn cancel_operation(&self, operation_id: &Uuid, requester_id: &Uuid) -> Result<PendingOperation, AppError> {
+        let existing = self.get_operation_by_id(operation_id).await?;
+        if existing.fk_requesting_employee_id != *requester_id {
+            return Err(AppError::Forbidden("Only the requester can cancel a pending operation".to_string(), "PENDING_OPERATION_NOT_REQUESTER".to_string()));
+        }
+
+        let action = existing.action()
+            .map_err(|e| AppError::Internal(format!("Invalid pending operation payload: {}", e)))?;
+        let (entity_type, entity_id) = action.entity();
+
+        let mut tx = self.pool.begin().await?;
+
+        let operation = sqlx::query_as::<_, PendingOperation>(&format!(
+            "UPDATE pending_operations SET status = 'CANCELLED' WHERE pk_pending_operation_id = $1 AND status IN ('PENDING', 'APPROVED') RETURNING {}",
+            PENDING_OPERATION_COLUMNS
+        ))
+        .bind(operation_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("The operation cannot be cancelled anymore".to_string(), "PENDING_OPERATION_NOT_PENDING".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *requester_id,
+            authorization_type_id: operation.fk_employee_authorization_type_id,
+            entity_id: Some(entity_id),
+            entity_type,
+            description: json!({
+                "action": "CANCEL_PENDING_OPERATION",
+                "pending_operation_id": operation.pk_pending_operation_id,
+                "operation_type": operation.operation_type,
+                "previous_status": existing.status,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(operation)
+    }
+
+    // The requester retrieves the result of an approved operation, it can only be retrieved once and
+    // only while the requester still holds the authorization type of the operation
+    pub async fn execute_operation(&self, operation_id: &Uuid, requester_id: &Uuid, requester_authorizations: &[i32]) -> Result<PendingOperationExecution, AppError> {
+        let existing = self.get_operation_in_status(operation_id, PendingOperationStatus::APPROVED).await?;
+        if existing.fk_requesting_employee_id != *requester_id {
+            return Err(AppError::Forbidden("Only the requester can retrieve the result of a pending operation".to_string(), "PENDING_OPERATION_NOT_REQUESTER".to_string()));
+        }
+        if !requester_authorizations.contains(&existing.fk_employee_authorization_type_id) {
+            return Err(AppError::InsufficientPermissions(vec![existing.fk_employee_authorization_type_id]));
+        }
+        let action = existing.action()
+            .map_err(|e| AppError::Internal(format!("Invalid pending operation payload: {}", e)))?;
+
+        let mut tx = self.pool.begin().await?;
+
+        let operation = sqlx::query_as::<_, PendingOperation>(&format!(
+            "UPDATE pending_operations SET status = 'EXECUTED', executed_at = NOW() WHERE pk_pending_operation_id = $1 AND status = 'APPROVED' RETURNING {}",
+            PENDING_OPERATION_COLUMNS
+        ))
+        .bind(operation_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("The operation has already been executed".to_string(), "PENDING_OPERATION_NOT_APPROVED".to_string()))?;
+
+        let result = Self::execute_action(&mut tx, &operation, &action).await?;
+
+        tx.commit().await?;
+
+        Ok(PendingOperationExecution { operation, result })
+    }
+
+    async fn get_operation_in_status(&self, operation_id: &Uuid, status: PendingOperationStatus) -> Result<PendingOperation, AppError> {
+        let operation = self.get_operation_by_id(operation_id).await?;
+        if operation.status != status.as_str() {
+            return match status {
+                PendingOperationStatus::APPROVED => Err(AppError::Conflict("The operation is not waiting to be executed".to_string(), "PENDING_OPERATION_NOT_APPROVED".to_string())),
+                _ => Err(AppError::Conflict("The operation is not pending anymore".to_string(), "PENDING_OPERATION_NOT_PENDING".to_string())),
+            };
+        }
+        Ok(operation)
+    }
+
+    // The reviewer must be another employee holding the authorization type of the operation
+    fn ensure_can_review(&self, operation: &PendingOperation, reviewer_id: &Uuid, reviewer_authorizations: &[i32]) -> Result<PendingOperationAction, AppError> {
+        if operation.fk_requesting_employee_id == *reviewer_id {
+            return Err(AppError::Forbidden("An employee cannot review their own operation".to_string(), "PENDING_OPERATION_SELF_REVIEW".to_string()));
+        }
+        if !reviewer_authorizations.contains(&operation.fk_employee_authorization_type_id) {
+            return Err(AppError::InsufficientPermissions(vec![operation.fk_employee_authorization_type_id]));
+        }
+
+        let action = operation.action()
+            .map_err(|e| AppError::Internal(format!("Invalid pending operation payload: {}", e)))?;
+        if let PendingOperationAction::GRANT_ADMIN_ACCREDITATION { fk_recipient_employee_id, .. } | PendingOperationAction::UPDATE_ADMIN_ACCREDITATION { fk_recipient_employee_id, .. } = &action {
+            if fk_recipient_employee_id == reviewer_id {
+                return Err(AppError::Forbidden("An employee cannot approve their own accreditation".to_string(), "PENDING_OPERATION_SELF_REVIEW".to_string()));
+            }
+        }
+
+        Ok(action)
+    }
+
+    // Checked when the operation is requested and again when it is approved
+    async fn validate_action(&self, action: &PendingOperationAction) -> Result<(), AppError> {
+        match action {
+            PendingOperationAction::DEACTIVATE_DRIVER { fk_driver_id } => {
+                let driver = self.driver_service.get_driver_by_id(fk_driver_id).await?;
+                if driver.deactivated_at.is_some() {
+                    return Err(AppError::Conflict("Driver has already been deactivated".to_string(), "DRIVER_DEACTIVATED".to_string()));
+                }
+            }
+            PendingOperationAction::GRANT_ADMIN_ACCREDITATION { fk_recipient_employee_id, start_at, end_at } => {
+                let level = self.employee_service.get_admin_level().await?;
+                // a grant approved after its requested start begins at the approval
+                let start_at = (*start_at).max(Utc::now());
+                self.employee_service.validate_accreditation_grant(fk_recipient_employee_id, &level, &start_at, end_at.as_ref()).await?;
+            }
+            PendingOperationAction::UPDATE_ADMIN_ACCREDITATION { fk_recipient_employee_id, accreditation_created_at, start_at, end_at } => {
+                let existing = self.employee_service.get_employee_accreditation(fk_recipient_employee_id, accreditation_created_at).await?;
+                let update = EmployeeAccreditationUpdate { start_at: *start_at, end_at: *end_at };
+                self.employee_service.validate_accreditation_update(&existing, &update).await?;
+            }
+            PendingOperationAction::REVEAL_PROFESSIONAL_EMAIL_PASSWORD { fk_employee_id } => {
+                self.employee_service.get_employee_by_id(&fk_employee_id.to_string()).await?;
+            }
+            PendingOperationAction::ERASE_DRIVER { fk_driver_id } => {
+                self.driver_service.get_driver_by_id(fk_driver_id).await?;
+                ensure_driver_erasable(&self.pool, fk_driver_id).await?;
+            }
+        }
+        Ok(())
+    }
+
+    // Run the operation on behalf of the requester, the history entry names both employees
+    async fn execute_action(conn: &mut PgConnection, operation: &PendingOperation, action: &PendingOperationAction) -> Result<Option<Value>, AppError> {
+        let (entity_type, entity_id) = action.entity();
+
+        let (mut description, result) = match action {
+            PendingOperationAction::DEACTIVATE_DRIVER { fk_driver_id } => {
+                let deactivated = sqlx::query!(
+                    "UPDATE \"drivers\" SET deactivated_at = NOW() WHERE pk_driver_id = $1 AND deactivated_at IS NULL",
+                    fk_driver_id
+                )
+                .execute(&mut *conn)
+                .await?;
+                if deactivated.rows_affected() == 0 {
+                    return Err(AppError::Conflict("Driver has already been deactivated".to_string(), "DRIVER_DEACTIVATED".to_string()));
+                }
+
+                (json!({ "action": "DEACTIVATE_DRIVER" }), None)
+            }
+            PendingOperationAction::GRANT_ADMIN_ACCREDITATION { fk_recipient_employee_id, start_at, end_at } => {
+                let start_at = (*start_at).max(Utc::now());
+                let level = sqlx::query!(
+                    "SELECT pk_employee_level_id, level_label FROM employee_levels WHERE level_label = $1 AND deleted_at IS NULL",
+                    ADMIN_LEVEL_LABEL
+                )
+                .fetch_one(&mut *conn)
+                .await?;
+
+                let accreditation_created_at = sqlx::query_scalar!(
+                    r#"
+                    INSERT INTO employee_accreditation_authorizations (fk_recipient_employee_id, fk_employee_level_id, fk_authorizing_employee_id, start_at, end_at)
+                    VALUES ($1, $2, $3, $4, $5)
+                    RETURNING created_at
+                    "#,
+                    fk_recipient_employee_id,
+                    level.pk_employee_level_id,
+                    operation.fk_requesting_employee_id,
+                    start_at,
+                    *end_at
+                )
+                .fetch_one(&mut *conn)
+                .await?;
+
+                invalidate_employee_permissions(&mut *conn, &[*fk_recipient_employee_id]).await?;
+
+                (json!({
+                    "action": "GRANT_ACCREDITATION",
+                    "accreditation_created_at": accreditation_created_at,
+                    "employee_level_id": level.pk_employee_level_id,
+                    "employee_level_label": level.level_label,
+                    "start_at": start_at,
+                    "end_at": end_at,
+                }), None)
+            }
+            PendingOperationAction::UPDATE_ADMIN_ACCREDITATION { fk_recipient_employee_id, accreditation_created_at, start_at, end_at } => {
+                let updated = sqlx::query!(
+                    r#"
+                    UPDATE employee_accreditation_authorizations
+                    SET start_at = COALESCE($1, start_at), end_at = COALESCE($2, end_at)
+                    WHERE fk_recipient_employee_id = $3 AND created_at = $4 AND revoked_at IS NULL
+                    "#,
+                    *start_at,
+                    *end_at,
+                    fk_recipient_employee_id,
+                    accreditation_created_at
+                )
+                .execute(&mut *conn)
+                .await?;
+                if updated.rows_affected() == 0 {
+                    return Err(AppError::Conflict("A revoked accreditation cannot be modified".to_string(), "ACCREDITATION_REVOKED".to_string()));
+                }
+
+                invalidate_employee_permissions(&mut *conn, &[*fk_recipient_employee_id]).await?;
+
+                (json!({
+                    "action": "UPDATE_ACCREDITATION",
+                    "accreditation_created_at": accreditation_created_at,
+                    "start_at": start_at,
+                    "end_at": end_at,
+                }), None)
+            }
+            PendingOperationAction::REVEAL_PROFESSIONAL_EMAIL_PASSWORD { fk_employee_id } => {
+                let professional_email_password = sqlx::query_scalar!(
+                    "SELECT professional_email_password FROM employees WHERE pk_employee_id = $1",
+                    fk_employee_id
+                )
+                .fetch_one(&mut *conn)
+                .await?;
+
+                (
+                    json!({ "action": "REVEAL_PROFESSIONAL_EMAIL_PASSWORD" }),
+                    Some(json!({ "professional_email_password": professional_email_password })),
+                )
+            }
+            PendingOperationAction::ERASE_DRIVER { fk_driver_id } => {
+                let erasure = schedule_driver_erasure(&mut *conn, operation, fk_driver_id).await?;
+
+                (json!({
+                    "action": "SCHEDULE_DRIVER_ERASURE",
+                    "erasure_id": erasure.pk_driver_erasure_id,
+                    "scheduled_at": erasure.scheduled_at,
+                }), None)
+            }
+        };
+
+        description["pending_operation_id"] = json!(operation.pk_pending_operation_id);
+        description["approving_employee_id"] = json!(operation.fk_reviewing_employee_id);
+
+        record_action(&mut *conn, &NewActionHistory {
+            employee_id: operation.fk_requesting_employee_id,
+            authorization_type_id: operation.fk_employee_authorization_type_id,
+            entity_id: Some(entity_id),
+            entity_type,
+            description,
+        }).await?;
+
+        Ok(result)
+    }
+}
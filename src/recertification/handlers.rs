@@ -0,0 +1,145 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension,
+    Json,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    errors::app_error::AppError, middleware::AuthState, models::{paginate::{PaginatedResponse, PaginationInfo, PAGINATE_MAX_LIMIT}, path::parse_uuid}, recertification::{models::{AssignRecertificationReviewerRequest, CreateRecertificationCampaignRequest, GetAllRecertificationCampaignsQuery, GetAllRecertificationItemsQuery, RecertificationCampaignItem, RecertificationCampaignWithProgress, RecertificationDecision, RecertificationDecisionRequest}, services::RecertificationService}
+};
+
+fn validate_request<T: Validate>(req: &T) -> Result<(), AppError> {
+    req.validate()
+        .map_err(|e| AppError::Validation(format!("The request content is not valid: {}", e)))
+}
+
+fn validate_pagination(page: u32, limit: u32) -> Result<(), AppError> {
+    if page == 0 {
+        return Err(AppError::Validation("Page must be greater than 0".to_string()));
+    }
+    if limit == 0 || limit > PAGINATE_MAX_LIMIT {
+        return Err(AppError::Validation(format!("Limit must be between 1 and {}.", PAGINATE_MAX_LIMIT)));
+    }
+    Ok(())
+}
+
+async fn paginated_items(
+    campaign_id: Option<&Uuid>,
+    filters: &GetAllRecertificationItemsQuery,
+    recertification_service: &RecertificationService,
+) -> Result<Json<PaginatedResponse<RecertificationCampaignItem>>, AppError> {
+    validate_pagination(filters.page, filters.limit)?;
+    if filters.decision.as_deref().is_some_and(|decision| decision != "PENDING" && decision.parse::<RecertificationDecision>().is_err()) {
+        return Err(AppError::Validation("Decision must be PENDING, KEEP, REVOKE, EXPIRED or ENDED".to_string()));
+    }
+
+    let (items, total) = recertification_service.get_all_items(campaign_id, filters).await?;
+
+    Ok(Json(PaginatedResponse {
+        data: items,
+        pagination: PaginationInfo {
+            page: filters.page,
+            limit: filters.limit,
+            total,
+        },
+    }))
+}
+
+pub async fn get_all_campaigns(
+    Query(filters): Query<GetAllRecertificationCampaignsQuery>,
+    State(recertification_service): State<Arc<RecertificationService>>,
+) -> Result<Json<PaginatedResponse<RecertificationCampaignWithProgress>>, AppError> {
+    validate_pagination(filters.page, filters.limit)?;
+
+    let (campaigns, total) = recertification_service.get_all_campaigns(&filters).await?;
+
+    Ok(Json(PaginatedResponse {
+        data: campaigns,
+        pagination: PaginationInfo {
+            page: filters.page,
+            limit: filters.limit,
+            total,
+        },
+    }))
+}
+
+pub async fn get_campaign_by_id(
+    Path(campaign_id): Path<String>,
+    State(recertification_service): State<Arc<RecertificationService>>,
+) -> Result<Json<RecertificationCampaignWithProgress>, AppError> {
+    let campaign_uuid = parse_uuid(&campaign_id, "Recertification campaign")?;
+    let campaign = recertification_service.get_campaign_with_progress(&campaign_uuid).await?;
+    Ok(Json(campaign))
+}
+
+pub async fn create_campaign(
+    State(recertification_service): State<Arc<RecertificationService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(create_req): Json<CreateRecertificationCampaignRequest>,
+) -> Result<(StatusCode, Json<RecertificationCampaignWithProgress>), AppError> {
+    validate_request(&create_req)?;
+    let campaign = recertification_service.create_campaign(&create_req, &auth_state.employee_id).await?;
+    Ok((StatusCode::CREATED, Json(campaign)))
+}
+
+pub async fn close_campaign(
+    Path(campaign_id): Path<String>,
+    State(recertification_service): State<Arc<RecertificationService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<RecertificationCampaignWithProgress>, AppError> {
+    let campaign_uuid = parse_uuid(&campaign_id, "Recertification campaign")?;
+    let campaign = recertification_service.close_campaign(&campaign_uuid, Some(&auth_state.employee_id)).await?;
+    Ok(Json(campaign))
+}
+
+pub async fn get_campaign_items(
+    Path(campaign_id): Path<String>,
+    Query(filters): Query<GetAllRecertificationItemsQuery>,
+    State(recertification_service): State<Arc<RecertificationService>>,
+) -> Result<Json<PaginatedResponse<RecertificationCampaignItem>>, AppError> {
+    let campaign_uuid = parse_uuid(&campaign_id, "Recertification campaign")?;
+    recertification_service.get_campaign_by_id(&campaign_uuid).await?;
+    paginated_items(Some(&campaign_uuid), &filters, &recertification_service).await
+}
+
+/// Pending reviews assigned to the caller across the open campaigns
+pub async fn get_my_reviews(
+    Query(mut filters): Query<GetAllRecertificationItemsQuery>,
+    State(recertification_service): State<Arc<RecertificationService>>,
+    Extension(auth_state): Extension<AuthState>,
+) -> Result<Json<PaginatedResponse<RecertificationCampaignItem>>, AppError> {
+    filters.fk_reviewer_employee_id = Some(auth_state.employee_id);
+    if filters.decision.is_none() {
+        filters.decision = Some("PENDING".to_string());
+    }
+    paginated_items(None, &filters, &recertification_service).await
+}
+
+pub async fn decide_item(
+    Path((campaign_id, item_id)): Path<(String, String)>,
+    State(recertification_service): State<Arc<RecertificationService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(decision_req): Json<RecertificationDecisionRequest>,
+) -> Result<Json<RecertificationCampaignItem>, AppError> {
+    let campaign_uuid = parse_uuid(&campaign_id, "Recertification campaign")?;
+    let item_uuid = parse_uuid(&item_id, "Recertification campaign item")?;
+    validate_request(&decision_req)?;
+    let item = recertification_service.decide(&campaign_uuid, &item_uuid, &decision_req, &auth_state.employee_id).await?;
+    Ok(Json(item))
+}
+
+pub async fn assign_reviewer(
+    Path((campaign_id, item_id)): Path<(String, String)>,
+    State(recertification_service): State<Arc<RecertificationService>>,
+    Extension(auth_state): Extension<AuthState>,
+    Json(assign_req): Json<AssignRecertificationReviewerRequest>,
+) -> Result<Json<RecertificationCampaignItem>, AppError> {
+    let campaign_uuid = parse_uuid(&campaign_id, "Recertification campaign")?;
+    let item_uuid = parse_uuid(&item_id, "Recertification campaign item")?;
+    let item = recertification_service.assign_reviewer(&campaign_uuid, &item_uuid, &assign_req, &auth_state.employee_id).await?;
+    Ok(Json(item))
+}
@@ -0,0 +1,143 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::paginate::{default_limit, default_page, default_sort_order};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum RecertificationDecision {
+    /// The reviewer confirmed the accreditation
+    KEEP,
+    /// The reviewer revoked the accreditation
+    REVOKE,
+    /// Not reviewed before the campaign close, the accreditation was ended
+    EXPIRED,
+    /// The accreditation ended on its own before being reviewed
+    ENDED,
+}
+
+impl FromStr for RecertificationDecision {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "KEEP" => Ok(RecertificationDecision::KEEP),
+            "REVOKE" => Ok(RecertificationDecision::REVOKE),
+            "EXPIRED" => Ok(RecertificationDecision::EXPIRED),
+            "ENDED" => Ok(RecertificationDecision::ENDED),
+            _ => Err(()),
+        }
+    }
+}
+
+impl RecertificationDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecertificationDecision::KEEP => "KEEP",
+            RecertificationDecision::REVOKE => "REVOKE",
+            RecertificationDecision::EXPIRED => "EXPIRED",
+            RecertificationDecision::ENDED => "ENDED",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RecertificationCampaign {
+    pub pk_recertification_campaign_id: Uuid,
+    pub name: String,
+    pub fk_creating_employee_id: Uuid,
+    /// The campaign is closed automatically once due
+    pub due_at: Option<DateTime<Utc>>,
+    /// Whether the accreditations left unreviewed are ended when the campaign closes
+    pub end_unreviewed_on_close: bool,
+    pub closed_at: Option<DateTime<Utc>>,
+    /// None when the campaign was closed automatically
+    pub fk_closing_employee_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, FromRow)]
+pub struct RecertificationCampaignProgress {
+    pub total: i64,
+    pub pending: i64,
+    pub kept: i64,
+    pub revoked: i64,
+    pub expired: i64,
+    pub ended: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RecertificationCampaignWithProgress {
+    #[serde(flatten)]
+    pub campaign: RecertificationCampaign,
+    pub progress: RecertificationCampaignProgress,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RecertificationCampaignItem {
+    pub pk_recertification_campaign_item_id: Uuid,
+    pub fk_recertification_campaign_id: Uuid,
+    pub fk_recipient_employee_id: Uuid,
+    /// With the recipient, identifies the reviewed accreditation
+    pub accreditation_created_at: DateTime<Utc>,
+    pub fk_employee_level_id: i32,
+    pub accreditation_start_at: DateTime<Utc>,
+    pub accreditation_end_at: Option<DateTime<Utc>>,
+    pub fk_reviewer_employee_id: Option<Uuid>,
+    /// None while pending, otherwise KEEP, REVOKE, EXPIRED or ENDED
+    pub decision: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+    pub review_comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateRecertificationCampaignRequest {
+    #[validate(length(min = 1, max = 255, message = "Name is required and cannot be longer than 255 characters"))]
+    pub name: String,
+    pub due_at: Option<DateTime<Utc>>,
+    /// Defaults to true
+    pub end_unreviewed_on_close: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct RecertificationDecisionRequest {
+    /// KEEP or REVOKE
+    pub decision: String,
+    #[validate(length(min = 1, max = 1000, message = "Review comment cannot be empty and cannot be longer than 1000 characters"))]
+    pub review_comment: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AssignRecertificationReviewerRequest {
+    pub fk_reviewer_employee_id: Uuid,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetAllRecertificationCampaignsQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    #[serde(default)]
+    pub closed: Option<bool>,
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetAllRecertificationItemsQuery {
+    #[serde(default = "default_page")]
+    pub page: u32,
+    #[serde(default = "default_limit")]
+    pub limit: u32,
+    /// PENDING for the items not reviewed yet, or a decision
+    #[serde(default)]
+    pub decision: Option<String>,
+    #[serde(default)]
+    pub fk_reviewer_employee_id: Option<Uuid>,
+    #[serde(default = "default_sort_order")]
+    pub sort_order: String,
+}
@@ -0,0 +1,27 @@
+use axum::{
+    middleware::{from_fn, from_fn_with_state}, routing::{get, post, put}, Router
+};
+use crate::{
+    middleware::{auth_middleware, with_required_permissions, MiddlewareState}, recertification::{handlers::{assign_reviewer, close_campaign, create_campaign, decide_item, get_all_campaigns, get_campaign_by_id, get_campaign_items, get_my_reviews}, services::RecertificationService}
+};
+use std::sync::Arc;
+
+pub fn protected_recertification_routes(
+    middleware_state: MiddlewareState,
+    recertification_service: Arc<RecertificationService>,
+) -> Router {
+    Router::new()
+        .route("/employees/recertifications", get(get_all_campaigns).route_layer(from_fn(with_required_permissions(vec![42]))))
+        .route("/employees/recertifications", post(create_campaign).route_layer(from_fn(with_required_permissions(vec![43]))))
+        .route("/employees/recertifications/reviews", get(get_my_reviews).route_layer(from_fn(with_required_permissions(vec![44]))))
+        .route("/employees/recertifications/{id}", get(get_campaign_by_id).route_layer(from_fn(with_required_permissions(vec![42]))))
+        .route("/employees/recertifications/{id}/items", get(get_campaign_items).route_layer(from_fn(with_required_permissions(vec![42]))))
+        .route("/employees/recertifications/{id}/items/{item_id}/decision", post(decide_item).route_layer(from_fn(with_required_permissions(vec![44]))))
+        .route("/employees/recertifications/{id}/items/{item_id}/reviewer", put(assign_reviewer).route_layer(from_fn(with_required_permissions(vec![43]))))
+        .route("/employees/recertifications/{id}/close", post(close_campaign).route_layer(from_fn(with_required_permissions(vec![45]))))
+        .layer(from_fn_with_state(
+            middleware_state,
+            auth_middleware,
+        ))
+        .with_state(recertification_service.clone())
+}
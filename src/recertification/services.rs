@@ -0,0 +1,490 @@
+use chrono::Utc;
+use sqlx::{PgExecutor, PgPool};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    auth::services::invalidate_employee_permissions, employee::{models::EntityType, services::{EmployeeService, ADMIN_LEVEL_LABEL}}, errors::app_error::AppError, history::{models::{NewActionHistory, SYSTEM_EMPLOYEE_ID}, services::record_action}, recertification::models::{AssignRecertificationReviewerRequest, CreateRecertificationCampaignRequest, GetAllRecertificationCampaignsQuery, GetAllRecertificationItemsQuery, RecertificationCampaign, RecertificationCampaignItem, RecertificationCampaignProgress, RecertificationCampaignWithProgress, RecertificationDecision, RecertificationDecisionRequest}
+};
+
+const CAMPAIGN_COLUMNS: &str = "pk_recertification_campaign_id, name, fk_creating_employee_id, due_at, end_unreviewed_on_close, closed_at, fk_closing_employee_id, created_at";
+const ITEM_COLUMNS: &str = "pk_recertification_campaign_item_id, fk_recertification_campaign_id, fk_recipient_employee_id, accreditation_created_at, fk_employee_level_id, accreditation_start_at, accreditation_end_at, fk_reviewer_employee_id, decision, decided_at, review_comment";
+
+pub struct RecertificationService {
+    pool: PgPool,
+    employee_service: EmployeeService,
+}
+
+impl RecertificationService {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            employee_service: EmployeeService::new(pool.clone()),
+            pool,
+        }
+    }
+
+    pub async fn get_all_campaigns(&self, filters: &GetAllRecertificationCampaignsQuery) -> Result<(Vec<RecertificationCampaignWithProgress>, u64), AppError> {
+        let offset = (filters.page - 1) * filters.limit;
+
+        let where_clause = "WHERE ($1::BOOL IS NULL OR $1 = (closed_at IS NOT NULL))";
+
+        let total_count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) as count FROM recertification_campaigns {}", where_clause))
+            .bind(filters.closed)
+            .fetch_one(&self.pool)
+            .await?;
+
+        // Determine sort order (default to ASC for chronological order)
+        let order_direction = match filters.sort_order.to_lowercase().as_str() {
+            "desc" => "DESC",
+            _ => "ASC",
+        };
+
+        let campaigns = sqlx::query_as::<_, RecertificationCampaign>(&format!(
+            "SELECT {} FROM recertification_campaigns {} ORDER BY created_at {} LIMIT $2 OFFSET $3",
+            CAMPAIGN_COLUMNS,
+            where_clause,
+            order_direction
+        ))
+        .bind(filters.closed)
+        .bind(filters.limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut result = Vec::with_capacity(campaigns.len());
+        for campaign in campaigns {
+            let progress = self.get_campaign_progress(&campaign.pk_recertification_campaign_id).await?;
+            result.push(RecertificationCampaignWithProgress { campaign, progress });
+        }
+
+        Ok((result, total_count as u64))
+    }
+
+    pub async fn get_campaign_by_id(&self, campaign_id: &Uuid) -> Result<RecertificationCampaign, AppError> {
+        sqlx::query_as::<_, RecertificationCampaign>(&format!("SELECT {} FROM recertification_campaigns WHERE pk_recertification_campaign_id = $1", CAMPAIGN_COLUMNS))
+            .bind(campaign_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Recertification campaign not found".to_string()))
+    }
+
+    pub async fn get_campaign_with_progress(&self, campaign_id: &Uuid) -> Result<RecertificationCampaignWithProgress, AppError> {
+        let campaign = self.get_campaign_by_id(campaign_id).await?;
+        let progress = self.get_campaign_progress(campaign_id).await?;
+        Ok(RecertificationCampaignWithProgress { campaign, progress })
+    }
+
+    async fn get_campaign_progress(&self, campaign_id: &Uuid) -> Result<RecertificationCampaignProgress, AppError> {
+        let progress = sqlx::query_as!(
+            RecertificationCampaignProgress,
+            r#"
+            SELECT
+                COUNT(*) as "total!",
+                COUNT(*) FILTER (WHERE decision IS NULL) as "pending!",
+                COUNT(*) FILTER (WHERE decision = 'KEEP') as "kept!",
+                COUNT(*) FILTER (WHERE decision = 'REVOKE') as "revoked!",
+                COUNT(*) FILTER (WHERE decision = 'EXPIRED') as "expired!",
+                COUNT(*) FILTER (WHERE decision = 'ENDED') as "ended!"
+            FROM recertification_campaign_items
+            WHERE fk_recertification_campaign_id = $1
+            "#,
+            campaign_id
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(progress)
+    }
+
+    // Open a campaign on every accreditation currently in effect. Each one is reviewed by the employee
+    // who granted it when possible, otherwise by the campaign creator; nobody reviews their own access.
+    pub async fn create_campaign(&self, create_req: &CreateRecertificationCampaignRequest, author_id: &Uuid) -> Result<RecertificationCampaignWithProgress, AppError> {
+        if create_req.due_at.is_some_and(|due_at| due_at <= Utc::now()) {
+            return Err(AppError::Validation("The campaign due date must be in the future".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let campaign = sqlx::query_as::<_, RecertificationCampaign>(&format!(
+            "INSERT INTO recertification_campaigns (name, fk_creating_employee_id, due_at, end_unreviewed_on_close) VALUES ($1, $2, $3, $4) RETURNING {}",
+            CAMPAIGN_COLUMNS
+        ))
+        .bind(&create_req.name)
+        .bind(author_id)
+        .bind(create_req.due_at)
+        .bind(create_req.end_unreviewed_on_close.unwrap_or(true))
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // break-glass elevations expire on their own and are not reviewed
+        let snapshotted = sqlx::query!(
+            r#"
+            INSERT INTO recertification_campaign_items (fk_recertification_campaign_id, fk_recipient_employee_id, accreditation_created_at, fk_employee_level_id, accreditation_start_at, accreditation_end_at, fk_reviewer_employee_id)
+            SELECT $1, eaa.fk_recipient_employee_id, eaa.created_at, eaa.fk_employee_level_id, eaa.start_at, eaa.end_at,
+                CASE
+                    WHEN authorizing.deactivated_at IS NULL AND eaa.fk_authorizing_employee_id != eaa.fk_recipient_employee_id THEN eaa.fk_authorizing_employee_id
+                    WHEN $2 != eaa.fk_recipient_employee_id THEN $2
+                END
+            FROM employee_accreditation_authorizations eaa
+            LEFT JOIN employees authorizing ON authorizing.pk_employee_id = eaa.fk_authorizing_employee_id
+            WHERE NOT eaa.is_break_glass
+                AND eaa.revoked_at IS NULL
+                AND eaa.start_at <= NOW()
+                AND (eaa.end_at IS NULL OR eaa.end_at > NOW())
+            "#,
+            campaign.pk_recertification_campaign_id,
+            author_id
+        )
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 43,
+            entity_id: None,
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "CREATE_RECERTIFICATION_CAMPAIGN",
+                "recertification_campaign_id": campaign.pk_recertification_campaign_id,
+                "name": campaign.name,
+                "due_at": campaign.due_at,
+                "end_unreviewed_on_close": campaign.end_unreviewed_on_close,
+                "accreditation_count": snapshotted,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        self.get_campaign_with_progress(&campaign.pk_recertification_campaign_id).await
+    }
+
+    // Items of a campaign, or the items assigned to a reviewer across the open campaigns
+    pub async fn get_all_items(&self, campaign_id: Option<&Uuid>, filters: &GetAllRecertificationItemsQuery) -> Result<(Vec<RecertificationCampaignItem>, u64), AppError> {
+        let offset = (filters.page - 1) * filters.limit;
+
+        let where_clause = r#"
+            WHERE ($1::UUID IS NULL OR fk_recertification_campaign_id = $1)
+            AND ($1::UUID IS NOT NULL OR fk_recertification_campaign_id IN (SELECT pk_recertification_campaign_id FROM recertification_campaigns WHERE closed_at IS NULL))
+            AND ($2::UUID IS NULL OR fk_reviewer_employee_id = $2)
+            AND ($3::VARCHAR IS NULL OR ($3 = 'PENDING' AND decision IS NULL) OR decision = $3)
+        "#;
+
+        let total_count = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) as count FROM recertification_campaign_items {}", where_clause))
+            .bind(campaign_id)
+            .bind(filters.fk_reviewer_employee_id)
+            .bind(&filters.decision)
+            .fetch_one(&self.pool)
+            .await?;
+
+        let order_direction = match filters.sort_order.to_lowercase().as_str() {
+            "desc" => "DESC",
+            _ => "ASC",
+        };
+
+        let items = sqlx::query_as::<_, RecertificationCampaignItem>(&format!(
+            "SELECT {} FROM recertification_campaign_items {} ORDER BY accreditation_start_at {} LIMIT $4 OFFSET $5",
+            ITEM_COLUMNS,
+            where_clause,
+            order_direction
+        ))
+        .bind(campaign_id)
+        .bind(filters.fk_reviewer_employee_id)
+        .bind(&filters.decision)
+        .bind(filters.limit as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok((items, total_count as u64))
+    }
+
+    pub async fn get_item_by_id(&self, campaign_id: &Uuid, item_id: &Uuid) -> Result<RecertificationCampaignItem, AppError> {
+        sqlx::query_as::<_, RecertificationCampaignItem>(&format!(
+            "SELECT {} FROM recertification_campaign_items WHERE fk_recertification_campaign_id = $1 AND pk_recertification_campaign_item_id = $2",
+            ITEM_COLUMNS
+        ))
+        .bind(campaign_id)
+        .bind(item_id)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Recertification campaign item not found".to_string()))
+    }
+
+    pub async fn assign_reviewer(&self, campaign_id: &Uuid, item_id: &Uuid, assign_req: &AssignRecertificationReviewerRequest, author_id: &Uuid) -> Result<RecertificationCampaignItem, AppError> {
+        let (_, item) = self.get_pending_item(campaign_id, item_id).await?;
+
+        if assign_req.fk_reviewer_employee_id == item.fk_recipient_employee_id {
+            return Err(AppError::Conflict("An employee cannot review their own accreditation".to_string(), "RECERTIFICATION_SELF_REVIEW".to_string()));
+        }
+        let reviewer = self.employee_service.get_employee_by_id(&assign_req.fk_reviewer_employee_id.to_string()).await?;
+        if reviewer.deactivated_at.is_some() {
+            return Err(AppError::Conflict("A deactivated employee cannot review accreditations".to_string(), "EMPLOYEE_DEACTIVATED".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let updated = sqlx::query_as::<_, RecertificationCampaignItem>(&format!(
+            "UPDATE recertification_campaign_items SET fk_reviewer_employee_id = $1 WHERE pk_recertification_campaign_item_id = $2 RETURNING {}",
+            ITEM_COLUMNS
+        ))
+        .bind(assign_req.fk_reviewer_employee_id)
+        .bind(item_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *author_id,
+            authorization_type_id: 43,
+            entity_id: Some(item.fk_recipient_employee_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "ASSIGN_RECERTIFICATION_REVIEWER",
+                "recertification_campaign_id": campaign_id,
+                "recertification_campaign_item_id": item_id,
+                "changes": {
+                    "fk_reviewer_employee_id": { "old": item.fk_reviewer_employee_id, "new": assign_req.fk_reviewer_employee_id },
+                },
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+
+    // The assigned reviewer keeps or revokes the accreditation
+    pub async fn decide(&self, campaign_id: &Uuid, item_id: &Uuid, decision_req: &RecertificationDecisionRequest, reviewer_id: &Uuid) -> Result<RecertificationCampaignItem, AppError> {
+        let decision = match decision_req.decision.parse::<RecertificationDecision>() {
+            Ok(decision @ (RecertificationDecision::KEEP | RecertificationDecision::REVOKE)) => decision,
+            _ => return Err(AppError::Validation("Decision must be KEEP or REVOKE".to_string())),
+        };
+
+        let (campaign, item) = self.get_pending_item(campaign_id, item_id).await?;
+        if item.fk_reviewer_employee_id != Some(*reviewer_id) {
+            return Err(AppError::Forbidden("Only the assigned reviewer can review this accreditation".to_string(), "RECERTIFICATION_NOT_REVIEWER".to_string()));
+        }
+
+        let accreditation = self.employee_service.get_employee_accreditation(&item.fk_recipient_employee_id, &item.accreditation_created_at).await?;
+        if accreditation.revoked_at.is_some() || accreditation.end_at.is_some_and(|end_at| end_at <= Utc::now()) {
+            return Err(AppError::Conflict("The accreditation has already ended".to_string(), "ACCREDITATION_ENDED".to_string()));
+        }
+        let mut tx = self.pool.begin().await?;
+
+        if decision == RecertificationDecision::REVOKE {
+            self.employee_service.ensure_not_last_active_admin(&mut tx, &item.fk_recipient_employee_id, &accreditation.employee_level).await?;
+        }
+
+        if decision == RecertificationDecision::REVOKE {
+            let revocation_reason = decision_req.review_comment.clone()
+                .unwrap_or_else(|| format!("Revoked during the recertification campaign \"{}\"", campaign.name));
+            sqlx::query!(
+                r#"
+                UPDATE employee_accreditation_authorizations
+                SET end_at = GREATEST(start_at, NOW()),
+                    revoked_at = NOW(),
+                    fk_revoking_employee_id = $1,
+                    revocation_reason = $2
+                WHERE fk_recipient_employee_id = $3 AND created_at = $4
+                "#,
+                reviewer_id,
+                revocation_reason,
+                item.fk_recipient_employee_id,
+                item.accreditation_created_at
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            invalidate_employee_permissions(&mut *tx, &[item.fk_recipient_employee_id]).await?;
+        }
+
+        let updated = sqlx::query_as::<_, RecertificationCampaignItem>(&format!(
+            "UPDATE recertification_campaign_items SET decision = $1, decided_at = NOW(), review_comment = $2 WHERE pk_recertification_campaign_item_id = $3 AND decision IS NULL RETURNING {}",
+            ITEM_COLUMNS
+        ))
+        .bind(decision.as_str())
+        .bind(&decision_req.review_comment)
+        .bind(item_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::Conflict("This accreditation has already been reviewed".to_string(), "RECERTIFICATION_ALREADY_REVIEWED".to_string()))?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: *reviewer_id,
+            authorization_type_id: 44,
+            entity_id: Some(item.fk_recipient_employee_id),
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "RECERTIFY_ACCREDITATION",
+                "recertification_campaign_id": campaign_id,
+                "recertification_campaign_item_id": item_id,
+                "accreditation_created_at": item.accreditation_created_at,
+                "employee_level_id": item.fk_employee_level_id,
+                "decision": decision.as_str(),
+                "review_comment": decision_req.review_comment,
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        Ok(updated)
+    }
+
+    // Close a campaign. The items whose accreditation already ended are marked as such and,
+    // when configured, the accreditations left unreviewed are ended; the last active
+    // administrator is always kept. `closer_id` is None when closed automatically, the
+    // ended accreditations are then recorded under the system employee.
+    pub async fn close_campaign(&self, campaign_id: &Uuid, closer_id: Option<&Uuid>) -> Result<RecertificationCampaignWithProgress, AppError> {
+        let actor_id = closer_id.copied().unwrap_or(SYSTEM_EMPLOYEE_ID);
+
+        let mut tx = self.pool.begin().await?;
+
+        // the lock makes a manual close and the background task wait for each other
+        let campaign = sqlx::query_as::<_, RecertificationCampaign>(&format!(
+            "SELECT {} FROM recertification_campaigns WHERE pk_recertification_campaign_id = $1 FOR UPDATE",
+            CAMPAIGN_COLUMNS
+        ))
+        .bind(campaign_id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Recertification campaign not found".to_string()))?;
+        if campaign.closed_at.is_some() {
+            return Err(AppError::Conflict("The recertification campaign is already closed".to_string(), "RECERTIFICATION_CAMPAIGN_CLOSED".to_string()));
+        }
+
+        let pending_items = sqlx::query_as::<_, RecertificationCampaignItem>(&format!(
+            "SELECT {} FROM recertification_campaign_items WHERE fk_recertification_campaign_id = $1 AND decision IS NULL FOR UPDATE",
+            ITEM_COLUMNS
+        ))
+        .bind(campaign_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        // the last administrator check sees the accreditations ended by the previous items
+        for item in pending_items {
+            let accreditation = self.employee_service.get_employee_accreditation(&item.fk_recipient_employee_id, &item.accreditation_created_at).await?;
+            let already_ended = accreditation.revoked_at.is_some() || accreditation.end_at.is_some_and(|end_at| end_at <= Utc::now());
+
+            if already_ended {
+                set_closing_decision(&mut *tx, &item, RecertificationDecision::ENDED).await?;
+                continue;
+            }
+            if !campaign.end_unreviewed_on_close {
+                continue;
+            }
+            if accreditation.employee_level.level_label == ADMIN_LEVEL_LABEL && self.employee_service.is_last_active_admin(&mut tx, &item.fk_recipient_employee_id).await? {
+                warn!("Recertification campaign {}: the accreditation of the last active administrator {} is kept", campaign_id, item.fk_recipient_employee_id);
+                continue;
+            }
+
+            let revocation_reason = format!("Not recertified during the campaign \"{}\"", campaign.name);
+            sqlx::query!(
+                r#"
+                UPDATE employee_accreditation_authorizations
+                SET end_at = GREATEST(start_at, NOW()),
+                    revoked_at = NOW(),
+                    fk_revoking_employee_id = $1,
+                    revocation_reason = $2
+                WHERE fk_recipient_employee_id = $3 AND created_at = $4
+                "#,
+                actor_id,
+                revocation_reason,
+                item.fk_recipient_employee_id,
+                item.accreditation_created_at
+            )
+            .execute(&mut *tx)
+            .await?;
+
+            set_closing_decision(&mut *tx, &item, RecertificationDecision::EXPIRED).await?;
+
+            record_action(&mut *tx, &NewActionHistory {
+                employee_id: actor_id,
+                authorization_type_id: 45,
+                entity_id: Some(item.fk_recipient_employee_id),
+                entity_type: EntityType::EMPLOYEE,
+                description: serde_json::json!({
+                    "action": "EXPIRE_ACCREDITATION",
+                    "recertification_campaign_id": campaign_id,
+                    "recertification_campaign_item_id": item.pk_recertification_campaign_item_id,
+                    "accreditation_created_at": item.accreditation_created_at,
+                    "employee_level_id": item.fk_employee_level_id,
+                    "previous_end_at": accreditation.end_at,
+                    "revocation_reason": revocation_reason,
+                }),
+            }).await?;
+
+            invalidate_employee_permissions(&mut *tx, &[item.fk_recipient_employee_id]).await?;
+        }
+
+        sqlx::query!(
+            "UPDATE recertification_campaigns SET closed_at = NOW(), fk_closing_employee_id = $1 WHERE pk_recertification_campaign_id = $2 AND closed_at IS NULL",
+            closer_id,
+            campaign_id
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        record_action(&mut *tx, &NewActionHistory {
+            employee_id: actor_id,
+            authorization_type_id: 45,
+            entity_id: None,
+            entity_type: EntityType::EMPLOYEE,
+            description: serde_json::json!({
+                "action": "CLOSE_RECERTIFICATION_CAMPAIGN",
+                "recertification_campaign_id": campaign_id,
+                "automatic": closer_id.is_none(),
+            }),
+        }).await?;
+
+        tx.commit().await?;
+
+        self.get_campaign_with_progress(campaign_id).await
+    }
+
+    // Close the campaigns whose due date has passed, a campaign failing to close does not
+    // prevent closing the next ones
+    pub async fn close_due_campaigns(&self) -> Result<(), AppError> {
+        let due_campaign_ids = sqlx::query_scalar!(
+            "SELECT pk_recertification_campaign_id FROM recertification_campaigns WHERE closed_at IS NULL AND due_at <= NOW()"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        for campaign_id in due_campaign_ids {
+            match self.close_campaign(&campaign_id, None).await {
+                Ok(closed) => info!("Recertification campaign {} closed automatically, {} accreditation(s) expired", campaign_id, closed.progress.expired),
+                // closed manually in the meantime
+                Err(AppError::Conflict(_, code)) if code == "RECERTIFICATION_CAMPAIGN_CLOSED" => {}
+                Err(e) => error!("Failed to close recertification campaign {}: {}", campaign_id, e),
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn get_pending_item(&self, campaign_id: &Uuid, item_id: &Uuid) -> Result<(RecertificationCampaign, RecertificationCampaignItem), AppError> {
+        let campaign = self.get_campaign_by_id(campaign_id).await?;
+        if campaign.closed_at.is_some() {
+            return Err(AppError::Conflict("The recertification campaign is closed".to_string(), "RECERTIFICATION_CAMPAIGN_CLOSED".to_string()));
+        }
+
+        let item = self.get_item_by_id(campaign_id, item_id).await?;
+        if item.decision.is_some() {
+            return Err(AppError::Conflict("This accreditation has already been reviewed".to_string(), "RECERTIFICATION_ALREADY_REVIEWED".to_string()));
+        }
+
+        Ok((campaign, item))
+    }
+}
+
+async fn set_closing_decision<'e, E: PgExecutor<'e>>(executor: E, item: &RecertificationCampaignItem, decision: RecertificationDecision) -> Result<(), AppError> {
+    sqlx::query!(
+        "UPDATE recertification_campaign_items SET decision = $1, decided_at = NOW() WHERE pk_recertification_campaign_item_id = $2",
+        decision.as_str(),
+        item.pk_recertification_campaign_item_id
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use std::{io::ErrorKind, path::{Component, Path, PathBuf}};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use tracing::error;
+
+use crate::{errors::app_error::AppError, storage::DocumentStorage};
+
+/// Stores the files in a directory of the local filesystem, the keys being relative paths
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    // Refuse the keys that would escape the root directory
+    fn path(&self, key: &str) -> Result<PathBuf, AppError> {
+        let relative = Path::new(key);
+        if !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+            return Err(AppError::Internal(format!("Invalid storage key: {}", key)));
+        }
+        Ok(self.root.join(relative))
+    }
+}
+
+fn storage_error(key: &str, e: std::io::Error) -> AppError {
+    error!("Local storage error on {}: {}", key, e);
+    AppError::Internal("Document storage error".to_string())
+}
+
+#[async_trait]
+impl DocumentStorage for LocalStorage {
+    async fn put(&self, key: &str, content: Bytes, _content_type: &str) -> Result<(), AppError> {
+        let path = self.path(key)?;
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|e| storage_error(key, e))?;
+        }
+        tokio::fs::write(&path, &content).await.map_err(|e| storage_error(key, e))
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, AppError> {
+        match tokio::fs::read(self.path(key)?).await {
+            Ok(content) => Ok(Bytes::from(content)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Err(AppError::NotFound("Document content not found".to_string())),
+            Err(e) => Err(storage_error(key, e)),
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        match tokio::fs::remove_file(self.path(key)?).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(storage_error(key, e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_local_storage_round_trip() {
+        let root = std::env::temp_dir().join(format!("plannify-storage-{}", uuid::Uuid::new_v4()));
+        let storage = LocalStorage::new(&root);
+
+        storage.put("drivers/a/document", Bytes::from_static(b"%PDF-1.7"), "application/pdf").await.unwrap();
+        assert_eq!(storage.get("drivers/a/document").await.unwrap(), Bytes::from_static(b"%PDF-1.7"));
+
+        storage.delete("drivers/a/document").await.unwrap();
+        assert!(matches!(storage.get("drivers/a/document").await, Err(AppError::NotFound(_))));
+        // deleting twice is not an error
+        storage.delete("drivers/a/document").await.unwrap();
+
+        assert!(storage.get("../outside").await.is_err());
+
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+}
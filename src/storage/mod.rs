@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::{errors::app_error::AppError, storage::{local::LocalStorage, s3::S3Storage}};
+
+pub mod local;
+pub mod s3;
+
+/// Backend keeping binary files, such as the workday documents, under a key
+#[async_trait]
+pub trait DocumentStorage: Send + Sync {
+    async fn put(&self, key: &str, content: Bytes, content_type: &str) -> Result<(), AppError>;
+
+    /// NotFound when nothing is stored under the key
+    async fn get(&self, key: &str) -> Result<Bytes, AppError>;
+
+    /// Deleting a missing key is not an error
+    async fn delete(&self, key: &str) -> Result<(), AppError>;
+}
+
+/// Build the backend selected by `DOCUMENT_STORAGE_BACKEND`: `local` (default) or `s3`
+pub fn storage_from_env() -> Arc<dyn DocumentStorage> {
+    let backend = std::env::var("DOCUMENT_STORAGE_BACKEND").unwrap_or_else(|_| "local".to_string());
+    match backend.as_str() {
+        "local" => {
+            let root = std::env::var("DOCUMENT_STORAGE_PATH").unwrap_or_else(|_| "storage".to_string());
+            Arc::new(LocalStorage::new(root))
+        },
+        "s3" => {
+            let bucket = std::env::var("DOCUMENT_STORAGE_S3_BUCKET")
+                .expect("DOCUMENT_STORAGE_S3_BUCKET must be defined");
+            let access_key_id = std::env::var("DOCUMENT_STORAGE_S3_ACCESS_KEY_ID")
+                .expect("DOCUMENT_STORAGE_S3_ACCESS_KEY_ID must be defined");
+            let secret_access_key = std::env::var("DOCUMENT_STORAGE_S3_SECRET_ACCESS_KEY")
+                .expect("DOCUMENT_STORAGE_S3_SECRET_ACCESS_KEY must be defined");
+            let region = std::env::var("DOCUMENT_STORAGE_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string());
+            // e.g. http://localhost:9000 for a local MinIO, AWS is used when undefined
+            let endpoint = std::env::var("DOCUMENT_STORAGE_S3_ENDPOINT").ok();
+            Arc::new(S3Storage::new(bucket, &access_key_id, &secret_access_key, region, endpoint))
+        },
+        _ => panic!("DOCUMENT_STORAGE_BACKEND must be local or s3"),
+    }
+}
@@ -0,0 +1,107 @@
+use async_trait::async_trait;
+use aws_sdk_s3::{config::{BehaviorVersion, Credentials, Region}, primitives::ByteStream, Client};
+use bytes::Bytes;
+use tracing::error;
+
+use crate::{errors::app_error::AppError, storage::DocumentStorage};
+
+/// Stores the files in a bucket of an S3-compatible service (AWS, MinIO, ...)
+pub struct S3Storage {
+    client: Client,
+    bucket: String,
+}
+
+impl S3Storage {
+    pub fn new(bucket: String, access_key_id: &str, secret_access_key: &str, region: String, endpoint: Option<String>) -> Self {
+        let credentials = Credentials::new(access_key_id, secret_access_key, None, None, "plannify");
+        let mut config = aws_sdk_s3::Config::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new(region))
+            .credentials_provider(credentials);
+        if let Some(endpoint) = endpoint {
+            // self-hosted services such as MinIO do not resolve the bucket as a subdomain
+            config = config.endpoint_url(endpoint).force_path_style(true);
+        }
+
+        Self {
+            client: Client::from_conf(config.build()),
+            bucket,
+        }
+    }
+}
+
+fn storage_error(key: &str, e: impl std::fmt::Debug) -> AppError {
+    error!("S3 storage error on {}: {:?}", key, e);
+    AppError::Internal("Document storage error".to_string())
+}
+
+#[async_trait]
+impl DocumentStorage for S3Storage {
+    async fn put(&self, key: &str, content: Bytes, content_type: &str) -> Result<(), AppError> {
+        self.client.put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .content_type(content_type)
+            .body(ByteStream::from(content))
+            .send()
+            .await
+            .map_err(|e| storage_error(key, e))?;
+
+        Ok(())
+    }
+
+    async fn get(&self, key: &str) -> Result<Bytes, AppError> {
+        let output = match self.client.get_object().bucket(&self.bucket).key(key).send().await {
+            Ok(output) => output,
+            Err(e) if e.as_service_error().is_some_and(|e| e.is_no_such_key()) => {
+                return Err(AppError::NotFound("Document content not found".to_string()));
+            },
+            Err(e) => return Err(storage_error(key, e)),
+        };
+
+        let content = output.body.collect().await.map_err(|e| storage_error(key, e))?;
+        Ok(content.into_bytes())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), AppError> {
+        self.client.delete_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| storage_error(key, e))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_or(name: &str, default: &str) -> String {
+        std::env::var(name).unwrap_or_else(|_| default.to_string())
+    }
+
+    // run with `docker compose up -d minio minio-init` then `cargo test -- --ignored`
+    #[tokio::test]
+    #[ignore = "needs the MinIO service of docker-compose.yml"]
+    async fn test_s3_storage_round_trip() {
+        let storage = S3Storage::new(
+            env_or("DOCUMENT_STORAGE_S3_BUCKET", "plannify-documents"),
+            &env_or("DOCUMENT_STORAGE_S3_ACCESS_KEY_ID", "plannify_minio"),
+            &env_or("DOCUMENT_STORAGE_S3_SECRET_ACCESS_KEY", "plannify_minio_password"),
+            env_or("DOCUMENT_STORAGE_S3_REGION", "us-east-1"),
+            Some(env_or("DOCUMENT_STORAGE_S3_ENDPOINT", "http://localhost:9000")),
+        );
+        let key = format!("tests/{}", uuid::Uuid::new_v4());
+
+        storage.put(&key, Bytes::from_static(b"%PDF-1.7"), "application/pdf").await.unwrap();
+        assert_eq!(storage.get(&key).await.unwrap(), Bytes::from_static(b"%PDF-1.7"));
+
+        storage.delete(&key).await.unwrap();
+        assert!(matches!(storage.get(&key).await, Err(AppError::NotFound(_))));
+        // deleting twice is not an error
+        storage.delete(&key).await.unwrap();
+    }
+}